@@ -1,9 +1,14 @@
-use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, RequestBuilder, Response, Url};
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
+use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::sync::Mutex;
 use chrono::{DateTime, Utc, Duration};
+use crate::exit_code::{CommandError, ExitCode};
+use crate::store::{KvStore, VersionConflict};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginRequest {
@@ -54,53 +59,121 @@ pub struct Project {
     pub name: String,
     pub slug: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_touched: Option<i64>, // Metadata marker, not a time entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>, // Hourly rate for billing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_hours: Option<f64>, // Hours planned for the project, `time total`/`start` warn from 80% on
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool, // Hidden from lists and menus, entries are kept
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TimeEntry {
     pub timestamp: i64,
     #[serde(rename = "type")]
-    pub entry_type: String, // "start" or "end"
+    pub entry_type: String, // "start", "end", "pause", "resume" or "note"
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>, // Categories like "dev" or "meeting", set on start/stop
 }
 
 pub struct ApiClient {
     client: Client,
-    token: Option<String>,
+    // Behind a mutex so an expired token can be replaced while a command runs
+    token: Mutex<Option<String>>,
     login_url: String,
     data_base_url: String,
     username: String,
     password: String,
     token_cache_file: String,
+    auth_header_name: String,
+    auth_scheme: String,
+    retries: u32,
+    retry_base_ms: u64,
+    timeout_secs: u64,
+    // Keys per listing page, sent as `?limit=`; None leaves it to the server
+    page_size: Option<u32>,
+    // -v traces every request and its status to stderr, -vv adds headers and bodies
+    verbose: u8,
+    // Send If-Match on updates; off for backends that reject conditional requests
+    optimistic_locking: bool,
 }
 
 impl ApiClient {
-    pub fn new() -> Result<Self> {
+    /// `workspace` replaces API_PROJECT, so one backend can hold several separate sets of projects
+    pub fn new(verbose: u8, workspace: Option<&str>) -> Result<Self> {
         // Load environment variables from .env file
         dotenv::dotenv().ok(); // Don't fail if .env doesn't exist
         
         let api_domain = env::var("API_DOMAIN")
             .unwrap_or_else(|_| "https://kv.srv.signalwerk.ch".to_string());
-        let api_project = env::var("API_PROJECT")
-            .unwrap_or_else(|_| "timetracker".to_string());
+        let api_project = match workspace {
+            Some(workspace) => workspace.to_string(),
+            None => env::var("API_PROJECT").unwrap_or_else(|_| "timetracker".to_string()),
+        };
         let username = env::var("API_USERNAME")
             .map_err(|_| anyhow!("API_USERNAME not found in environment"))?;
         let password = env::var("API_PASSWORD")
             .map_err(|_| anyhow!("API_PASSWORD not found in environment"))?;
         let token_cache_file = env::var("TOKEN_CACHE_FILE")
             .unwrap_or_else(|_| ".token_cache.json".to_string());
+        let auth_header_name = env::var("API_AUTH_HEADER")
+            .unwrap_or_else(|_| "Authorization".to_string());
+        let auth_scheme = env::var("API_AUTH_SCHEME")
+            .unwrap_or_else(|_| "Bearer".to_string());
+        let retries = env::var("API_RETRIES").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(3);
+        let retry_base_ms = env::var("API_RETRY_BASE_MS").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(200);
+        let timeout_secs = env::var("API_TIMEOUT_SECS").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(30);
+        let page_size = env::var("API_PAGE_SIZE").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|size| *size > 0);
+        let optimistic_locking = env::var("API_OPTIMISTIC_LOCKING")
+            .map(|value| !matches!(value.trim().to_lowercase().as_str(), "false" | "0" | "no"))
+            .unwrap_or(true);
 
         let login_url = format!("{}/login", api_domain);
         let data_base_url = format!("{}/{}", api_domain, api_project);
 
+        // Connecting gets at most 10s of the overall budget, an unreachable host fails fast
+        let mut builder = Client::builder()
+            .timeout(std::time::Duration::from_secs(timeout_secs))
+            .connect_timeout(std::time::Duration::from_secs(timeout_secs.min(10)))
+            .default_headers(extra_headers()?);
+        if let Some(proxy) = env::var("HTTPS_PROXY").or_else(|_| env::var("https_proxy")).ok()
+            .filter(|proxy| !proxy.trim().is_empty())
+        {
+            builder = builder.proxy(reqwest::Proxy::https(proxy.trim())
+                .map_err(|e| anyhow!("Invalid HTTPS_PROXY '{}': {}", proxy.trim(), e))?);
+        }
+        let client = builder.build()?;
+
         Ok(Self {
-            client: Client::new(),
-            token: None,
+            client,
+            token: Mutex::new(None),
             login_url,
             data_base_url,
             username,
             password,
             token_cache_file,
+            auth_header_name,
+            auth_scheme,
+            retries,
+            retry_base_ms,
+            timeout_secs,
+            page_size,
+            verbose,
+            optimistic_locking,
         })
     }
 
@@ -117,8 +190,11 @@ impl ApiClient {
     }
 
     fn save_token_to_cache(&self, token: &str) -> Result<()> {
-        // Set token to expire in 23 hours (assuming 24h validity, with 1h buffer)
-        let expires_at = Utc::now() + Duration::hours(23);
+        // Use the token's own expiry (minus a minute of slack); if it can't be read,
+        // assume 24h validity with a 1h buffer
+        let expires_at = jwt_expiry(token)
+            .map(|exp| exp - Duration::minutes(1))
+            .unwrap_or_else(|| Utc::now() + Duration::hours(23));
         let cache = TokenCache {
             token: token.to_string(),
             expires_at,
@@ -132,10 +208,9 @@ impl ApiClient {
     async fn is_token_valid(&self, token: &str) -> bool {
         // Test the token by making a simple API call
         let response = self
-            .client
-            .get(&format!("{}/data", self.data_base_url))
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .send(self.client
+                .get(format!("{}/data", self.data_base_url))
+                .header(self.auth_header_name.as_str(), self.auth_value(token)))
             .await;
         
         match response {
@@ -144,85 +219,195 @@ impl ApiClient {
         }
     }
 
-    pub async fn authenticate(&mut self) -> Result<()> {
+    pub async fn authenticate(&self) -> Result<()> {
         // First, try to load cached token
         if let Some(cached_token) = self.load_cached_token() {
             // Verify the cached token is still valid
             if self.is_token_valid(&cached_token).await {
-                self.token = Some(cached_token);
+                self.set_token(cached_token);
                 return Ok(());
             }
         }
 
         // If no valid cached token, perform fresh authentication
+        self.login().await
+    }
+
+    async fn login(&self) -> Result<()> {
         let login_request = LoginRequest {
             username: self.username.clone(),
             password: self.password.clone(),
         };
 
         let response = self
-            .client
-            .post(&self.login_url)
-            .json(&login_request)
-            .send()
-            .await?;
+            .send(self.client.post(&self.login_url).json(&login_request))
+            .await
+            .map_err(|e| self.request_error(e))?;
 
         if response.status().is_success() {
-            let login_response: LoginResponse = response.json().await?;
+            let login_response: LoginResponse = serde_json::from_str(&self.read_body(response).await?)?;
             
             // Save token to cache
             self.save_token_to_cache(&login_response.token)?;
             
-            self.token = Some(login_response.token);
+            self.set_token(login_response.token);
             Ok(())
         } else {
-            Err(anyhow!("Authentication failed: {}", response.status()))
+            Err(self.unexpected("Authentication failed", response).await)
         }
     }
 
-    async fn get_auth_header(&self) -> Result<String> {
-        match &self.token {
-            Some(token) => Ok(format!("Bearer {}", token)),
-            None => Err(anyhow!("Not authenticated")),
+    fn auth_value(&self, token: &str) -> String {
+        // An empty scheme sends the bare token, e.g. "X-Auth-Token: <token>"
+        if self.auth_scheme.is_empty() {
+            token.to_string()
+        } else {
+            format!("{} {}", self.auth_scheme, token)
         }
     }
 
-    pub async fn get_key(&self, key: &str) -> Result<serde_json::Value> {
-        let auth_header = self.get_auth_header().await?;
+    fn set_token(&self, token: String) {
+        *self.token.lock().unwrap_or_else(|e| e.into_inner()) = Some(token);
+    }
+
+    /// Send an authenticated request, retrying connection errors and 5xx responses with
+    /// exponential backoff. A 401 triggers one fresh login and a replay with the new token;
+    /// other 4xx responses are returned right away, they won't get better by retrying.
+    async fn send_with_retry(&self, build: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut attempt = 0;
+        let mut reauthenticated = false;
+        loop {
+            // Read the header on every attempt so a replay after login uses the new token
+            let auth_header = self.get_auth_header().await?;
+            let result = self.send(build().header(self.auth_header_name.as_str(), auth_header)).await;
+            
+            if let Ok(response) = &result {
+                if response.status().as_u16() == 401 && !reauthenticated {
+                    // The token expired since it was checked, log in again and replay once
+                    reauthenticated = true;
+                    self.login().await?;
+                    continue;
+                }
+            }
+            
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            if !retryable || attempt >= self.retries {
+                return result.map_err(|e| self.request_error(e));
+            }
+            
+            let delay = self.retry_base_ms.saturating_mul(1 << attempt.min(16));
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a request; -v traces method, URL and status to stderr, -vv also headers and the request body
+    async fn send(&self, builder: RequestBuilder) -> reqwest::Result<Response> {
+        if self.verbose == 0 {
+            return builder.send().await;
+        }
+        let request = builder.build()?;
+        eprintln!("> {} {}", request.method(), request.url());
+        if self.verbose >= 2 {
+            for (name, value) in request.headers() {
+                eprintln!(">   {}: {}", name, self.redact_header(name, value));
+            }
+            if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+                eprintln!(">   {}", redact_body(&String::from_utf8_lossy(body)));
+            }
+        }
         
+        let started = std::time::Instant::now();
+        let result = self.client.execute(request).await;
+        match &result {
+            Ok(response) => eprintln!("< {} ({} ms)", response.status(), started.elapsed().as_millis()),
+            Err(e) => eprintln!("< {} ({} ms)", e, started.elapsed().as_millis()),
+        }
+        result
+    }
+
+    /// The token never ends up in the trace
+    fn redact_header<'a>(&self, name: &HeaderName, value: &'a HeaderValue) -> &'a str {
+        if name == reqwest::header::AUTHORIZATION || name.as_str().eq_ignore_ascii_case(&self.auth_header_name) {
+            "<redacted>"
+        } else {
+            value.to_str().unwrap_or("<binary>")
+        }
+    }
+
+    /// Response body as text, traced with -vv
+    async fn read_body(&self, response: Response) -> Result<String> {
+        let body = response.text().await?;
+        if self.verbose >= 2 {
+            eprintln!("<   {}", redact_body(&body));
+        }
+        Ok(body)
+    }
+
+    /// Error for an unsuccessful response; with -vv its body is traced first, it often says why
+    async fn unexpected(&self, what: &str, response: Response) -> anyhow::Error {
+        let status = response.status();
+        if self.verbose >= 2 {
+            let _ = self.read_body(response).await;
+        }
+        // A rejected token ends the run with its own exit code, see exit_code
+        if status.as_u16() == 401 {
+            CommandError::new(ExitCode::Auth, format!("{}: {}", what, status)).into()
+        } else {
+            anyhow!("{}: {}", what, status)
+        }
+    }
+
+    /// Name timeouts plainly, reqwest's own message doesn't say how long it waited
+    fn request_error(&self, error: reqwest::Error) -> anyhow::Error {
+        if error.is_timeout() {
+            let message = format!("request timed out after {}s (see API_TIMEOUT_SECS)", self.timeout_secs);
+            anyhow::Error::new(error).context(message)
+        } else {
+            error.into()
+        }
+    }
+
+    async fn get_auth_header(&self) -> Result<String> {
+        match self.token.lock().unwrap_or_else(|e| e.into_inner()).as_deref() {
+            Some(token) => Ok(self.auth_value(token)),
+            None => Err(CommandError::new(ExitCode::Auth, "Not authenticated").into()),
+        }
+    }
+}
+
+impl KvStore for ApiClient {
+    async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>> {
+        let (value, _) = self.get_key_versioned(key).await?;
+        Ok(value)
+    }
+
+    async fn get_key_versioned(&self, key: &str) -> Result<(Option<serde_json::Value>, Option<String>)> {
         let encoded_key = urlencoding::encode(key);
         let response = self
-            .client
-            .get(&format!("{}/data/{}", self.data_base_url, encoded_key))
-            .header("Authorization", auth_header)
-            .send()
+            .send_with_retry(|| {
+                self.client
+                    .get(format!("{}/data/{}", self.data_base_url, encoded_key))
+            })
             .await?;
 
         if response.status().is_success() {
-            let kv_response: KeyValueResponse = response.json().await?;
-            
-            // The API returns values as JSON strings, so we need to parse them
-            match &kv_response.data.value {
-                serde_json::Value::String(s) => {
-                    // Try to parse the string as JSON
-                    match serde_json::from_str(s) {
-                        Ok(parsed) => Ok(parsed),
-                        Err(_) => Ok(kv_response.data.value) // Return as-is if not valid JSON
-                    }
-                }
-                _ => Ok(kv_response.data.value)
-            }
+            let etag = response.headers().get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string());
+            let kv_response: KeyValueResponse = serde_json::from_str(&self.read_body(response).await?)?;
+            Ok((Some(kv_response.data.value), etag))
         } else if response.status().as_u16() == 404 {
-            // Key doesn't exist, return empty array for lists
-            Ok(serde_json::json!([]))
+            Ok((None, None))
         } else {
-            Err(anyhow!("Failed to get key: {}", response.status()))
+            Err(self.unexpected("Failed to get key", response).await)
         }
     }
 
-    pub async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
-        let auth_header = self.get_auth_header().await?;
-        
+    async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
         // Serialize the value to a JSON string since the API expects string values
         let value_string = serde_json::to_string(&value)?;
         let request = KeyValueRequest {
@@ -231,23 +416,25 @@ impl ApiClient {
         };
 
         let response = self
-            .client
-            .post(&format!("{}/data", self.data_base_url))
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
+            .send_with_retry(|| {
+                self.client
+                    .post(format!("{}/data", self.data_base_url))
+                    .json(&request)
+            })
             .await?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(anyhow!("Failed to set key: {}", response.status()))
+            Err(self.unexpected("Failed to set key", response).await)
         }
     }
 
-    pub async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
-        let auth_header = self.get_auth_header().await?;
-        
+    async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        self.update_key_if_match(key, value, None).await
+    }
+
+    async fn update_key_if_match(&self, key: &str, value: serde_json::Value, version: Option<&str>) -> Result<()> {
         // Serialize the value to a JSON string since the API expects string values
         let value_string = serde_json::to_string(&value)?;
         let request = UpdateRequest { 
@@ -256,223 +443,366 @@ impl ApiClient {
 
         let encoded_key = urlencoding::encode(key);
         let response = self
-            .client
-            .put(&format!("{}/data/{}", self.data_base_url, encoded_key))
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
+            .send_with_retry(|| {
+                let request_builder = self.client
+                    .put(format!("{}/data/{}", self.data_base_url, encoded_key))
+                    .json(&request);
+                match version.filter(|_| self.optimistic_locking) {
+                    Some(version) => request_builder.header(reqwest::header::IF_MATCH, version),
+                    None => request_builder,
+                }
+            })
             .await?;
 
         if response.status().is_success() {
             Ok(())
+        } else if matches!(response.status().as_u16(), 409 | 412) {
+            Err(VersionConflict.into())
         } else {
-            Err(anyhow!("Failed to update key: {}", response.status()))
+            Err(self.unexpected("Failed to update key", response).await)
         }
     }
 
-    pub async fn get_projects(&self) -> Result<Vec<Project>> {
-        let projects_value = self.get_key("projects").await?;
-        let projects: Vec<Project> = serde_json::from_value(projects_value)?;
-        Ok(projects)
-    }
-
-    pub async fn get_project(&self, slug: &str) -> Result<Project> {
-        let projects = self.get_projects().await?;
-        projects
-            .into_iter()
-            .find(|p| p.slug == slug)
-            .ok_or_else(|| anyhow!("Project with slug '{}' not found", slug))
-    }
-
-    pub async fn add_project(&self, project: Project) -> Result<()> {
-        let mut projects = self.get_projects().await.unwrap_or_default();
-        
-        // Check if project already exists
-        if projects.iter().any(|p| p.slug == project.slug) {
-            return Err(anyhow!("Project with slug '{}' already exists", project.slug));
-        }
-        
-        projects.push(project);
-        let is_first_project = projects.len() == 1;
-        let value = serde_json::to_value(projects)?;
-        
-        // Use set_key for first time, or update_key if projects already exist
-        if is_first_project {
-            self.set_key("projects", value).await
-        } else {
-            self.update_key("projects", value).await
+    /// Follows pagination until the last page: a `Link: <...>; rel="next"` header, or a
+    /// `next`/`next_cursor` field in the response (a URL, or a cursor sent as `?cursor=`)
+    async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
+        let mut first_page = Url::parse(&format!("{}/data", self.data_base_url))?;
+        if let Some(page_size) = self.page_size {
+            first_page.query_pairs_mut().append_pair("limit", &page_size.to_string());
         }
-    }
-
-    pub async fn update_project(&self, old_slug: &str, updated_project: Project) -> Result<()> {
-        let mut projects = self.get_projects().await.unwrap_or_default();
         
-        // Find the project to update
-        let project_index = projects.iter().position(|p| p.slug == old_slug)
-            .ok_or_else(|| anyhow!("Project with slug '{}' not found", old_slug))?;
-        
-        // If slug is changing, check if new slug already exists (but ignore the current project)
-        if old_slug != updated_project.slug {
-            if projects.iter().enumerate().any(|(i, p)| i != project_index && p.slug == updated_project.slug) {
-                return Err(anyhow!("Project with slug '{}' already exists", updated_project.slug));
+        let mut keys = Vec::new();
+        let mut fetched = HashSet::new();
+        let mut url = first_page.clone();
+        loop {
+            fetched.insert(url.to_string());
+            let response = self
+                .send_with_retry(|| self.client.get(url.clone()))
+                .await?;
+            if !response.status().is_success() {
+                return Err(self.unexpected("Failed to get all keys", response).await);
             }
             
-            // If slug is changing, we need to move the time entries to the new key
-            let old_time_key = format!("projects/{}", old_slug);
-            let new_time_key = format!("projects/{}", updated_project.slug);
+            let link = next_link(response.headers());
+            let body: serde_json::Value = serde_json::from_str(&self.read_body(response).await?)?;
+            keys.extend(parse_key_page(&body)?);
             
-            // Get existing time entries for the old slug
-            if let Ok(time_entries) = self.get_time_entries(old_slug).await {
-                if !time_entries.is_empty() {
-                    // Save time entries under new slug
-                    let value = serde_json::to_value(time_entries)?;
-                    self.set_key(&new_time_key, value).await?;
-                    
-                    // Delete old time entries
-                    if let Err(e) = self.delete_key(&old_time_key).await {
-                        // Only fail if it's not a 404 (key doesn't exist)
-                        if !e.to_string().contains("404") {
-                            return Err(anyhow!("Failed to delete old time entries: {}", e));
-                        }
-                    }
+            let next = match (link, next_cursor(&body)) {
+                (Some(link), _) => Some(url.join(&link)?),
+                (None, Some(cursor)) if cursor.contains("://") || cursor.starts_with('/') => Some(url.join(&cursor)?),
+                (None, Some(cursor)) => {
+                    let mut next = first_page.clone();
+                    next.query_pairs_mut().append_pair("cursor", &cursor);
+                    Some(next)
                 }
+                (None, None) => None,
+            };
+            // A server handing out the same page again would otherwise loop forever
+            match next {
+                Some(next) if !fetched.contains(next.as_str()) => url = next,
+                _ => break,
             }
         }
-        
-        // Update the project in the projects list
-        projects[project_index] = updated_project;
-        let value = serde_json::to_value(projects)?;
-        self.update_key("projects", value).await
-    }
-
-    pub async fn get_time_entries(&self, project_slug: &str) -> Result<Vec<TimeEntry>> {
-        let key = format!("projects/{}", project_slug);
-        let value = self.get_key(&key).await?;
-        let entries: Vec<TimeEntry> = serde_json::from_value(value)?;
-        Ok(entries)
-    }
-
-    pub async fn add_time_entry(&self, project_slug: &str, entry: TimeEntry) -> Result<()> {
-        let key = format!("projects/{}", project_slug);
-        let mut entries = self.get_time_entries(project_slug).await.unwrap_or_default();
-        entries.push(entry);
-        let is_first_entry = entries.len() == 1;
-        let value = serde_json::to_value(entries)?;
-        
-        // Use set_key for first time, or update_key if entries already exist
-        if is_first_entry {
-            self.set_key(&key, value).await
-        } else {
-            self.update_key(&key, value).await
-        }
+        Ok(keys)
     }
 
-    pub async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
-        let auth_header = self.get_auth_header().await?;
+    async fn delete_key(&self, key: &str) -> Result<()> {
+        let encoded_key = urlencoding::encode(key);
         
         let response = self
-            .client
-            .get(&format!("{}/data", self.data_base_url))
-            .header("Authorization", auth_header)
-            .send()
+            .send_with_retry(|| {
+                self.client
+                    .delete(format!("{}/data/{}", self.data_base_url, encoded_key))
+            })
             .await?;
 
         if response.status().is_success() {
-            let list_response: KeyValueListResponse = response.json().await?;
-            Ok(list_response.data)
+            Ok(())
         } else {
-            Err(anyhow!("Failed to get all keys: {}", response.status()))
+            Err(self.unexpected("Failed to delete key", response).await)
         }
     }
+}
 
-    pub async fn delete_key(&self, key: &str) -> Result<()> {
-        let auth_header = self.get_auth_header().await?;
-        let encoded_key = urlencoding::encode(key);
-        
-        let response = self
-            .client
-            .delete(&format!("{}/data/{}", self.data_base_url, encoded_key))
-            .header("Authorization", auth_header)
-            .send()
-            .await?;
+/// Headers from API_EXTRA_HEADER, one "Name: Value" per line, sent with every request
+fn extra_headers() -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    let Ok(value) = env::var("API_EXTRA_HEADER") else {
+        return Ok(headers);
+    };
+    for line in value.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (name, value) = line.split_once(':')
+            .ok_or_else(|| anyhow!("Invalid API_EXTRA_HEADER '{}', expected 'Name: Value'", line))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| anyhow!("Invalid header name in API_EXTRA_HEADER: '{}'", name.trim()))?;
+        let value = HeaderValue::from_str(value.trim())
+            .map_err(|_| anyhow!("Invalid header value in API_EXTRA_HEADER for '{}'", name))?;
+        headers.append(name, value);
+    }
+    Ok(headers)
+}
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(anyhow!("Failed to delete key: {}", response.status()))
+/// A traced body with the login password and token blanked out
+fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            for field in ["password", "token"] {
+                if let Some(value) = object.get_mut(field) {
+                    *value = serde_json::Value::String("<redacted>".to_string());
+                }
+            }
+            serde_json::Value::Object(object).to_string()
         }
+        _ => body.to_string(),
     }
+}
 
-    pub async fn delete_project(&self, project_slug: &str) -> Result<()> {
-        let mut projects = self.get_projects().await.unwrap_or_default();
-        
-        // Find and remove the project
-        let original_len = projects.len();
-        projects.retain(|p| p.slug != project_slug);
-        
-        if projects.len() == original_len {
-            return Err(anyhow!("Project with slug '{}' not found", project_slug));
-        }
-        
-        // First, delete the time entries for this project
-        let time_key = format!("projects/{}", project_slug);
-        if let Err(e) = self.delete_key(&time_key).await {
-            // Only fail if it's not a 404 (key doesn't exist)
-            if !e.to_string().contains("404") {
-                return Err(anyhow!("Failed to delete time entries for project '{}': {}", project_slug, e));
-            }
-            // If 404, it just means no time entries exist, which is fine
+/// Keys of one listing page; accepts both the {"data": [...]} envelope and a bare array
+fn parse_key_page(body: &serde_json::Value) -> Result<Vec<KeyValueData>> {
+    if let Ok(list_response) = serde_json::from_value::<KeyValueListResponse>(body.clone()) {
+        return Ok(list_response.data);
+    }
+    if let Ok(list) = serde_json::from_value::<Vec<KeyValueData>>(body.clone()) {
+        return Ok(list);
+    }
+    Err(anyhow!("Unexpected response when listing keys: expected an array of key/value pairs or an object with a \"data\" array, got {}",
+                describe_json_shape(body)))
+}
+
+/// Target of a `Link: <url>; rel="next"` header
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers.get_all(reqwest::header::LINK).iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find(|link| link.split(';').skip(1).any(|param| param.trim().replace('"', "") == "rel=next"))
+        .and_then(|link| {
+            let target = link.split(';').next()?.trim();
+            Some(target.strip_prefix('<')?.strip_suffix('>')?.to_string())
+        })
+}
+
+/// `next`, `next_cursor` or `links.next` of a listing page, if there are more pages
+fn next_cursor(body: &serde_json::Value) -> Option<String> {
+    [body.get("next"), body.get("next_cursor"), body.pointer("/links/next")].into_iter()
+        .flatten()
+        .find_map(|value| match value {
+            serde_json::Value::String(cursor) if !cursor.is_empty() => Some(cursor.clone()),
+            serde_json::Value::Number(cursor) => Some(cursor.to_string()),
+            _ => None,
+        })
+}
+
+/// Whether a request failed because the server could not be reached at all
+pub fn is_unreachable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
+
+/// Expiry from a JWT's `exp` claim; the signature is not checked, this only drives the cache
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let payload = token.split('.').nth(1)?;
+    let claims: serde_json::Value = serde_json::from_slice(&decode_base64url(payload)?).ok()?;
+    let exp = claims.get("exp")?.as_f64()?;
+    DateTime::from_timestamp(exp as i64, 0)
+}
+
+fn decode_base64url(input: &str) -> Option<Vec<u8>> {
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    let mut bytes = Vec::new();
+    
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            // Accept the standard alphabet too, some servers don't use the URL-safe one
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
         }
-        
-        // Then update the projects list
-        let value = serde_json::to_value(projects)?;
-        self.update_key("projects", value).await?;
-        
-        Ok(())
     }
+    
+    Some(bytes)
+}
 
-    pub async fn delete_project_times(&self, project_slug: &str) -> Result<()> {
-        let key = format!("projects/{}", project_slug);
-        self.delete_key(&key).await
+fn describe_json_shape(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(_) => "a boolean".to_string(),
+        serde_json::Value::Number(_) => "a number".to_string(),
+        serde_json::Value::String(_) => "a string".to_string(),
+        serde_json::Value::Array(items) => format!("an array of {} items without \"key\"/\"value\" fields", items.len()),
+        serde_json::Value::Object(map) => {
+            let keys: Vec<&str> = map.keys().map(|k| k.as_str()).collect();
+            format!("an object with keys [{}]", keys.join(", "))
+        }
     }
+}
 
-    pub async fn delete_time_entry_by_timestamp(&self, project_slug: &str, timestamp: i64) -> Result<()> {
-        let key = format!("projects/{}", project_slug);
-        let mut entries = self.get_time_entries(project_slug).await.unwrap_or_default();
-        
-        // Find and remove the entry with the specified timestamp
-        let original_len = entries.len();
-        entries.retain(|entry| entry.timestamp != timestamp);
-        
-        if entries.len() == original_len {
-            return Err(anyhow!("Time entry with timestamp {} not found for project '{}'", timestamp, project_slug));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    
+    /// Request line and authorization header of every request the mock server got
+    type Requests = Arc<Mutex<Vec<String>>>;
+    
+    /// HTTP server on a free local port answering each request with `respond(request head)`,
+    /// i.e. the status, extra header lines and the body
+    async fn serve(respond: fn(&str) -> (u16, String, String)) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Requests::default();
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut reader = BufReader::new(stream);
+                let mut head = String::new();
+                while reader.read_line(&mut head).await.unwrap_or(0) > 2 {}
+                let length = head.lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|n| n.trim().parse().unwrap_or(0)))
+                    .unwrap_or(0);
+                let mut body = vec![0; length];
+                let _ = reader.read_exact(&mut body).await;
+                
+                let auth = head.lines().find(|line| line.to_lowercase().starts_with("authorization:")).unwrap_or("");
+                seen.lock().unwrap().push(format!("{} {}", head.lines().next().unwrap_or(""), auth).trim().to_string());
+                
+                let (status, headers, body) = respond(&head);
+                let response = format!("HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                                       status, body.len(), headers, body);
+                let _ = reader.get_mut().write_all(response.as_bytes()).await;
+            }
+        });
+        (base_url, requests)
+    }
+    
+    /// Client for the mock server, already holding `token`
+    fn client(base_url: &str, token: &str, name: &str) -> ApiClient {
+        ApiClient {
+            client: Client::new(),
+            token: Mutex::new(Some(token.to_string())),
+            login_url: format!("{}/login", base_url),
+            data_base_url: format!("{}/timetracker", base_url),
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            token_cache_file: env::temp_dir().join(format!("timetracker-test-{}-{}.json", name, std::process::id()))
+                .to_string_lossy().to_string(),
+            auth_header_name: "Authorization".to_string(),
+            auth_scheme: "Bearer".to_string(),
+            retries: 0,
+            retry_base_ms: 1,
+            timeout_secs: 5,
+            page_size: None,
+            verbose: 0,
+            optimistic_locking: true,
         }
+    }
+    
+    #[tokio::test]
+    async fn replays_request_after_login_on_401() {
+        let (base_url, requests) = serve(|head| {
+            if head.starts_with("POST /login") {
+                (200, String::new(), r#"{"token":"fresh"}"#.to_string())
+            } else if head.contains("Bearer fresh") {
+                (200, String::new(), r#"{"data":{"key":"projects","value":"[]"}}"#.to_string())
+            } else {
+                (401, String::new(), String::new())
+            }
+        }).await;
+        let api = client(&base_url, "expired", "relogin");
         
-        // Update the entries list
-        let value = serde_json::to_value(entries)?;
-        self.update_key(&key, value).await
+        let value = api.get_key_raw("projects").await.unwrap();
+        assert_eq!(value, Some(serde_json::json!("[]")));
+        assert_eq!(*requests.lock().unwrap(), vec![
+            "GET /timetracker/data/projects HTTP/1.1 authorization: Bearer expired",
+            "POST /login HTTP/1.1",
+            "GET /timetracker/data/projects HTTP/1.1 authorization: Bearer fresh",
+        ]);
+        let _ = fs::remove_file(&api.token_cache_file);
     }
-
-    pub async fn update_time_entry_by_timestamp(&self, project_slug: &str, timestamp: i64, new_description: Option<String>) -> Result<()> {
-        let key = format!("projects/{}", project_slug);
-        let mut entries = self.get_time_entries(project_slug).await.unwrap_or_default();
+    
+    #[tokio::test]
+    async fn logs_in_only_once_when_the_new_token_is_rejected_too() {
+        let (base_url, requests) = serve(|head| {
+            if head.starts_with("POST /login") {
+                (200, String::new(), r#"{"token":"fresh"}"#.to_string())
+            } else {
+                (401, String::new(), String::new())
+            }
+        }).await;
+        let api = client(&base_url, "expired", "relogin-rejected");
         
-        // Find the entry with the specified timestamp and update its description
-        let mut found = false;
-        for entry in &mut entries {
-            if entry.timestamp == timestamp {
-                entry.description = new_description.clone();
-                found = true;
-                break;
+        assert!(api.get_key_raw("projects").await.is_err());
+        let logins = requests.lock().unwrap().iter().filter(|request| request.starts_with("POST /login")).count();
+        assert_eq!(logins, 1);
+        let _ = fs::remove_file(&api.token_cache_file);
+    }
+    
+    #[test]
+    fn next_link_finds_the_next_relation() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::LINK, HeaderValue::from_static(
+            r#"</data?page=1>; rel="prev", </data?page=3>; rel="next""#));
+        assert_eq!(next_link(&headers).as_deref(), Some("/data?page=3"));
+        
+        headers.insert(reqwest::header::LINK, HeaderValue::from_static(r#"</data?page=1>; rel="prev""#));
+        assert_eq!(next_link(&headers), None);
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+    
+    #[test]
+    fn next_cursor_reads_the_known_fields() {
+        assert_eq!(next_cursor(&serde_json::json!({"data": [], "next": "/data?page=2"})).as_deref(), Some("/data?page=2"));
+        assert_eq!(next_cursor(&serde_json::json!({"data": [], "next_cursor": 42})).as_deref(), Some("42"));
+        assert_eq!(next_cursor(&serde_json::json!({"data": [], "links": {"next": "abc"}})).as_deref(), Some("abc"));
+        // An empty or null cursor means this was the last page
+        assert_eq!(next_cursor(&serde_json::json!({"data": [], "next_cursor": ""})), None);
+        assert_eq!(next_cursor(&serde_json::json!({"data": [], "next": null})), None);
+        assert_eq!(next_cursor(&serde_json::json!([])), None);
+    }
+    
+    #[tokio::test]
+    async fn lists_keys_across_pages_by_cursor() {
+        let (base_url, requests) = serve(|head| {
+            if head.starts_with("GET /timetracker/data?cursor=abc ") {
+                (200, String::new(), r#"{"data":[{"key":"projects/demo","value":"[]"}]}"#.to_string())
+            } else {
+                (200, String::new(), r#"{"data":[{"key":"projects","value":"[]"}],"next_cursor":"abc"}"#.to_string())
             }
-        }
+        }).await;
+        let api = client(&base_url, "valid", "cursor-pages");
         
-        if !found {
-            return Err(anyhow!("Time entry with timestamp {} not found for project '{}'", timestamp, project_slug));
-        }
+        let keys: Vec<String> = api.get_all_keys().await.unwrap().into_iter().map(|data| data.key).collect();
+        assert_eq!(keys, vec!["projects", "projects/demo"]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn lists_keys_across_pages_by_link_header() {
+        let (base_url, requests) = serve(|head| {
+            if head.starts_with("GET /timetracker/data?page=2 ") {
+                (200, String::new(), r#"[{"key":"projects/demo","value":"[]"}]"#.to_string())
+            } else {
+                (200, "Link: </timetracker/data?page=2>; rel=\"next\"\r\n".to_string(), r#"[{"key":"projects","value":"[]"}]"#.to_string())
+            }
+        }).await;
+        let api = client(&base_url, "valid", "link-pages");
         
-        // Update the entries list
-        let value = serde_json::to_value(entries)?;
-        self.update_key(&key, value).await
+        let keys: Vec<String> = api.get_all_keys().await.unwrap().into_iter().map(|data| data.key).collect();
+        assert_eq!(keys, vec!["projects", "projects/demo"]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
     }
-} 
\ No newline at end of file
+}
@@ -1,19 +1,58 @@
-use crate::api::{ApiClient, Project, TimeEntry};
+use crate::api::{Project, TimeEntry};
 use crate::logger::Logger;
+use crate::locale;
+use crate::store::{self, decode_value, KvStore};
+use crate::config;
+use crate::confirm::Confirm;
+use crate::exit_code::{self, CommandError, ExitCode};
+use crate::icons;
+use crate::journal::{self, Change};
+use crate::line_edit;
+use crate::output;
+use crate::queue::OfflineQueue;
+use crate::style;
+use crate::table::{Align, Table};
+use crate::tz;
 use anyhow::Result;
-use chrono::{DateTime, Utc, Local};
+use chrono::{DateTime, Datelike, Utc, NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
 use std::fs;
 use std::path::Path;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Projects started by this invocation, so an interrupt can point out the sessions it leaves running
+static STARTED_THIS_RUN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn started_this_run() -> Vec<String> {
+    STARTED_THIS_RUN.lock().map(|started| started.clone()).unwrap_or_default()
+}
+
+/// Set while `time start --watch` runs its stopwatch, which records the stop itself on Ctrl-C
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+pub fn watching() -> bool {
+    WATCHING.load(Ordering::Relaxed)
+}
 
 pub async fn add_project(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
     slug: &str,
     name: Option<String>,
     description: Option<String>,
+    rate: Option<f64>,
+    currency: Option<String>,
 ) -> Result<()> {
+    if let Err(e) = validate_slug(slug) {
+        eprintln!("{} {}", icons::error(), e);
+        logger.log(&format!("Refused to add project with invalid slug '{}'", slug)).await?;
+        return Err(exit_code::reported(&e));
+    }
+    
     let project_name = name.unwrap_or_else(|| slug.to_string());
     let project_description = description.unwrap_or_else(|| format!("Project {}", slug));
     
@@ -21,57 +60,260 @@ pub async fn add_project(
         name: project_name.clone(),
         slug: slug.to_string(),
         description: project_description.clone(),
+        last_touched: None,
+        rate,
+        budget_hours: None,
+        archived: false,
+        currency: currency.map(|currency| currency.to_uppercase()),
     };
 
     match api_client.add_project(project).await {
         Ok(_) => {
-            println!("✅ Project '{}' added successfully", slug);
+            println!("{} Project '{}' added successfully", icons::ok(), slug);
             logger.log(&format!("Added project: {} ({})", slug, project_name)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to add project: {}", e);
+            eprintln!("{} Failed to add project: {}", icons::error(), e);
             logger.log(&format!("Failed to add project {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
         }
     }
 
     Ok(())
 }
 
+/// `project add` without a slug: ask for the slug and for the name and description not given as flags
+pub async fn add_project_interactive(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    name: Option<String>,
+    description: Option<String>,
+    rate: Option<f64>,
+    currency: Option<String>,
+) -> Result<()> {
+    let existing: Vec<String> = api_client.get_projects().await
+        .map(|projects| projects.into_iter().map(|p| p.slug).collect())
+        .unwrap_or_default();
+    
+    // Ask again until the slug is usable; an empty answer cancels
+    let slug = loop {
+        print!("Enter project slug: ");
+        io::stdout().flush()?;
+        let mut slug = String::new();
+        io::stdin().read_line(&mut slug)?;
+        let slug = slug.trim();
+        if slug.is_empty() {
+            println!("{} No project added", icons::error());
+            return Ok(());
+        }
+        if let Err(e) = validate_slug(slug) {
+            println!("{} {}", icons::error(), e);
+            continue;
+        }
+        if existing.iter().any(|existing| existing == slug) {
+            println!("{} Project with slug '{}' already exists", icons::error(), slug);
+            continue;
+        }
+        break slug.to_string();
+    };
+    
+    let name = match name {
+        Some(name) => Some(name),
+        None => {
+            print!("Enter project name [{}]: ", slug);
+            io::stdout().flush()?;
+            let mut name = String::new();
+            io::stdin().read_line(&mut name)?;
+            Some(name.trim().to_string()).filter(|name| !name.is_empty())
+        }
+    };
+    
+    let description = match description {
+        Some(description) => Some(description),
+        None => {
+            print!("Enter project description [Project {}]: ", slug);
+            io::stdout().flush()?;
+            let mut description = String::new();
+            io::stdin().read_line(&mut description)?;
+            Some(description.trim().to_string()).filter(|description| !description.is_empty())
+        }
+    };
+    
+    add_project(api_client, logger, &slug, name, description, rate, currency).await
+}
+
+/// Slugs end up in keys like `projects/<slug>`, so only letters, numbers, hyphens and underscores are allowed
+fn validate_slug(slug: &str) -> Result<()> {
+    if slug.is_empty() {
+        return Err(anyhow::anyhow!("Slug must not be empty."));
+    }
+    if !slug.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(anyhow::anyhow!("Invalid slug format '{}'. Slug can only contain letters, numbers, hyphens, and underscores.", slug));
+    }
+    Ok(())
+}
+
+/// Options for `time start`
+#[derive(Debug, Clone, Default)]
+pub struct StartOptions {
+    pub resume_within: Option<i64>,
+    pub ago: Option<String>,
+    pub at: Option<String>,
+    /// Refuse to start while another project is running
+    pub exclusive: bool,
+    /// Use this time as "now", e.g. to start exactly where another session ended
+    pub timestamp: Option<i64>,
+    pub tags: Vec<String>,
+    /// Close a forgotten session at the MAX_SESSION_HOURS boundary before starting
+    pub auto_stop: bool,
+    /// Record the entries even if they lie in the future or before the project's latest entry
+    pub force: bool,
+}
+
 pub async fn start_tracking(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
+    confirm: Confirm,
     project_slug: &str,
     description: Option<String>,
+    options: &StartOptions,
 ) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let mut now = options.timestamp.unwrap_or_else(|| Utc::now().timestamp());
+    let backdated = match (options.ago.as_deref(), options.at.as_deref()) {
+        (Some(ago), _) => parse_duration(ago).map(|seconds| Some(now - seconds)),
+        (None, Some(at)) => parse_start_time(at).map(Some),
+        (None, None) => Ok(None),
+    };
+    let backdated = match backdated {
+        Ok(backdated) => backdated,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
     // Check current status before starting
     let project_display = get_project_display_name(api_client, project_slug).await;
-    match api_client.get_time_entries(project_slug).await {
-        Ok(entries) => {
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(mut entries) => {
+            if check_session_length(api_client, logger, project_slug, &entries, options.auto_stop).await? {
+                // The forgotten session is closed now, so this start opens a new one
+                entries = api_client.get_time_entries(project_slug).await.unwrap_or(entries);
+            }
+            if paused_since(&entries).is_some() {
+                eprintln!("{} Project {} is paused!", icons::error(), project_display);
+                eprintln!("   {} Use 'timetracker time resume {}' to continue the session", icons::hint(), project_slug);
+                logger.log(&format!("Attempted to start paused project: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            }
             if is_project_running(&entries) {
-                eprintln!("❌ Project {} is already running!", project_display);
-                eprintln!("   💡 Use 'timetracker end {}' to stop tracking first", project_slug);
+                eprintln!("{} Project {} is already running!", icons::error(), project_display);
+                if let Some(start) = last_start(&entries) {
+                    let running = calculate_total_time(&entries, RunningPolicy::Include)
+                        - calculate_total_time(&entries, RunningPolicy::Exclude);
+                    eprintln!("   Started at: {}{}", format_local_time(start.timestamp, &locale::current().datetime_format()),
+                              format_description(&start.description));
+                    eprintln!("   Running for: {}", format_duration(running, DurationStyle::Short));
+                }
                 logger.log(&format!("Attempted to start already running project: {}", project_slug)).await?;
-                return Ok(());
+                
+                // --yes never ends a session on its own, and JSON output has no one to ask
+                if confirm.assume_yes() || output::json() || !prompt_stop_and_restart()? {
+                    eprintln!("   {} Use 'timetracker time stop {}' to stop tracking first", icons::hint(), project_slug);
+                    return Err(CommandError::reported(ExitCode::Failure).into());
+                }
+                let Some(stop_description) = prompt_stop_description(&entries, confirm)? else {
+                    println!("{} Stop cancelled, a description is required", icons::error());
+                    return Err(CommandError::reported(ExitCode::Failure).into());
+                };
+                let stopped_at = Utc::now().timestamp();
+                end_tracking_at(api_client, logger, project_slug, stop_description, &[], stopped_at, options.force).await?;
+                
+                entries = api_client.get_time_entries(project_slug).await.unwrap_or(entries);
+                if is_project_running(&entries) {
+                    return Err(CommandError::reported(ExitCode::Failure).into());
+                }
+                // One second after the end, so the new start keeps its own timestamp
+                now = now.max(stopped_at + 1);
             }
+            entries
         }
         Err(e) => {
-            eprintln!("❌ Failed to check project status: {}", e);
+            eprintln!("{} Failed to check project status: {}", icons::error(), e);
             logger.log(&format!("Failed to check status before starting {}: {}", project_slug, e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
+        }
+    };
+
+    if options.exclusive {
+        let running = match running_projects(api_client).await {
+            Ok(running) => running,
+            Err(e) => {
+                eprintln!("{} Failed to check other projects: {}", icons::error(), e);
+                logger.log(&format!("Failed to check other projects before starting {}: {}", project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        };
+        let others: Vec<&Project> = running.iter().filter(|p| p.slug != project_slug).collect();
+        if !others.is_empty() {
+            for other in &others {
+                eprintln!("{} Project '{}' ({}) is already running!", icons::error(), other.name, other.slug);
+            }
+            eprintln!("   {} Use 'timetracker time switch {}' to stop it and start this one", icons::hint(), project_slug);
+            eprintln!("   {} Use --no-exclusive to track both in parallel", icons::hint());
+            logger.log(&format!("Refused exclusive start of {} while {} running", project_slug,
+                                others.iter().map(|p| p.slug.as_str()).collect::<Vec<_>>().join(", "))).await?;
+            return Err(CommandError::reported(ExitCode::Failure).into());
         }
     }
 
-    let timestamp = Utc::now().timestamp();
+    // Continue the previous session instead of opening a new one if it was stopped recently
+    if let Some(minutes) = options.resume_within {
+        let last_entry = entries.iter()
+            .filter(|e| e.entry_type == "start" || e.entry_type == "end")
+            .max_by_key(|e| e.timestamp);
+        if let Some(last_end) = last_entry.filter(|e| e.entry_type == "end") {
+            let stopped_for = Utc::now().timestamp() - last_end.timestamp;
+            if stopped_for <= minutes * 60 {
+                return resume_session(api_client, logger, confirm, project_slug, &project_display, last_end, description).await;
+            }
+        }
+    }
+
+    let timestamp = backdated.unwrap_or(now);
+    
+    if backdated.is_some() {
+        let datetime_format = locale::current().datetime_format();
+        if timestamp > now {
+            eprintln!("{} Start time {} is in the future", icons::error(), format_local_time(timestamp, &datetime_format));
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+        if let Some(last) = entries.iter().map(|e| e.timestamp).max() {
+            if timestamp < last {
+                eprintln!("{} Start time {} is before the last entry of project {} ({})", icons::error(),
+                          format_local_time(timestamp, &datetime_format),
+                          project_display,
+                          format_local_time(last, &datetime_format));
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            }
+        }
+    }
     
     let entry = TimeEntry {
         timestamp,
         entry_type: "start".to_string(),
         description: description.clone(),
+        tags: normalize_tags(&options.tags),
     };
 
-    match api_client.add_time_entry(project_slug, entry).await {
+    match api_client.add_time_entry(project_slug, entry, options.force).await {
         Ok(_) => {
-            println!("⏱️  Started tracking time for project {}", project_display);
+            println!("{}  Started tracking time for project {}", icons::timer(), project_display);
+            if backdated.is_some() {
+                println!("   Started at: {}", format_local_time(timestamp, &locale::current().datetime_format()));
+            }
             if let Some(desc) = &description {
                 println!("   Description: {}", desc);
             }
@@ -81,232 +323,3607 @@ pub async fn start_tracking(
                 format!("Started tracking time for project '{}'", project_slug)
             };
             logger.log(&log_msg).await?;
+            if let Ok(mut started) = STARTED_THIS_RUN.lock() {
+                started.push(project_slug.to_string());
+            }
+            if let Some(budget_hours) = api_client.get_project(project_slug).await.ok().and_then(|project| project.budget_hours) {
+                print_budget(budget_hours, calculate_total_time(&entries, RunningPolicy::Exclude));
+            }
         }
         Err(e) => {
-            eprintln!("❌ Failed to start tracking: {}", e);
+            eprintln!("{} Failed to start tracking: {}", icons::error(), e);
             logger.log(&format!("Failed to start tracking for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Offer to end the running session so a fresh one can start: (s)top and restart or (c)ancel
+fn prompt_stop_and_restart() -> Result<bool> {
+    print!("   (s)top and restart / (c)ancel: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "s" | "stop"))
+}
+
+/// Start the project that was stopped most recently again, offering its last description
+pub async fn resume_last(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, description: Option<String>, force: bool) -> Result<()> {
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
+            logger.log(&format!("Failed to get projects for resume-last: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // The latest end of every project that is not running, with the description of that session
+    let mut last_stopped: Option<(&Project, i64, Option<String>)> = None;
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let Ok(entries) = entries else { continue };
+        if is_project_running(&entries) {
+            continue;
+        }
+        let Some(end) = entries.iter().filter(|e| e.entry_type == "end").max_by_key(|e| e.timestamp) else {
+            continue;
+        };
+        if last_stopped.as_ref().is_some_and(|(_, timestamp, _)| *timestamp >= end.timestamp) {
+            continue;
+        }
+        // What the session was about, else what was done in it
+        let session_description = entries.iter()
+            .filter(|e| e.entry_type == "start" && e.timestamp <= end.timestamp)
+            .max_by_key(|e| e.timestamp)
+            .and_then(|start| start.description.clone())
+            .or_else(|| end.description.clone());
+        last_stopped = Some((project, end.timestamp, session_description));
+    }
+    
+    let Some((project, stopped_at, last_description)) = last_stopped else {
+        println!("{} No stopped session found to resume", icons::error());
+        return Err(CommandError::reported(ExitCode::NotFound).into());
+    };
+    
+    println!("{}  Resuming '{}' ({}), stopped at {}", icons::resume(), project.name, project.slug,
+             format_local_time(stopped_at, &locale::current().datetime_format()));
+    
+    let description = match (description, last_description) {
+        (Some(description), _) => Some(description),
+        (None, Some(last)) if !confirm.assume_yes() => {
+            let Some(input) = line_edit::edit("Description ('-' for none)", &last)? else {
+                println!("{} Resume cancelled", icons::error());
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            };
+            match input.as_str() {
+                "" | "-" => None,
+                _ => Some(input),
+            }
+        }
+        (None, last) => last,
+    };
+    
+    logger.log(&format!("Resuming last stopped project '{}'", project.slug)).await?;
+    let options = StartOptions {
+        force,
+        ..StartOptions::default()
+    };
+    start_tracking(api_client, logger, confirm, &project.slug, description, &options).await
+}
+
+async fn resume_session(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    project_display: &str,
+    last_end: &TimeEntry,
+    description: Option<String>,
+) -> Result<()> {
+    let stopped_for = Utc::now().timestamp() - last_end.timestamp;
+    
+    match api_client.resume_session(project_slug, last_end.timestamp).await {
+        Ok(_) => {
+            println!("{}  Resumed previous session for project {} (stopped {}m ago)", icons::resume(), project_display, stopped_for / 60);
+            if let Some(desc) = &last_end.description {
+                println!("   Stop description kept as note: {}", desc);
+            }
+            logger.log(&format!("Resumed session for project '{}' by removing end entry {}", project_slug, last_end.timestamp)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to resume session: {}", icons::error(), e);
+            logger.log(&format!("Failed to resume session for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    // The start description belongs to the original session, so record a new one as a note
+    if let Some(desc) = description {
+        add_note(api_client, logger, confirm, project_slug, &desc, false).await?;
+    }
+    
+    Ok(())
+}
+
+pub async fn end_tracking(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    description: Option<String>,
+    tags: &[String],
+    force: bool,
+) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let description = match description {
+        Some(description) => description,
+        None => {
+            let entries = api_client.get_time_entries(&project_slug).await.unwrap_or_default();
+            // Nothing to describe when nothing runs; end_tracking_at reports that
+            if !is_project_running(&entries) {
+                String::new()
+            } else if let Some(description) = prompt_stop_description(&entries, confirm)? {
+                description
+            } else {
+                println!("{} Stop cancelled, a description is required", icons::error());
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            }
         }
+    };
+    end_tracking_at(api_client, logger, &project_slug, description, tags, Utc::now().timestamp(), force).await
+}
+
+async fn end_tracking_at(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    description: String,
+    tags: &[String],
+    timestamp: i64,
+    force: bool,
+) -> Result<()> {
+    // Check current status before stopping
+    let project_display = get_project_display_name(api_client, project_slug).await;
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => {
+            if entries.is_empty() {
+                eprintln!("{} No time entries found for project {}!", icons::error(), project_display);
+                eprintln!("   {} Use 'timetracker start {}' to start tracking first", icons::hint(), project_slug);
+                logger.log(&format!("Attempted to stop project with no entries: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::NotFound).into());
+            }
+            
+            if !is_project_running(&entries) {
+                eprintln!("{} Project {} is not currently running!", icons::error(), project_display);
+                eprintln!("   {} Use 'timetracker start {}' to start tracking first", icons::hint(), project_slug);
+                logger.log(&format!("Attempted to stop already stopped project: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::NotFound).into());
+            }
+            entries
+        }
+        Err(e) => {
+            eprintln!("{} Failed to check project status: {}", icons::error(), e);
+            logger.log(&format!("Failed to check status before stopping {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+
+    let paused_since = paused_since(&entries);
+    
+    let entry = TimeEntry {
+        timestamp,
+        entry_type: "end".to_string(),
+        description: Some(description.clone()),
+        tags: normalize_tags(tags),
+    };
+
+    match api_client.add_time_entry(project_slug, entry, force).await {
+        Ok(_) => {
+            println!("{}  Stopped tracking time for project {}", icons::stop(), project_display);
+            println!("   What was done: {}", description);
+            if let Some(paused_at) = paused_since {
+                println!("   {}  The project was paused since {}, the time after the pause is not counted", icons::pause(),
+                         format_local_time(paused_at, &locale::current().datetime_format()));
+            }
+            let log_msg = format!("Stopped tracking time for project '{}' with description: {}", project_slug, description);
+            logger.log(&log_msg).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to stop tracking: {}", icons::error(), e);
+            logger.log(&format!("Failed to stop tracking for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn when the running session started more than MAX_SESSION_HOURS ago, which usually means
+/// a forgotten stop. With `auto_stop` the session is closed at that boundary; returns whether it was.
+async fn check_session_length(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    entries: &[TimeEntry],
+    auto_stop: bool,
+) -> Result<bool> {
+    let max_hours = config::max_session_hours();
+    let Some(start) = last_start(entries).filter(|_| is_project_running(entries)) else {
+        return Ok(false);
+    };
+    let running_hours = (Utc::now().timestamp() - start.timestamp) as f64 / 3600.0;
+    if running_hours <= max_hours {
+        return Ok(false);
+    }
+    
+    let max_hours_text = locale::current().format_decimal(max_hours, 1);
+    println!("{} Project '{}' has been running for {}h since {} (more than {}h). Did you forget to stop it?",
+             icons::danger(), project_slug, locale::current().format_decimal(running_hours, 1),
+             format_local_time(start.timestamp, &locale::current().datetime_format()), max_hours_text);
+    logger.log(&format!("Session of project '{}' started at {} runs longer than {}h", project_slug, start.timestamp, max_hours)).await?;
+    
+    if !auto_stop {
+        println!("   {} Close it at the right time with 'timetracker time fix {}', or use --auto-stop to end it after {}h",
+                 icons::hint(), project_slug, max_hours_text);
+        return Ok(false);
+    }
+    
+    // Entries written after the boundary (notes, pauses) stay inside the session
+    let boundary = start.timestamp + (max_hours * 3600.0) as i64;
+    let boundary = entries.iter().map(|e| e.timestamp + 1).fold(boundary, i64::max);
+    end_tracking_at(api_client, logger, project_slug, format!("Auto-stopped after {}h", max_hours_text), &[], boundary, false).await?;
+    logger.log(&format!("Auto-stopped project '{}' at {}", project_slug, boundary)).await?;
+    Ok(true)
+}
+
+/// Notes written since the running session started, oldest first, joined into one line
+fn notes_since_start(entries: &[TimeEntry]) -> Option<String> {
+    let start = last_start(entries)?.timestamp;
+    let mut notes: Vec<&TimeEntry> = entries.iter()
+        .filter(|e| e.entry_type == "note" && e.timestamp >= start)
+        .collect();
+    notes.sort_by_key(|e| e.timestamp);
+    let text = notes.iter()
+        .filter_map(|e| e.description.as_deref())
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Show a live stopwatch for the running session of `project_slug` until Enter or Ctrl-C, then stop it
+pub async fn watch_session(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+) -> Result<()> {
+    let entries = api_client.get_time_entries(project_slug).await.unwrap_or_default();
+    let Some(start) = last_start(&entries).filter(|_| is_project_running(&entries)).map(|e| e.timestamp) else {
+        eprintln!("{} Project '{}' is not running, nothing to watch", icons::error(), project_slug);
+        return Err(CommandError::reported(ExitCode::NotFound).into());
+    };
+    
+    WATCHING.store(true, Ordering::Relaxed);
+    // Reading stdin blocks, so a plain thread waits for Enter and the tick loop polls it
+    let (enter_tx, enter_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        let _ = io::stdin().read_line(&mut line);
+        let _ = enter_tx.send(());
+    });
+    
+    let mut ticks = tokio::time::interval(std::time::Duration::from_secs(1));
+    let mut interrupt = std::pin::pin!(tokio::signal::ctrl_c());
+    print!("\x1b[?25l");
+    let interrupted = loop {
+        tokio::select! {
+            _ = ticks.tick() => {
+                if enter_rx.try_recv().is_ok() {
+                    break false;
+                }
+                let elapsed = (Utc::now().timestamp() - start).max(0);
+                print!("\r\x1b[2K{} {}  {:02}:{:02}:{:02}  (press Enter to stop)",
+                       icons::timer(), project_slug, elapsed / 3600, elapsed % 3600 / 60, elapsed % 60);
+                io::stdout().flush()?;
+            }
+            _ = &mut interrupt => break true,
+        }
+    };
+    let stopped_at = Utc::now().timestamp();
+    print!("\x1b[?25h");
+    if interrupted {
+        println!();
+    }
+    io::stdout().flush()?;
+    
+    // The entries may have changed while watching, e.g. notes added from another terminal
+    let entries = api_client.get_time_entries(project_slug).await.unwrap_or(entries);
+    let description = if interrupted {
+        // A prompt can't be answered after Ctrl-C, so the notes are used and can be edited later
+        let notes = notes_since_start(&entries);
+        if notes.is_none() {
+            println!("{} Stopping without a description, add one with: timetracker time edit {}", icons::hint(), project_slug);
+        }
+        notes.unwrap_or_default()
+    } else {
+        match prompt_stop_description(&entries, confirm)? {
+            Some(description) => description,
+            None => {
+                println!("{} No description given, '{}' keeps running", icons::hint(), project_slug);
+                WATCHING.store(false, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+    };
+    logger.log(&format!("Stopwatch for {} ended after {}s", project_slug, stopped_at - start)).await?;
+    let result = end_tracking_at(api_client, logger, project_slug, description, &[], stopped_at, false).await;
+    WATCHING.store(false, Ordering::Relaxed);
+    result
+}
+
+/// Ask what was done, offering the session's notes, else its start description, as the default;
+/// None if nothing was given
+fn prompt_stop_description(entries: &[TimeEntry], confirm: Confirm) -> Result<Option<String>> {
+    let notes = notes_since_start(entries);
+    let started_with = last_start(entries)
+        .and_then(|start| start.description.clone())
+        .filter(|description| !description.trim().is_empty());
+    let suggestion = notes.clone().or(started_with.clone());
+    match (&notes, &started_with) {
+        (Some(notes), _) if confirm.assume_yes() => {
+            println!("Description: {} (--yes, from the notes)", notes);
+            return Ok(suggestion);
+        }
+        (Some(notes), _) => {
+            println!("{} Notes of this session: {}", icons::note(), notes);
+            print!("Description (press Enter to use the notes): ");
+        }
+        (None, Some(started_with)) if confirm.assume_yes() => {
+            println!("Description: {} (--yes, from the start)", started_with);
+            return Ok(suggestion);
+        }
+        (None, Some(started_with)) => {
+            println!("{} Started with: {}", icons::note(), started_with);
+            print!("Description (press Enter to keep it): ");
+        }
+        (None, None) => print!("Description of what was accomplished: "),
+    }
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    
+    if input.is_empty() {
+        return Ok(suggestion);
+    }
+    Ok(Some(input.to_string()))
+}
+
+/// The most recent start entry, i.e. the start of the running session if there is one
+fn last_start(entries: &[TimeEntry]) -> Option<&TimeEntry> {
+    entries.iter()
+        .filter(|e| e.entry_type == "start")
+        .max_by_key(|e| e.timestamp)
+}
+
+pub async fn switch_tracking(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    to_slug: &str,
+    description: String,
+    start_description: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let running = match running_projects(api_client).await {
+        Ok(running) => running,
+        Err(e) => {
+            eprintln!("{} Failed to check running projects: {}", icons::error(), e);
+            logger.log(&format!("Failed to check running projects before switching to {}: {}", to_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let others: Vec<&Project> = running.iter().filter(|p| p.slug != to_slug).collect();
+    if others.is_empty() {
+        if running.iter().any(|p| p.slug == to_slug) {
+            println!("{} Project '{}' is already running, nothing to switch", icons::hint(), to_slug);
+            return Ok(());
+        }
+        println!("{} No project is running, just starting '{}'", icons::hint(), to_slug);
+    }
+    
+    // End and start share one timestamp so there is no gap between the sessions
+    let timestamp = Utc::now().timestamp();
+    for other in &others {
+        end_tracking_at(api_client, logger, &other.slug, description.clone(), &[], timestamp, force).await?;
+    }
+    
+    let options = StartOptions {
+        timestamp: Some(timestamp),
+        force,
+        ..StartOptions::default()
+    };
+    start_tracking(api_client, logger, confirm, to_slug, start_description, &options).await?;
+    
+    logger.log(&format!("Switched from [{}] to '{}'",
+                        others.iter().map(|p| p.slug.as_str()).collect::<Vec<_>>().join(", "), to_slug)).await?;
+    Ok(())
+}
+
+pub async fn stop_all_running(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    description: String,
+    force: bool,
+) -> Result<()> {
+    let running = match running_projects(api_client).await {
+        Ok(running) => running,
+        Err(e) => {
+            eprintln!("{} Failed to check running projects: {}", icons::error(), e);
+            logger.log(&format!("Failed to check running projects for stop --all-running: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    if running.is_empty() {
+        println!("{} No projects are currently running", icons::stopped());
+        logger.log("Stop --all-running found no running projects").await?;
+        return Err(CommandError::reported(ExitCode::NotFound).into());
+    }
+    
+    println!("{}  Running projects to stop:", icons::stop());
+    for project in &running {
+        println!("  {} {} ({})", icons::bullet(), project.name, project.slug);
+    }
+    println!("   What was done: {}", description);
+    
+    println!();
+    if !confirm.ask(logger, &format!("Stop {} running project(s)?", running.len())).await? {
+        println!("{} Stop cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    // One shared timestamp so all sessions end at exactly the same moment
+    let timestamp = Utc::now().timestamp();
+    let mut stopped = 0;
+    // Keep stopping the others, the first failure decides the exit code
+    let mut failure = None;
+    for project in &running {
+        let entry = TimeEntry {
+            timestamp,
+            entry_type: "end".to_string(),
+            description: Some(description.clone()),
+            tags: Vec::new(),
+        };
+        match api_client.add_time_entry(&project.slug, entry, force).await {
+            Ok(_) => {
+                stopped += 1;
+                println!("{}  Stopped tracking time for project '{}' ({})", icons::stop(), project.name, project.slug);
+                logger.log(&format!("Stopped tracking time for project '{}' with description: {}", project.slug, description)).await?;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to stop '{}': {}", icons::error(), project.slug, e);
+                logger.log(&format!("Failed to stop tracking for {}: {}", project.slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+            }
+        }
+    }
+    
+    println!("{} Stopped {} of {} running project(s)", icons::ok(), stopped, running.len());
+    failure.map_or(Ok(()), Err)
+}
+
+pub async fn pause_tracking(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    description: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let project_display = get_project_display_name(api_client, project_slug).await;
+    match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => {
+            if !is_project_running(&entries) {
+                eprintln!("{} Project {} is not currently running!", icons::error(), project_display);
+                eprintln!("   {} Use 'timetracker time start {}' to start tracking first", icons::hint(), project_slug);
+                logger.log(&format!("Attempted to pause stopped project: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::NotFound).into());
+            }
+            if let Some(paused_at) = paused_since(&entries) {
+                eprintln!("{} Project {} is already paused since {}", icons::error(), project_display,
+                          format_local_time(paused_at, &locale::current().datetime_format()));
+                eprintln!("   {} Use 'timetracker time resume {}' to continue the session", icons::hint(), project_slug);
+                logger.log(&format!("Attempted to pause already paused project: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            }
+        }
+        Err(e) => {
+            eprintln!("{} Failed to check project status: {}", icons::error(), e);
+            logger.log(&format!("Failed to check status before pausing {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    let entry = TimeEntry {
+        timestamp: Utc::now().timestamp(),
+        entry_type: "pause".to_string(),
+        description: description.clone(),
+        tags: Vec::new(),
+    };
+    
+    match api_client.add_time_entry(project_slug, entry, force).await {
+        Ok(_) => {
+            println!("{}  Paused project {}", icons::pause(), project_display);
+            if let Some(desc) = &description {
+                println!("   Reason: {}", desc);
+            }
+            logger.log(&format!("Paused project '{}'", project_slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to pause: {}", icons::error(), e);
+            logger.log(&format!("Failed to pause {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+pub async fn resume_tracking(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    force: bool,
+) -> Result<()> {
+    let project_display = get_project_display_name(api_client, project_slug).await;
+    let paused_at = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => match paused_since(&entries) {
+            Some(paused_at) => paused_at,
+            None => {
+                if is_project_running(&entries) {
+                    eprintln!("{} Project {} is running, not paused", icons::error(), project_display);
+                } else {
+                    eprintln!("{} Project {} is not currently running!", icons::error(), project_display);
+                    eprintln!("   {} Use 'timetracker time start {}' to start tracking", icons::hint(), project_slug);
+                }
+                logger.log(&format!("Attempted to resume project that is not paused: {}", project_slug)).await?;
+                return Err(CommandError::reported(ExitCode::NotFound).into());
+            }
+        },
+        Err(e) => {
+            eprintln!("{} Failed to check project status: {}", icons::error(), e);
+            logger.log(&format!("Failed to check status before resuming {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let timestamp = Utc::now().timestamp();
+    let entry = TimeEntry {
+        timestamp,
+        entry_type: "resume".to_string(),
+        description: None,
+        tags: Vec::new(),
+    };
+    
+    match api_client.add_time_entry(project_slug, entry, force).await {
+        Ok(_) => {
+            let paused_for = timestamp - paused_at;
+            println!("{}  Resumed project {} after a {} pause", icons::resume(), project_display, format_duration(paused_for, DurationStyle::Short));
+            logger.log(&format!("Resumed project '{}'", project_slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to resume: {}", icons::error(), e);
+            logger.log(&format!("Failed to resume {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Order of `project list --sort`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ProjectSort {
+    Name,
+    Slug,
+}
+
+/// Drop archived projects unless --include-archived was given
+fn visible_projects(projects: Vec<Project>) -> Vec<Project> {
+    if store::include_archived() {
+        return projects;
+    }
+    projects.into_iter().filter(|project| !project.archived).collect()
+}
+
+/// Projects containing `filter` in their name, slug or description, ignoring case
+fn filter_projects(projects: Vec<Project>, filter: &str) -> Vec<Project> {
+    let filter = filter.to_lowercase();
+    projects.into_iter()
+        .filter(|project| {
+            project.name.to_lowercase().contains(&filter)
+                || project.slug.to_lowercase().contains(&filter)
+                || project.description.to_lowercase().contains(&filter)
+        })
+        .collect()
+}
+
+pub async fn list_projects(api_client: &impl KvStore, logger: &Logger, filter: Option<&str>, sort: Option<ProjectSort>) -> Result<()> {
+    match filter {
+        Some(filter) => logger.log(&format!("Listed projects matching '{}'", filter)).await?,
+        None => logger.log("Listed all projects").await?,
+    }
+    
+    let projects = api_client.get_projects().await.map(|projects| {
+        let projects = visible_projects(projects);
+        let mut projects = match filter {
+            Some(filter) => filter_projects(projects, filter),
+            None => projects,
+        };
+        match sort {
+            Some(ProjectSort::Name) => projects.sort_by_key(|project| project.name.to_lowercase()),
+            Some(ProjectSort::Slug) => projects.sort_by(|a, b| a.slug.cmp(&b.slug)),
+            None => {}
+        }
+        projects
+    });
+    
+    match projects {
+        Ok(projects) if output::json() => {
+            output::print_json(&serde_json::to_value(&projects)?)?;
+        }
+        Ok(projects) => {
+            if let (true, Some(filter)) = (projects.is_empty(), filter) {
+                println!("{} No projects match '{}'", icons::list(), filter);
+            } else if projects.is_empty() {
+                println!("{} No projects found", icons::list());
+            } else {
+                println!("{} Projects:", icons::list());
+                let entries = api_client.get_time_entries_of(&projects).await;
+                let mut table = Table::new(vec![Align::Left, Align::Left, Align::Left, Align::Right]).truncate(2);
+                table.row(vec!["Name".to_string(), "Slug".to_string(), "Description".to_string(), "Total".to_string()]);
+                for (project, entries) in projects.into_iter().zip(entries) {
+                    let name = if project.archived { format!("{} [archived]", project.name) } else { project.name };
+                    // The total is left out when the entries could not be read
+                    let total = entries.as_ref().ok()
+                        .map(|entries| format_duration(calculate_total_time(entries, RunningPolicy::Include), DurationStyle::Short))
+                        .unwrap_or_default();
+                    let cells = vec![format!("{} {}", icons::bullet(), name), project.slug, project.description, total];
+                    if entries.is_ok_and(|entries| is_project_running(&entries)) {
+                        table.styled_row(cells, style::green);
+                    } else {
+                        table.row(cells);
+                    }
+                }
+                table.print();
+            }
+        }
+        Err(e) => {
+            logger.log(&format!("Failed to list projects: {}", e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to list projects: {}", e)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Options for `time list`
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub pairs: bool,
+    pub fix_missing: bool,
+    /// First day to show (YYYY-MM-DD, local time)
+    pub from: Option<String>,
+    /// Last day to show, inclusive (YYYY-MM-DD, local time)
+    pub to: Option<String>,
+}
+
+pub async fn list_times(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    options: &ListOptions,
+) -> Result<()> {
+    logger.log(&format!("Listed times for project '{}'", project_slug)).await?;
+    let pairs = options.pairs;
+    let fix_missing = options.fix_missing;
+    
+    // Timestamps from the start of --from up to the end of the --to day
+    let range = options.from.as_deref().map(parse_date).transpose()
+        .and_then(|from| Ok((from, options.to.as_deref().map(parse_date).transpose()?)));
+    let (from, to) = match range {
+        Ok((Some(from), Some(to))) if from > to => {
+            eprintln!("{} --from must not be after --to", icons::error());
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    let filtered = from.is_some() || to.is_some();
+    let from_ts = from.map(local_day_start);
+    let to_ts = to.and_then(|to| to.succ_opt()).map(local_day_start);
+    
+    match api_client.get_time_entries(project_slug).await.map(|entries| {
+        let total = entries.len();
+        let entries: Vec<TimeEntry> = entries.into_iter()
+            .filter(|e| from_ts.is_none_or(|from| e.timestamp >= from))
+            .filter(|e| to_ts.is_none_or(|to| e.timestamp < to))
+            .collect();
+        (entries, total)
+    }) {
+        Ok((entries, _)) if output::json() => {
+            let document = if pairs {
+                let sessions: Vec<serde_json::Value> = reconstruct_sessions(&entries).iter()
+                    .map(session_json)
+                    .collect();
+                serde_json::json!({ "project": project_slug, "sessions": sessions })
+            } else {
+                serde_json::json!({ "project": project_slug, "entries": entries })
+            };
+            output::print_json(&document)?;
+        }
+        Ok((entries, total)) => {
+            if filtered {
+                let date_format = locale::current().date_format();
+                println!("{} {} of {} entries between {} and {}", icons::search(),
+                         entries.len(), total,
+                         from.map(|d| d.format(date_format).to_string()).unwrap_or_else(|| "the beginning".to_string()),
+                         to.map(|d| d.format(date_format).to_string()).unwrap_or_else(|| "now".to_string()));
+            }
+            if entries.is_empty() {
+                println!("{}  No time entries found for project '{}'", icons::timer(), project_slug);
+            } else if pairs {
+                println!("{}  Sessions for project '{}':", icons::timer(), project_slug);
+                print_sessions_with_notes(&entries);
+            } else {
+                println!("{}  Time entries for project '{}':", icons::timer(), project_slug);
+                // Durations shown on the end of each session and on the start that is still running
+                let sessions: HashMap<i64, Session> = reconstruct_sessions(&entries).into_iter()
+                    .map(|session| (session.end, session))
+                    .collect();
+                let running = last_start(&entries)
+                    .filter(|_| is_project_running(&entries))
+                    .map(|start| (start.timestamp, calculate_total_time(&entries, RunningPolicy::Include)
+                        - calculate_total_time(&entries, RunningPolicy::Exclude)));
+                
+                let mut sorted_entries: Vec<&TimeEntry> = entries.iter().collect();
+                sorted_entries.sort_by_key(|e| e.timestamp);
+                
+                let mut table = Table::new(vec![Align::Left, Align::Left, Align::Left, Align::Left, Align::Left, Align::Left]).truncate(5);
+                for entry in sorted_entries {
+                    let duration = match entry.entry_type.as_str() {
+                        "end" => sessions.get(&entry.timestamp)
+                            .map(|session| format!("(duration: {})", format_duration(session.duration(), DurationStyle::Short))),
+                        "start" => running.filter(|(start, _)| *start == entry.timestamp)
+                            .map(|(_, seconds)| format!("(running: {})", format_duration(seconds, DurationStyle::Short))),
+                        _ => None,
+                    };
+                    let utc_datetime = DateTime::from_timestamp(entry.timestamp, 0)
+                        .unwrap_or_else(Utc::now);
+                    let local_datetime = tz::localize(utc_datetime);
+                    let type_icon = entry_type_icon(&entry.entry_type);
+                    // The end line describes the whole session, so a description given on start shows up there too
+                    let description = match sessions.get(&entry.timestamp).filter(|_| entry.entry_type == "end") {
+                        Some(Session { description: Some(description), .. }) if entry.description.as_deref().is_none_or(|d| d.trim().is_empty()) => {
+                            format!("- {} (from start)", description)
+                        }
+                        _ => entry.description.as_ref().map(|d| format!("- {}", d)).unwrap_or_default(),
+                    };
+                    
+                    table.row(vec![
+                        type_icon.to_string(),
+                        entry.entry_type.to_uppercase(),
+                        local_datetime.format(&locale::current().datetime_format()).to_string(),
+                        format!("[ts:{}]", entry.timestamp),
+                        duration.unwrap_or_default(),
+                        description,
+                    ]);
+                }
+                table.print();
+                println!();
+                println!("{} To delete a specific entry: timetracker delete times {} --timestamp <ts>", icons::hint(), project_slug);
+            }
+            
+            if fix_missing && !entries.is_empty() {
+                fix_missing_descriptions(api_client, logger, project_slug, &entries).await?;
+            }
+        }
+        Err(e) => {
+            logger.log(&format!("Failed to list times for {}: {}", project_slug, e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to list times: {}", e)));
+        }
+    }
+
+    Ok(())
+}
+
+async fn fix_missing_descriptions(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    entries: &[TimeEntry],
+) -> Result<()> {
+    let mut missing: Vec<&TimeEntry> = entries.iter()
+        .filter(|e| e.description.is_none() && e.entry_type != "note")
+        .collect();
+    missing.sort_by_key(|e| e.timestamp);
+    
+    if missing.is_empty() {
+        println!("{} All time entries have a description", icons::ok());
+        return Ok(());
+    }
+    
+    println!();
+    println!("{} {} entries without a description:", icons::note(), missing.len());
+    
+    // Collect all answers first and write them in one go at the end
+    let mut descriptions = HashMap::new();
+    for entry in missing {
+        println!();
+        println!("  {} {} {}",
+                 entry_type_icon(&entry.entry_type),
+                 entry.entry_type.to_uppercase(),
+                 format_local_time(entry.timestamp, &locale::current().datetime_format()));
+        print!("Enter description (press Enter to skip, or 'q' to stop): ");
+        io::stdout().flush()?;
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        
+        if input.eq_ignore_ascii_case("q") {
+            break;
+        }
+        if !input.is_empty() {
+            descriptions.insert(entry.timestamp, input.to_string());
+        }
+    }
+    
+    if descriptions.is_empty() {
+        println!("{} No descriptions added", icons::error());
+        return Ok(());
+    }
+    
+    match api_client.update_time_entry_descriptions(project_slug, &descriptions).await {
+        Ok(_) => {
+            println!("{} Added {} descriptions", icons::ok(), descriptions.len());
+            logger.log(&format!("Added {} missing descriptions for project {}", descriptions.len(), project_slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to update descriptions: {}", icons::error(), e);
+            logger.log(&format!("Failed to add missing descriptions for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+fn print_sessions_with_notes(entries: &[TimeEntry]) {
+    let mut sorted_entries: Vec<&TimeEntry> = entries.iter().collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
+    
+    // The open start entry and the notes collected while it runs
+    let mut open_session: Option<(&TimeEntry, Vec<&TimeEntry>)> = None;
+    
+    for entry in sorted_entries {
+        match entry.entry_type.as_str() {
+            "start" => {
+                if let Some((start, notes)) = open_session.take() {
+                    // A second start without an end in between
+                    print_session(start, None, &notes);
+                }
+                open_session = Some((entry, Vec::new()));
+            }
+            "end" => {
+                if let Some((start, notes)) = open_session.take() {
+                    print_session(start, Some(entry), &notes);
+                } else {
+                    println!("  {}  {} (no matching start){}", icons::stop(),
+                             format_local_time(entry.timestamp, &locale::current().datetime_format()),
+                             format_description(&entry.description));
+                }
+            }
+            "note" | "pause" | "resume" => {
+                if let Some((_, notes)) = open_session.as_mut() {
+                    notes.push(entry);
+                } else {
+                    println!("  {} {}{}", icons::note(),
+                             format_local_time(entry.timestamp, &locale::current().datetime_format()),
+                             format_description(&entry.description));
+                }
+            }
+            _ => {} // Ignore unknown types
+        }
+    }
+    
+    if let Some((start, notes)) = open_session {
+        print_session(start, None, &notes);
+    }
+}
+
+fn print_session(start: &TimeEntry, end: Option<&TimeEntry>, notes: &[&TimeEntry]) {
+    let start_text = format_local_time(start.timestamp, &locale::current().datetime_format());
+    match end {
+        Some(end) => {
+            let duration = end.timestamp - start.timestamp - paused_seconds(notes, end.timestamp);
+            println!("  {} {} {} {} {} ({}){}{}", icons::start(),
+                     start_text, icons::arrow(), icons::stop(),
+                     format_local_time(end.timestamp, "%H:%M:%S"),
+                     format_duration(duration, DurationStyle::Short),
+                     format_tags(&normalize_tags(&[start.tags.clone(), end.tags.clone()].concat())),
+                     format_description(&end.description.clone().or_else(|| start.description.clone())));
+        }
+        None => {
+            println!("  {} {} {} (running){}{}", icons::start(), start_text, icons::arrow(),
+                     format_tags(&start.tags), format_description(&start.description));
+        }
+    }
+    
+    for note in notes {
+        println!("     {} {}{}",
+                 entry_type_icon(&note.entry_type),
+                 format_local_time(note.timestamp, "%H:%M:%S"),
+                 format_description(&note.description));
+    }
+}
+
+/// Seconds spent paused within a session; an open pause lasts until `end`
+fn paused_seconds(entries: &[&TimeEntry], end: i64) -> i64 {
+    let mut paused = 0;
+    let mut pause_start: Option<i64> = None;
+    for entry in entries {
+        match entry.entry_type.as_str() {
+            "pause" if pause_start.is_none() => pause_start = Some(entry.timestamp),
+            "resume" => {
+                if let Some(start) = pause_start.take() {
+                    paused += entry.timestamp - start;
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = pause_start {
+        paused += (end - start).max(0);
+    }
+    paused
+}
+
+fn format_description(description: &Option<String>) -> String {
+    description.as_ref()
+        .map(|d| format!(" - {}", d))
+        .unwrap_or_default()
+}
+
+fn format_local_time(timestamp: i64, format: &str) -> String {
+    let utc_datetime = DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_else(Utc::now);
+    tz::localize(utc_datetime).format(format).to_string()
+}
+
+fn entry_type_icon(entry_type: &str) -> &'static str {
+    match entry_type {
+        "start" => icons::start(),
+        "note" => icons::note(),
+        "pause" => icons::pause(),
+        "resume" => icons::resume(),
+        "end" => icons::stop(),
+        _ => icons::unknown(),
+    }
+}
+
+pub async fn add_manual_entry(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    start: &str,
+    end: &str,
+    description: String,
+    allow_overlap: bool,
+) -> Result<()> {
+    let (start, end) = match (parse_datetime(start), parse_datetime(end)) {
+        (Ok(start), Ok(end)) => (start, end),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    if end <= start {
+        eprintln!("{} End time must be after start time", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    let datetime_format = locale::current().datetime_format();
+    
+    if !allow_overlap {
+        let entries = match api_client.get_time_entries(project_slug).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        };
+        
+        let mut sessions: Vec<(i64, i64)> = reconstruct_sessions(&entries).iter()
+            .map(|s| (s.start, s.end))
+            .collect();
+        // The running session occupies everything from its start up to now
+        if is_project_running(&entries) {
+            if let Some(last_start) = entries.iter().filter(|e| e.entry_type == "start").map(|e| e.timestamp).max() {
+                sessions.push((last_start, Utc::now().timestamp()));
+            }
+        }
+        
+        if let Some((other_start, other_end)) = sessions.iter().find(|(s, e)| *s < end && start < *e) {
+            eprintln!("{} The new session overlaps an existing one in project '{}':", icons::error(), project_slug);
+            eprintln!("   {} {} {}",
+                      format_local_time(*other_start, &datetime_format), icons::arrow(),
+                      format_local_time(*other_end, &datetime_format));
+            eprintln!("   {} Use --allow-overlap to add it anyway", icons::hint());
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+    }
+    
+    let new_entries = vec![
+        TimeEntry {
+            timestamp: start,
+            entry_type: "start".to_string(),
+            description: Some(description.clone()),
+            tags: Vec::new(),
+        },
+        TimeEntry {
+            timestamp: end,
+            entry_type: "end".to_string(),
+            description: Some(description.clone()),
+            tags: Vec::new(),
+        },
+    ];
+    
+    match api_client.add_time_entries(project_slug, new_entries).await {
+        Ok(_) => {
+            let duration = end - start;
+            println!("{} Added session to project '{}'", icons::add(), project_slug);
+            println!("   {} {} {} ({})",
+                     format_local_time(start, &datetime_format), icons::arrow(),
+                     format_local_time(end, &datetime_format),
+                     format_duration(duration, DurationStyle::Short));
+            println!("   What was done: {}", description);
+            logger.log(&format!("Added manual session to project '{}' from {} to {}: {}", project_slug, start, end, description)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to add session: {}", icons::error(), e);
+            logger.log(&format!("Failed to add manual session for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// File format of `time import-entries`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportFormat {
+    /// start,end,description per line; a header line is skipped
+    Csv,
+    /// An array of {"start", "end", "description"} objects
+    Json,
+}
+
+/// A session read from an import file, with the line or position it came from
+struct ImportedSession {
+    row: usize,
+    start: i64,
+    end: i64,
+    description: Option<String>,
+}
+
+/// Rows of an import file that were left out, with the reason
+type SkippedRows = Vec<(usize, String)>;
+
+/// Add every valid session of `file` with one write, reporting the rows that were skipped
+pub async fn import_entries(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    file: &str,
+    format: Option<ImportFormat>,
+) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let format = format.unwrap_or(if file.to_lowercase().ends_with(".json") { ImportFormat::Json } else { ImportFormat::Csv });
+    
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{} Failed to read {}: {}", icons::error(), file, e);
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+    };
+    let (sessions, mut skipped) = match format {
+        ImportFormat::Csv => parse_import_csv(&content),
+        ImportFormat::Json => match parse_import_json(&content) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("{} Failed to parse {}: {}", icons::error(), file, e);
+                return Err(exit_code::reported(&e));
+            }
+        },
+    };
+    
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let new_entries = plan_import(&entries, sessions, &mut skipped);
+    let imported = new_entries.len() / 2;
+    
+    skipped.sort_by_key(|(row, _)| *row);
+    for (row, reason) in &skipped {
+        eprintln!("{}  Skipped row {}: {}", icons::warning(), row, reason);
+    }
+    if imported == 0 {
+        eprintln!("{} Nothing to import into project '{}'", icons::error(), project_slug);
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    match api_client.add_time_entries(project_slug, new_entries).await {
+        Ok(_) => {
+            println!("{} Imported {} sessions into project '{}' ({} skipped)", icons::add(), imported, project_slug, skipped.len());
+            logger.log(&format!("Imported {} sessions from {} into project '{}', skipped {}", imported, file, project_slug, skipped.len())).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to import sessions: {}", icons::error(), e);
+            logger.log(&format!("Failed to import sessions from {} for {}: {}", file, project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Start and end entries for the sessions that fit between `entries`; the others are added to `skipped`
+fn plan_import(entries: &[TimeEntry], mut sessions: Vec<ImportedSession>, skipped: &mut SkippedRows) -> Vec<TimeEntry> {
+    // Existing sessions, the running one up to now, and every accepted row block their time
+    let mut occupied: Vec<(i64, i64)> = reconstruct_sessions(entries).iter()
+        .map(|session| (session.start, session.end))
+        .collect();
+    if is_project_running(entries) {
+        if let Some(start) = last_start(entries) {
+            occupied.push((start.timestamp, Utc::now().timestamp()));
+        }
+    }
+    let mut taken: HashSet<i64> = entries.iter().map(|e| e.timestamp).collect();
+    
+    sessions.sort_by_key(|session| session.start);
+    let mut new_entries = Vec::new();
+    for session in sessions {
+        if let Some((start, end)) = occupied.iter().find(|(start, end)| *start < session.end && session.start < *end) {
+            skipped.push((session.row, format!("overlaps the session {} {} {}",
+                                               format_local_time(*start, "%Y-%m-%d %H:%M"), icons::arrow(), format_local_time(*end, "%Y-%m-%d %H:%M"))));
+            continue;
+        }
+        if taken.contains(&session.start) || taken.contains(&session.end) {
+            skipped.push((session.row, "another entry already exists at its start or end".to_string()));
+            continue;
+        }
+        occupied.push((session.start, session.end));
+        taken.extend([session.start, session.end]);
+        new_entries.push(TimeEntry {
+            timestamp: session.start,
+            entry_type: "start".to_string(),
+            description: session.description.clone(),
+            tags: Vec::new(),
+        });
+        new_entries.push(TimeEntry {
+            timestamp: session.end,
+            entry_type: "end".to_string(),
+            description: session.description,
+            tags: Vec::new(),
+        });
+    }
+    
+    new_entries
+}
+
+/// A start or end of an import: a unix timestamp or anything parse_datetime understands
+fn parse_import_time(input: &str) -> Result<i64> {
+    input.trim().parse::<i64>().or_else(|_| parse_datetime(input.trim()))
+}
+
+/// A session with a valid time range, or why the row can't be used
+fn imported_session(row: usize, start: &str, end: &str, description: Option<String>) -> Result<ImportedSession, (usize, String)> {
+    let start = parse_import_time(start).map_err(|e| (row, e.to_string()))?;
+    let end = parse_import_time(end).map_err(|e| (row, e.to_string()))?;
+    if end <= start {
+        return Err((row, "the end is not after the start".to_string()));
+    }
+    let description = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    Ok(ImportedSession { row, start, end, description })
+}
+
+/// Sessions from CSV lines of start,end,description (rows are line numbers)
+fn parse_import_csv(content: &str) -> (Vec<ImportedSession>, SkippedRows) {
+    let mut sessions = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        if index == 0 && fields.first().is_some_and(|field| field.trim().eq_ignore_ascii_case("start")) {
+            continue;
+        }
+        if fields.len() < 2 {
+            skipped.push((index + 1, "expected start,end,description".to_string()));
+            continue;
+        }
+        match imported_session(index + 1, &fields[0], &fields[1], fields.get(2).cloned()) {
+            Ok(session) => sessions.push(session),
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    (sessions, skipped)
+}
+
+/// Split a CSV line into fields, honouring double quotes ("" is a literal quote)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Sessions from a JSON array of {"start", "end", "description"} (rows count from 1)
+fn parse_import_json(content: &str) -> Result<(Vec<ImportedSession>, SkippedRows)> {
+    let rows: Vec<serde_json::Value> = serde_json::from_str(content)?;
+    let mut sessions = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        // Times may be strings or unix timestamps
+        let time = |field: &str| match &row[field] {
+            serde_json::Value::String(text) => Some(text.clone()),
+            serde_json::Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        };
+        let (Some(start), Some(end)) = (time("start"), time("end")) else {
+            skipped.push((index + 1, "needs a start and an end".to_string()));
+            continue;
+        };
+        let description = row["description"].as_str().map(str::to_string);
+        match imported_session(index + 1, &start, &end, description) {
+            Ok(session) => sessions.push(session),
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    Ok((sessions, skipped))
+}
+
+/// A session from a Toggl or Clockify CSV export, with the project name it belongs to
+struct ForeignSession {
+    project: String,
+    session: ImportedSession,
+}
+
+/// Import a Toggl (or Clockify) detailed CSV export, creating the projects it mentions
+pub async fn import_toggl(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    file: &str,
+) -> Result<()> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{} Failed to read {}: {}", icons::error(), file, e);
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+    };
+    let (rows, mut skipped) = match parse_toggl_csv(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{} Failed to parse {}: {}", icons::error(), file, e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    let mut projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
+            logger.log(&format!("Failed to get projects: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // Toggl only knows project names, so match them by name first and by slug second
+    let mut by_project: BTreeMap<String, (String, Vec<ImportedSession>)> = BTreeMap::new();
+    for row in rows {
+        let slug = projects.iter()
+            .find(|project| project.name.eq_ignore_ascii_case(&row.project))
+            .or_else(|| projects.iter().find(|project| project.slug == slugify(&row.project)))
+            .map(|project| project.slug.clone())
+            .unwrap_or_else(|| slugify(&row.project));
+        by_project.entry(slug).or_insert_with(|| (row.project.clone(), Vec::new())).1.push(row.session);
+    }
+    
+    let mut created = Vec::new();
+    let mut imported = 0;
+    // Keep importing the other projects, the first failure decides the exit code
+    let mut failure = None;
+    for (slug, (name, sessions)) in by_project {
+        if !projects.iter().any(|project| project.slug == slug) {
+            let project = Project {
+                name: name.clone(),
+                slug: slug.clone(),
+                description: format!("Imported from {}", file),
+                last_touched: None,
+                rate: None,
+                budget_hours: None,
+                archived: false,
+                currency: None,
+            };
+            if let Err(e) = api_client.add_project(project.clone()).await {
+                eprintln!("{} Failed to create project '{}': {}", icons::error(), slug, e);
+                logger.log(&format!("Failed to create project {} for import: {}", slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+                skipped.extend(sessions.iter().map(|session| (session.row, format!("project '{}' could not be created", slug))));
+                continue;
+            }
+            logger.log(&format!("Added project: {} ({}) from {}", slug, name, file)).await?;
+            projects.push(project);
+            created.push(slug.clone());
+        }
+        
+        let entries = match api_client.get_time_entries(&slug).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::error(), slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+                continue;
+            }
+        };
+        let new_entries = plan_import(&entries, sessions, &mut skipped);
+        let count = new_entries.len() / 2;
+        if count == 0 {
+            continue;
+        }
+        match api_client.add_time_entries(&slug, new_entries).await {
+            Ok(_) => {
+                println!("{} Imported {} sessions into project '{}'", icons::add(), count, slug);
+                logger.log(&format!("Imported {} sessions from {} into project '{}'", count, file, slug)).await?;
+                imported += count;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to import sessions into '{}': {}", icons::error(), slug, e);
+                logger.log(&format!("Failed to import sessions from {} for {}: {}", file, slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+            }
+        }
+    }
+    
+    skipped.sort_by_key(|(row, _)| *row);
+    for (row, reason) in &skipped {
+        eprintln!("{}  Skipped row {}: {}", icons::warning(), row, reason);
+    }
+    if !created.is_empty() {
+        println!("{} Created {} projects: {}", icons::ok(), created.len(), created.join(", "));
+    }
+    if imported == 0 {
+        eprintln!("{} Nothing imported from {} ({} skipped)", icons::error(), file, skipped.len());
+        failure.get_or_insert(CommandError::reported(ExitCode::Failure).into());
+    } else {
+        println!("{} Imported {} sessions from {} ({} projects created, {} skipped)", icons::ok(), imported, file, created.len(), skipped.len());
+    }
+    
+    failure.map_or(Ok(()), Err)
+}
+
+/// A slug for a project name from another tool, e.g. "Client Work / Q1" becomes "client-work-q1"
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() { "no-project".to_string() } else { slug }
+}
+
+/// Rows of a Toggl/Clockify export, found by their header names (rows are line numbers)
+fn parse_toggl_csv(content: &str) -> Result<(Vec<ForeignSession>, SkippedRows)> {
+    let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Err(anyhow::anyhow!("the file is empty"));
+    };
+    let header: Vec<String> = split_csv_line(header.trim_start_matches('\u{feff}')).iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|name| names.contains(&name.as_str()));
+    let required = |names: &[&str]| column(names).ok_or_else(|| anyhow::anyhow!("no '{}' column in the header", names[0]));
+    let project = required(&["project"])?;
+    let start_date = required(&["start date"])?;
+    let start_time = required(&["start time"])?;
+    let end_date = required(&["end date"])?;
+    let end_time = required(&["end time"])?;
+    let description = column(&["description"]);
+    let zone = column(&["timezone", "time zone", "tz"]);
+    
+    let mut sessions = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in lines {
+        let row = index + 1;
+        let fields = split_csv_line(line);
+        let field = |column: usize| fields.get(column).map(|field| field.trim()).unwrap_or("");
+        let zone = zone.map(field).filter(|zone| !zone.is_empty());
+        let times = toggl_timestamp(field(start_date), field(start_time), zone)
+            .and_then(|start| Ok((start, toggl_timestamp(field(end_date), field(end_time), zone)?)));
+        let (start, end) = match times {
+            Ok(times) => times,
+            Err(e) => {
+                skipped.push((row, e.to_string()));
+                continue;
+            }
+        };
+        match imported_session(row, &start.to_string(), &end.to_string(), description.map(|column| field(column).to_string())) {
+            Ok(session) => {
+                let project = match field(project) {
+                    "" => "No project",
+                    name => name,
+                };
+                sessions.push(ForeignSession { project: project.to_string(), session });
+            }
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    Ok((sessions, skipped))
+}
+
+/// Unix timestamp of a Toggl/Clockify date and time, in the row's zone or the configured one
+fn toggl_timestamp(date: &str, time: &str, zone: Option<&str>) -> Result<i64> {
+    let date = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y"].iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| anyhow::anyhow!("unknown date '{}'", date))?;
+    let time = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"].iter()
+        .find_map(|format| chrono::NaiveTime::parse_from_str(time, format).ok())
+        .ok_or_else(|| anyhow::anyhow!("unknown time '{}'", time))?;
+    let naive = date.and_time(time);
+    match zone {
+        Some(zone) => tz::timestamp_in(&naive, zone),
+        None => tz::timestamp_of(&naive).ok_or_else(|| anyhow::anyhow!("{} does not exist in the local time zone", naive)),
+    }
+}
+
+pub async fn add_note(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    text: &str,
+    force: bool,
+) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let running = api_client.get_time_entries(project_slug).await
+        .is_ok_and(|entries| is_project_running(&entries));
+    
+    let entry = TimeEntry {
+        timestamp: Utc::now().timestamp(),
+        entry_type: "note".to_string(),
+        description: Some(text.to_string()),
+        tags: Vec::new(),
+    };
+
+    match api_client.add_time_entry(project_slug, entry, force).await {
+        Ok(_) => {
+            println!("{} Added note to project '{}': {}", icons::note(), project_slug, text);
+            if running {
+                println!("   {} The session keeps running", icons::timer());
+            } else {
+                println!("   {} No session is running, the note was added to the timeline anyway", icons::hint());
+            }
+            logger.log(&format!("Added note to project '{}': {}", project_slug, text)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to add note: {}", icons::error(), e);
+            logger.log(&format!("Failed to add note for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Split the session containing `at`: the first part ends at `at`, the second starts a second later
+/// (timestamps identify entries, so they can't share one) and keeps the original end, if any.
+pub async fn split_session(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    at: &str,
+    description_first: Option<String>,
+    description_second: Option<String>,
+) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let at = match parse_start_time(at) {
+        Ok(at) => at,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let split = api_client.modify_time_entries(project_slug, |entries| {
+        let mut sorted: Vec<&TimeEntry> = entries.iter().collect();
+        sorted.sort_by_key(|e| e.timestamp);
+        
+        let start = sorted.iter()
+            .filter(|e| e.entry_type == "start" && e.timestamp < at)
+            .max_by_key(|e| e.timestamp)
+            .copied()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No session started before {}", format_local_time(at, "%Y-%m-%d %H:%M:%S")))?;
+        let end = sorted.iter()
+            .find(|e| e.timestamp > start.timestamp && matches!(e.entry_type.as_str(), "start" | "end"))
+            .copied()
+            .cloned();
+        match &end {
+            Some(end) if end.entry_type == "start" || end.timestamp <= at + 1 => {
+                return Err(anyhow::anyhow!("{} is not inside a session", format_local_time(at, "%Y-%m-%d %H:%M:%S")));
+            }
+            _ => {}
+        }
+        let in_pause = sorted.iter()
+            .rev()
+            .find(|e| e.timestamp > start.timestamp && e.timestamp <= at && matches!(e.entry_type.as_str(), "pause" | "resume"))
+            .is_some_and(|e| e.entry_type == "pause");
+        if in_pause {
+            return Err(anyhow::anyhow!("{} is within a pause, split before or after it", format_local_time(at, "%Y-%m-%d %H:%M:%S")));
+        }
+        if entries.iter().any(|e| e.timestamp == at || e.timestamp == at + 1) {
+            return Err(anyhow::anyhow!("Another entry already exists at {}", format_local_time(at, "%Y-%m-%d %H:%M:%S")));
+        }
+        
+        let original_description = end.as_ref().and_then(|end| end.description.clone())
+            .or_else(|| start.description.clone());
+        entries.push(TimeEntry {
+            timestamp: at,
+            entry_type: "end".to_string(),
+            description: description_first.clone().or_else(|| original_description.clone()),
+            tags: end.as_ref().map(|end| end.tags.clone()).unwrap_or_default(),
+        });
+        entries.push(TimeEntry {
+            timestamp: at + 1,
+            entry_type: "start".to_string(),
+            // A running second part keeps its description on the start until it is stopped
+            description: if end.is_none() { description_second.clone() } else { None },
+            tags: start.tags.clone(),
+        });
+        if let (Some(end), Some(description)) = (&end, &description_second) {
+            if let Some(entry) = entries.iter_mut().find(|e| e.timestamp == end.timestamp && e.entry_type == "end") {
+                entry.description = Some(description.clone());
+            }
+        }
+        Ok((start.timestamp, end.map(|end| end.timestamp)))
+    }).await;
+    
+    match split {
+        Ok((start, end)) => {
+            let format = locale::current().datetime_format();
+            println!("{} Split the session of project '{}':", icons::ok(), project_slug);
+            println!("   {} {} {} {}", icons::bullet(), format_local_time(start, &format), icons::arrow(), format_local_time(at, &format));
+            match end {
+                Some(end) => println!("   {} {} {} {}", icons::bullet(), format_local_time(at + 1, &format), icons::arrow(), format_local_time(end, &format)),
+                None => println!("   {} {} {} (running)", icons::bullet(), format_local_time(at + 1, &format), icons::arrow()),
+            }
+            logger.log(&format!("Split session of project '{}' starting {} at {}", project_slug, start, at)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to split session: {}", icons::error(), e);
+            logger.log(&format!("Failed to split session for {} at {}: {}", project_slug, at, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// How far `time merge --at` may be from the gap between two sessions
+const MERGE_WINDOW_SECONDS: i64 = 30 * 60;
+
+/// Join the two sessions around `at`: the end of the first and the start of the second are removed,
+/// so any gap between them counts as worked time
+pub async fn merge_sessions(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, project_slug: &str, at: &str) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let at = match parse_start_time(at) {
+        Ok(at) => at,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // Sessions as the totals pair them, plus the running one, which can be merged into as well
+    let mut sessions: Vec<(i64, Option<i64>)> = reconstruct_sessions(&entries).iter()
+        .map(|session| (session.start, Some(session.end)))
+        .collect();
+    if is_project_running(&entries) {
+        if let Some(start) = last_start(&entries) {
+            sessions.push((start.timestamp, None));
+        }
+    }
+    sessions.sort_by_key(|(start, _)| *start);
+    
+    // The stop/restart pair whose gap is closest to `at`
+    let distance = |gap_start: i64, gap_end: i64| (gap_start - at).max(at - gap_end).max(0);
+    let pair = sessions.windows(2)
+        .filter_map(|pair| Some((pair[0].0, pair[0].1?, pair[1].0, pair[1].1)))
+        .filter(|(_, first_end, second_start, _)| first_end <= second_start)
+        .min_by_key(|(_, first_end, second_start, _)| distance(*first_end, *second_start))
+        .filter(|(_, first_end, second_start, _)| distance(*first_end, *second_start) <= MERGE_WINDOW_SECONDS);
+    let Some((first_start, first_end, second_start, second_end)) = pair else {
+        eprintln!("{} No stop followed by a restart near {} for project '{}'", icons::error(),
+                  format_local_time(at, "%Y-%m-%d %H:%M:%S"), project_slug);
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
+    
+    let format = locale::current().datetime_format();
+    println!("{} Merging two sessions of project '{}':", icons::list(), project_slug);
+    println!("   {} {} {} {}", icons::bullet(), format_local_time(first_start, &format), icons::arrow(), format_local_time(first_end, &format));
+    match second_end {
+        Some(end) => println!("   {} {} {} {}", icons::bullet(), format_local_time(second_start, &format), icons::arrow(), format_local_time(end, &format)),
+        None => println!("   {} {} {} (running)", icons::bullet(), format_local_time(second_start, &format), icons::arrow()),
+    }
+    match second_end {
+        Some(end) => println!("   Merged: {} {} {}", format_local_time(first_start, &format), icons::arrow(), format_local_time(end, &format)),
+        None => println!("   Merged: {} {} (running)", format_local_time(first_start, &format), icons::arrow()),
+    }
+    let gap = second_start - first_end;
+    if gap > 0 {
+        println!("   {}  The {} between them will be counted as worked time", icons::warning(), format_duration(gap, DurationStyle::Short));
+    }
+    println!();
+    if !confirm.ask(logger, "Merge these sessions?").await? {
+        println!("{} Merge cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    let merged = api_client.modify_time_entries(project_slug, |entries| {
+        join_sessions(entries, first_start, first_end, second_start, second_end)
+    }).await;
+    
+    match merged {
+        Ok(_) => {
+            println!("{} Merged the sessions of project '{}'", icons::ok(), project_slug);
+            logger.log(&format!("Merged sessions of project '{}' at {} and {}", project_slug, first_end, second_start)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to merge sessions: {}", icons::error(), e);
+            logger.log(&format!("Failed to merge sessions for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Remove the end at `first_end` and the start at `second_start`, keeping what was written on both
+fn join_sessions(entries: &mut Vec<TimeEntry>, first_start: i64, first_end: i64, second_start: i64, second_end: Option<i64>) -> Result<()> {
+    let start = entries.iter().position(|e| e.timestamp == first_start && e.entry_type == "start");
+    let first = entries.iter().position(|e| e.timestamp == first_end && e.entry_type == "end");
+    let second = entries.iter().position(|e| e.timestamp == second_start && e.entry_type == "start");
+    let (Some(start), Some(first), Some(second)) = (start, first, second) else {
+        return Err(anyhow::anyhow!("The sessions were changed in the meantime"));
+    };
+    
+    // The restart's description and tags (e.g. from `time start -d`) move to the start that remains
+    let second_entry = entries[second].clone();
+    let descriptions: Vec<String> = [entries[start].description.clone(), second_entry.description]
+        .into_iter().flatten().collect();
+    entries[start].description = (!descriptions.is_empty()).then(|| descriptions.join("; "));
+    entries[start].tags = normalize_tags(&[entries[start].tags.clone(), second_entry.tags].concat());
+    
+    let first_entry = entries[first].clone();
+    let last_end = second_end.and_then(|end| entries.iter().position(|e| e.timestamp == end && e.entry_type == "end"));
+    match last_end {
+        // What was done in both parts ends up on the remaining end
+        Some(index) => {
+            let descriptions: Vec<String> = [first_entry.description.clone(), entries[index].description.clone()]
+                .into_iter().flatten().collect();
+            entries[index].description = (!descriptions.is_empty()).then(|| descriptions.join("; "));
+            entries[index].tags = normalize_tags(&[first_entry.tags.clone(), entries[index].tags.clone()].concat());
+        }
+        // Merged into the running session: keep what was written on stop as a note, like resume does
+        None if first_entry.description.is_some() => entries[first].entry_type = "note".to_string(),
+        None => {}
+    }
+    entries.retain(|e| {
+        let joined = (e.timestamp == second_start && e.entry_type == "start") || (e.timestamp == first_end && e.entry_type == "end");
+        !joined
+    });
+    Ok(())
+}
+
+pub async fn show_total(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    options: &TotalOptions,
+) -> Result<()> {
+    logger.log(&format!("Calculated total time for project '{}'", project_slug)).await?;
+    
+    // --rate/--currency win over what is stored with the project
+    let stored = api_client.get_project(project_slug).await.ok();
+    let rate = options.rate.or(stored.as_ref().and_then(|project| project.rate));
+    let currency = options.currency.clone().or(stored.as_ref().and_then(|project| project.currency.clone()));
+    let range = match parse_day_range(options.from.as_deref(), options.to.as_deref()) {
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    // The budget covers the whole project, so a total of one tag or period isn't compared with it
+    let budget_hours = stored.and_then(|project| project.budget_hours)
+        .filter(|_| options.tag.is_none() && range == (None, None));
+    
+    let entries = api_client.get_time_entries(project_slug).await
+        .map(|entries| options.filter(entries, range));
+    let tag_marker = options.tag.as_ref()
+        .map(|tag| format!(" tagged '{}'", tag))
+        .unwrap_or_default()
+        + &day_range_label(range);
+    
+    match entries {
+        Ok(entries) if options.json_lines => {
+            // One JSON object per completed session (NDJSON)
+            for session in reconstruct_sessions(&entries) {
+                println!("{}", serde_json::to_string(&session_json(&session))?);
+            }
+        }
+        Ok(entries) if output::json() => {
+            let total_seconds = calculate_total_time(&entries, options.running_policy);
+            let running_seconds = total_seconds - calculate_total_time(&entries, RunningPolicy::Exclude);
+            let mut json = serde_json::json!({
+                "project": project_slug,
+                "total_seconds": total_seconds,
+                "running": is_project_running(&entries),
+                "running_seconds": running_seconds,
+            });
+            if let Some(tag) = &options.tag {
+                json["tag"] = serde_json::json!(tag);
+            }
+            if range != (None, None) {
+                json["from"] = serde_json::json!(range.0.map(|from| from.to_string()));
+                json["to"] = serde_json::json!(range.1.map(|to| to.to_string()));
+            }
+            if let Some(rounding) = options.rounding {
+                json["rounded_seconds"] = serde_json::json!(calculate_rounded_total(&entries, options.running_policy, rounding));
+                json["rounding_minutes"] = serde_json::json!(rounding.minutes);
+            }
+            if let Some(rate) = rate {
+                let billed_seconds = json["rounded_seconds"].as_i64().unwrap_or(total_seconds);
+                json["billable"] = serde_json::json!((billed_seconds as f64 / 3600.0 * rate * 100.0).round() / 100.0);
+                json["currency"] = serde_json::json!(currency);
+            }
+            if let Some(budget_hours) = budget_hours {
+                let billed_seconds = json["rounded_seconds"].as_i64().unwrap_or(total_seconds);
+                json["budget_hours"] = serde_json::json!(budget_hours);
+                json["budget_used_percent"] = serde_json::json!((budget_used(budget_hours, billed_seconds) * 1000.0).round() / 10.0);
+            }
+            output::print_json(&json)?;
+        }
+        Ok(entries) => {
+            // Data quality warnings go first so the figure below isn't taken at face value
+            for warning in session_warnings(&entries, options.max_session_hours, options.max_unmatched) {
+                println!("{}  {}", icons::warning(), warning);
+            }
+            
+            let raw_seconds = calculate_total_time(&entries, options.running_policy);
+            let running_seconds = raw_seconds - calculate_total_time(&entries, RunningPolicy::Exclude);
+            // Billing increments replace the raw figure, which stays visible below
+            let total_seconds = match options.rounding {
+                Some(rounding) => calculate_rounded_total(&entries, options.running_policy, rounding),
+                None => raw_seconds,
+            };
+            // The full form comes with the decimal one, unless only decimals are wanted
+            let decimal = options.duration_style == DurationStyle::Decimal;
+            let total_text = |seconds: i64| if decimal {
+                format_duration(seconds, DurationStyle::Decimal)
+            } else {
+                format!("{} ({})", format_duration(seconds, options.duration_style), format_duration(seconds, DurationStyle::Decimal))
+            };
+            let running_marker = if running_seconds > 0 {
+                let style = if decimal { DurationStyle::Decimal } else { DurationStyle::Short };
+                format!(" (including {} running)", format_duration(running_seconds, style))
+            } else {
+                String::new()
+            };
+            
+            println!("{} Total time for project '{}'{}: {}{}", icons::report(), 
+                     project_slug, tag_marker, total_text(total_seconds), running_marker);
+            if let Some(rounding) = options.rounding {
+                println!("   {}  {}, raw total: {}", icons::timer(), rounding.describe(), total_text(raw_seconds));
+            }
+            if let Some(rate) = rate {
+                println!("   {} Billable: {} ({} at {})", icons::money(),
+                         format_amount(total_seconds as f64 / 3600.0 * rate, currency.as_deref()),
+                         format_duration(total_seconds, DurationStyle::Decimal), format_rate(Some(rate), currency.as_deref()));
+            }
+            if let Some(budget_hours) = budget_hours {
+                print_budget(budget_hours, total_seconds);
+            }
+            if let Some(day_hours) = options.working_day_hours {
+                if day_hours > 0.0 {
+                    println!("   {} In working days: {} ({}h days)", icons::date(),
+                             format_working_days(total_seconds, day_hours),
+                             locale::current().format_decimal(day_hours, 1));
+                } else {
+                    eprintln!("{} --working-day-hours must be greater than 0", icons::error());
+                    return Err(CommandError::reported(ExitCode::Failure).into());
+                }
+            }
+            if is_project_running(&entries) {
+                match options.running_policy {
+                    RunningPolicy::Exclude => println!("   {} The running session is not counted (drop --exclude-running to include it)", icons::hint()),
+                    RunningPolicy::Include => println!("   {}  Includes the running session up to now", icons::timer()),
+                    RunningPolicy::ClosedAt(ts) => println!("   {}  Includes the running session up to {}", icons::timer(),
+                                                           format_local_time(ts, &locale::current().datetime_format())),
+                }
+            }
+        }
+        Err(e) => {
+            logger.log(&format!("Failed to calculate total time for {}: {}", project_slug, e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to calculate total time: {}", e)));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn show_status(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, project_slug: &str, auto_stop: bool) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    logger.log(&format!("Checked status for project '{}'", project_slug)).await?;
+    
+    match api_client.get_time_entries(project_slug).await {
+        Ok(entries) if output::json() => {
+            let is_running = is_project_running(&entries);
+            let started_at = last_start(&entries)
+                .map(|e| e.timestamp)
+                .filter(|_| is_running);
+            let max_session_hours = config::max_session_hours();
+            output::print_json(&serde_json::json!({
+                "project": project_slug,
+                "running": is_running,
+                "paused_since": paused_since(&entries),
+                "started_at": started_at,
+                "exceeds_max_session_hours": started_at
+                    .is_some_and(|start| (Utc::now().timestamp() - start) as f64 > max_session_hours * 3600.0),
+                "running_seconds": calculate_total_time(&entries, RunningPolicy::Include)
+                    - calculate_total_time(&entries, RunningPolicy::Exclude),
+            }))?;
+        }
+        Ok(entries) => {
+            if check_session_length(api_client, logger, project_slug, &entries, auto_stop).await? {
+                return Ok(());
+            }
+            let is_running = is_project_running(&entries);
+            
+            if let Some(paused_at) = paused_since(&entries) {
+                println!("{}  Project '{}' is paused since {}", icons::pause(), project_slug,
+                         format_local_time(paused_at, &locale::current().datetime_format()));
+            } else if is_running {
+                println!("{} Project '{}' is currently running", icons::running(), project_slug);
+                if let Some(last_start) = last_start(&entries) {
+                    let utc_start_time = DateTime::from_timestamp(last_start.timestamp, 0)
+                        .unwrap_or_else(Utc::now);
+                    let local_start_time = tz::localize(utc_start_time);
+                    // Time of the running session without its pauses
+                    let duration = calculate_total_time(&entries, RunningPolicy::Include)
+                        - calculate_total_time(&entries, RunningPolicy::Exclude);
+                    println!("   Started at: {}", local_start_time.format(&locale::current().datetime_format()));
+                    println!("   Running for: {}", format_duration(duration, DurationStyle::Short));
+                }
+            } else {
+                println!("{} Project '{}' is not currently running", icons::stopped(), project_slug);
+            }
+        }
+        Err(e) => {
+            logger.log(&format!("Failed to check status for {}: {}", project_slug, e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to check status: {}", e)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Hours `time timeline` always shows; sessions outside of them widen the scale
+const TIMELINE_HOURS: (u32, u32) = (8, 20);
+/// Characters per hour in `time timeline`, i.e. 15 minutes each
+const TIMELINE_SLOTS_PER_HOUR: i64 = 4;
+
+/// A day of one project as a bar with filled blocks for worked time, numbered per session
+pub async fn show_timeline(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, project_slug: &str, date: Option<&str>) -> Result<()> {
+    let project_slug = resolve_slug(api_client, logger, confirm, project_slug).await?;
+    let project_slug = project_slug.as_str();
+    let day = match date.map(parse_date).transpose() {
+        Ok(day) => day.unwrap_or_else(tz::today),
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    logger.log(&format!("Showed the timeline of project '{}' on {}", project_slug, day)).await?;
+    
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let day_start = local_day_start(day);
+    let day_end = day.succ_opt().map(local_day_start).unwrap_or(i64::MAX);
+    let now = Utc::now().timestamp();
+    
+    // Sessions as the totals pair them, the running one up to now, cut to the day
+    let mut sessions = reconstruct_sessions(&entries);
+    if is_project_running(&entries) {
+        if let Some(start) = last_start(&entries) {
+            let pauses = paused_since(&entries).map(|paused_at| vec![(paused_at, now)]).unwrap_or_default();
+            sessions.push(Session {
+                start: start.timestamp,
+                end: now,
+                description: start.description.clone(),
+                pauses,
+                tags: start.tags.clone(),
+            });
+        }
+    }
+    let mut sessions: Vec<Session> = sessions.into_iter()
+        .filter(|session| session.start < day_end && session.end > day_start)
+        .map(|session| Session { start: session.start.max(day_start), end: session.end.min(day_end), ..session })
+        .collect();
+    sessions.sort_by_key(|session| session.start);
+    
+    println!("{} {} {} {}", icons::date(), day.format(locale::current().date_format()), icons::dash(), project_slug);
+    if sessions.is_empty() {
+        println!("   Nothing logged on this day");
+        return Ok(());
+    }
+    
+    // Whole hours covering the default range and every session
+    let hour_of = |timestamp: i64| (timestamp - day_start) / 3600;
+    let first_hour = sessions.iter().map(|session| hour_of(session.start)).min().unwrap_or(0).min(TIMELINE_HOURS.0 as i64);
+    let last_hour = sessions.iter().map(|session| hour_of(session.end - 1) + 1).max().unwrap_or(24).max(TIMELINE_HOURS.1 as i64);
+    let slot_seconds = 3600 / TIMELINE_SLOTS_PER_HOUR;
+    let slots = ((last_hour - first_hour) * TIMELINE_SLOTS_PER_HOUR) as usize;
+    let slot_start = |slot: usize| day_start + first_hour * 3600 + slot as i64 * slot_seconds;
+    
+    // Sessions are numbered 1-9, then a-z
+    let marker = |number: usize| char::from_digit(((number + 1) % 36) as u32, 36).unwrap_or('?');
+    
+    // A slot is filled when its middle was worked, so short breaks show up as gaps
+    let mut bar = vec![" "; slots];
+    let mut labels = vec![' '; slots];
+    for (number, session) in sessions.iter().enumerate() {
+        for (slot, cell) in bar.iter_mut().enumerate() {
+            let middle = slot_start(slot) + slot_seconds / 2;
+            let paused = session.pauses.iter().any(|(from, to)| middle >= *from && middle < *to);
+            if middle >= session.start && middle < session.end && !paused {
+                *cell = icons::block();
+            }
+        }
+        let first_slot = ((session.start - slot_start(0)) / slot_seconds).clamp(0, slots as i64 - 1) as usize;
+        labels[first_slot] = marker(number);
+    }
+    
+    let scale: String = (first_hour..last_hour)
+        .map(|hour| format!("{:<width$}", format!("{:02}", hour % 24), width = TIMELINE_SLOTS_PER_HOUR as usize))
+        .collect();
+    println!("   {}", scale);
+    println!("   {}", bar.concat());
+    println!("   {}", labels.iter().collect::<String>().trim_end());
+    println!();
+    
+    for (number, session) in sessions.iter().enumerate() {
+        let (start, end) = (session.start, session.end);
+        let paused: i64 = session.pauses.iter().map(|(from, to)| ((*to).min(end) - (*from).max(start)).max(0)).sum();
+        println!("   {} {} {} {}  {}  {}", marker(number),
+                 format_local_time(start, "%H:%M"), icons::dash(), format_local_time(end, "%H:%M"),
+                 format_duration(end - start - paused, DurationStyle::Short),
+                 session.description.as_deref().unwrap_or(""));
+    }
+    
+    Ok(())
+}
+
+/// Sessions started on `date` (default today) across all projects (`time today`)
+pub async fn show_day(api_client: &impl KvStore, logger: &Logger, date: Option<&str>) -> Result<()> {
+    let day = match date.map(parse_date).transpose() {
+        Ok(day) => day.unwrap_or_else(tz::today),
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    logger.log(&format!("Summed up the time of {}", day)).await?;
+    let day_start = local_day_start(day);
+    let day_end = day.succ_opt().map(local_day_start).unwrap_or(i64::MAX);
+    let on_day = |timestamp: i64| timestamp >= day_start && timestamp < day_end;
+    
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            logger.log(&format!("Failed to get projects: {}", e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to get projects: {}", e)));
+        }
+    };
+    
+    // Completed seconds, running seconds and the running start per project
+    let mut totals: Vec<(&Project, i64, i64, Option<i64>)> = Vec::new();
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::warning(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project.slug, e)).await?;
+                continue;
+            }
+        };
+        let completed: i64 = reconstruct_sessions(&entries).iter()
+            .filter(|session| on_day(session.start))
+            .map(Session::duration)
+            .sum();
+        let running_start = last_start(&entries)
+            .map(|start| start.timestamp)
+            .filter(|start| is_project_running(&entries) && on_day(*start));
+        let running = if running_start.is_some() {
+            calculate_total_time(&entries, RunningPolicy::Include) - calculate_total_time(&entries, RunningPolicy::Exclude)
+        } else {
+            0
+        };
+        if completed > 0 || running_start.is_some() {
+            totals.push((project, completed, running, running_start));
+        }
+    }
+    totals.sort_by_key(|(_, completed, running, _)| Reverse(completed + running));
+    let grand_total: i64 = totals.iter().map(|(_, completed, running, _)| completed + running).sum();
+    
+    if output::json() {
+        let projects: Vec<serde_json::Value> = totals.iter()
+            .map(|(project, completed, running, running_start)| serde_json::json!({
+                "project": project.slug,
+                "total_seconds": completed + running,
+                "running": running_start.is_some(),
+                "running_seconds": running,
+            }))
+            .collect();
+        output::print_json(&serde_json::json!({
+            "date": day.to_string(),
+            "projects": projects,
+            "total_seconds": grand_total,
+        }))?;
+        return Ok(());
+    }
+    
+    if totals.is_empty() {
+        println!("{} Nothing logged on {}", icons::date(), day.format(locale::current().date_format()));
+        return Ok(());
+    }
+    
+    println!("{} {}: {}", icons::date(), day.format(locale::current().date_format()), format_duration(grand_total, DurationStyle::Short));
+    let mut table = Table::new(vec![Align::Left, Align::Left, Align::Right, Align::Left]).truncate(0);
+    for (project, completed, running, running_start) in &totals {
+        let state = match running_start {
+            Some(start) => format!("{} running since {} ({})", icons::running(), format_local_time(*start, "%H:%M"),
+                                   format_duration(*running, DurationStyle::Short)),
+            None => String::new(),
+        };
+        table.row(vec![format!("{} {}", icons::bullet(), project.name), format!("({})", project.slug),
+                       format_duration(completed + running, DurationStyle::Short), state]);
+    }
+    table.print();
+    
+    Ok(())
+}
+
+/// Every project that is running right now, with the time since its last start
+pub async fn show_current(api_client: &impl KvStore, logger: &Logger) -> Result<()> {
+    logger.log("Checked currently running projects").await?;
+    
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            logger.log(&format!("Failed to get projects: {}", e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to get projects: {}", e)));
+        }
+    };
+    
+    let mut running = Vec::new();
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        match entries {
+            Ok(entries) if is_project_running(&entries) => running.push((project, entries)),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::warning(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project.slug, e)).await?;
+            }
+        }
+    }
+    
+    // Time of the running session without its pauses
+    let running_seconds = |entries: &[TimeEntry]| {
+        calculate_total_time(entries, RunningPolicy::Include) - calculate_total_time(entries, RunningPolicy::Exclude)
+    };
+    
+    if output::json() {
+        let json: Vec<serde_json::Value> = running.iter()
+            .map(|(project, entries)| serde_json::json!({
+                "project": project.slug,
+                "name": project.name,
+                "started_at": last_start(entries).map(|e| e.timestamp),
+                "paused_since": paused_since(entries),
+                "running_seconds": running_seconds(entries),
+            }))
+            .collect();
+        output::print_json(&serde_json::Value::Array(json))?;
+        return Ok(());
+    }
+    
+    if running.is_empty() {
+        println!("{} No project is running", icons::stopped());
+        return Ok(());
+    }
+    
+    println!("{} Running projects:", icons::running());
+    let mut table = Table::new(vec![Align::Left, Align::Left, Align::Left, Align::Right]).truncate(0);
+    for (project, entries) in &running {
+        let duration = running_seconds(entries);
+        let state = match paused_since(entries) {
+            Some(paused_at) => format!("{}  paused since {}", icons::pause(), format_local_time(paused_at, "%H:%M")),
+            None => match last_start(entries) {
+                Some(start) => format!("since {}", format_local_time(start.timestamp, &locale::current().datetime_format())),
+                None => String::new(),
+            },
+        };
+        table.row(vec![
+            format!("{} {}", icons::bullet(), project.name),
+            format!("({})", project.slug),
+            state,
+            format_duration(duration, DurationStyle::Short),
+        ]);
+    }
+    table.print();
+    
+    Ok(())
+}
+
+/// Totals of every project, largest first, with a grand total (`time total --all`)
+pub async fn show_total_all(api_client: &impl KvStore, logger: &Logger, options: &TotalOptions) -> Result<()> {
+    logger.log("Calculated total time for all projects").await?;
+    
+    let range = match parse_day_range(options.from.as_deref(), options.to.as_deref()) {
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            logger.log(&format!("Failed to get projects: {}", e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to get projects: {}", e)));
+        }
+    };
+    
+    let mut totals: Vec<(&Project, i64)> = Vec::new();
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::warning(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project.slug, e)).await?;
+                continue;
+            }
+        };
+        let entries = options.filter(entries, range);
+        let seconds = match options.rounding {
+            Some(rounding) => calculate_rounded_total(&entries, options.running_policy, rounding),
+            None => calculate_total_time(&entries, options.running_policy),
+        };
+        totals.push((project, seconds));
+    }
+    totals.sort_by_key(|(_, seconds)| Reverse(*seconds));
+    let grand_total: i64 = totals.iter().map(|(_, seconds)| seconds).sum();
+    
+    if output::json() {
+        let projects: Vec<serde_json::Value> = totals.iter()
+            .map(|(project, seconds)| serde_json::json!({ "project": project.slug, "total_seconds": seconds }))
+            .collect();
+        output::print_json(&serde_json::json!({
+            "projects": projects,
+            "total_seconds": grand_total,
+            "tag": options.tag,
+            "from": range.0.map(|from| from.to_string()),
+            "to": range.1.map(|to| to.to_string()),
+        }))?;
+        return Ok(());
+    }
+    
+    let style = match options.duration_style {
+        DurationStyle::Full => DurationStyle::Short,
+        style => style,
+    };
+    let tag_marker = options.tag.as_ref().map(|tag| format!(" tagged '{}'", tag)).unwrap_or_default() + &day_range_label(range);
+    println!("{} Total time of all projects{}:", icons::report(), tag_marker);
+    let mut table = Table::new(vec![Align::Left, Align::Left, Align::Right]).truncate(0);
+    for (project, seconds) in &totals {
+        table.row(vec![format!("{} {}", icons::bullet(), project.name), format!("({})", project.slug), format_duration(*seconds, style)]);
+    }
+    table.row(vec!["Total".to_string(), String::new(), format_duration(grand_total, style)]);
+    table.print();
+    if let Some(rounding) = options.rounding {
+        println!("   {}  {}", icons::timer(), rounding.describe());
+    }
+    
+    Ok(())
+}
+
+/// Output and calculation options for `time total`
+#[derive(Debug, Clone)]
+pub struct TotalOptions {
+    pub json_lines: bool,
+    pub running_policy: RunningPolicy,
+    pub working_day_hours: Option<f64>,
+    pub max_session_hours: Option<f64>,
+    pub max_unmatched: usize,
+    pub rounding: Option<Rounding>,
+    /// Hourly rate and currency, overriding the ones stored with the project
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    /// Only count sessions carrying this tag
+    pub tag: Option<String>,
+    /// First day whose sessions count (YYYY-MM-DD, local time)
+    pub from: Option<String>,
+    /// Last day whose sessions count, inclusive (YYYY-MM-DD, local time)
+    pub to: Option<String>,
+    pub duration_style: DurationStyle,
+}
+
+impl TotalOptions {
+    /// Sessions considered by the total: those with the tag and starting within --from/--to
+    fn filter(&self, entries: Vec<TimeEntry>, range: (Option<NaiveDate>, Option<NaiveDate>)) -> Vec<TimeEntry> {
+        let entries = match &self.tag {
+            Some(tag) => entries_with_tag(&entries, tag),
+            None => entries,
+        };
+        match range {
+            (None, None) => entries,
+            (from, to) => entries_started_between(&entries, from.map(local_day_start),
+                                                  to.and_then(|to| to.succ_opt()).map(local_day_start)),
+        }
+    }
+}
+
+/// --from and --to as dates, refusing a range that ends before it starts
+fn parse_day_range(from: Option<&str>, to: Option<&str>) -> Result<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let from = from.map(parse_date).transpose()?;
+    let to = to.map(parse_date).transpose()?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(anyhow::anyhow!("--from must not be after --to"));
+        }
+    }
+    Ok((from, to))
+}
+
+/// " from 2024-05-01 to 2024-05-31" for output headers, empty without a range
+fn day_range_label(range: (Option<NaiveDate>, Option<NaiveDate>)) -> String {
+    let date_format = locale::current().date_format();
+    match range {
+        (Some(from), Some(to)) => format!(" from {} to {}", from.format(date_format), to.format(date_format)),
+        (Some(from), None) => format!(" since {}", from.format(date_format)),
+        (None, Some(to)) => format!(" until {}", to.format(date_format)),
+        (None, None) => String::new(),
+    }
+}
+
+/// How `--round` treats a session that doesn't end on an increment
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum RoundMode {
+    /// Always round up to the next increment
+    Up,
+    /// Round to the closest increment, halfway rounds up
+    Nearest,
+}
+
+/// Billing increment applied to each session before summing
+#[derive(Debug, Clone, Copy)]
+pub struct Rounding {
+    pub minutes: u32,
+    pub mode: RoundMode,
+}
+
+impl Rounding {
+    fn apply(self, seconds: i64) -> i64 {
+        round_duration(seconds, self.minutes, self.mode)
+    }
+    
+    fn describe(self) -> String {
+        let mode = match self.mode {
+            RoundMode::Up => "up to",
+            RoundMode::Nearest => "to the nearest",
+        };
+        format!("Sessions rounded {} {}m", mode, self.minutes)
+    }
+}
+
+/// Round a duration to whole increments of `minutes`; 0 minutes leaves it unchanged
+pub fn round_duration(seconds: i64, minutes: u32, mode: RoundMode) -> i64 {
+    let increment = i64::from(minutes) * 60;
+    if increment == 0 || seconds <= 0 {
+        return seconds;
+    }
+    
+    let increments = match mode {
+        RoundMode::Up => (seconds + increment - 1) / increment,
+        RoundMode::Nearest => (seconds + increment / 2) / increment,
+    };
+    increments * increment
+}
+
+/// Find sessions that make a total unreliable: overly long ones and starts/ends without a partner
+fn session_warnings(entries: &[TimeEntry], max_session_hours: Option<f64>, max_unmatched: usize) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut open_start: Option<i64> = None;
+    
+    let mut sorted_entries: Vec<&TimeEntry> = entries.iter()
+        .filter(|e| e.entry_type == "start" || e.entry_type == "end")
+        .collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
+    
+    for entry in sorted_entries {
+        match (entry.entry_type.as_str(), open_start) {
+            ("start", _) => open_start = Some(entry.timestamp),
+            (_, Some(start)) => {
+                let hours = (entry.timestamp - start) as f64 / 3600.0;
+                if let Some(max_hours) = max_session_hours {
+                    if hours > max_hours {
+                        warnings.push(format!("Session from {} lasted {}h (more than {}h)",
+                                              format_local_time(start, &locale::current().datetime_format()),
+                                              locale::current().format_decimal(hours, 1),
+                                              locale::current().format_decimal(max_hours, 1)));
+                    }
+                }
+                open_start = None;
+            }
+            (_, None) => {}
+        }
+    }
+    
+    // The running session is not an anomaly for totals
+    let unmatched = session_anomalies(entries).iter()
+        .filter(|anomaly| !matches!(anomaly, Anomaly::OpenStart { .. }))
+        .count();
+    if unmatched > max_unmatched {
+        warnings.push(format!("Found {} unmatched start/end entries, the total may be wrong (repair them with 'time fix')", unmatched));
+    }
+    
+    warnings
+}
+
+/// A start or end entry that doesn't pair up into a session
+#[derive(Debug, Clone)]
+enum Anomaly {
+    /// A start followed by another start, so the first session never ended
+    DoubleStart { start: TimeEntry, next_start: i64 },
+    /// The last start without an end: still running, or forgotten overnight
+    OpenStart { start: TimeEntry },
+    /// An end without a start before it
+    StrayEnd { end: TimeEntry },
+}
+
+/// Pair starts and ends the same way the totals do and collect everything that doesn't fit
+fn session_anomalies(entries: &[TimeEntry]) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    let mut open_start: Option<&TimeEntry> = None;
+    
+    let mut sorted_entries: Vec<&TimeEntry> = entries.iter()
+        .filter(|e| e.entry_type == "start" || e.entry_type == "end")
+        .collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
+    
+    for entry in sorted_entries {
+        match (entry.entry_type.as_str(), open_start) {
+            ("start", previous) => {
+                if let Some(previous) = previous {
+                    anomalies.push(Anomaly::DoubleStart { start: previous.clone(), next_start: entry.timestamp });
+                }
+                open_start = Some(entry);
+            }
+            (_, Some(_)) => open_start = None,
+            (_, None) => anomalies.push(Anomaly::StrayEnd { end: entry.clone() }),
+        }
+    }
+    
+    if let Some(start) = open_start {
+        anomalies.push(Anomaly::OpenStart { start: start.clone() });
+    }
+    
+    anomalies
+}
+
+/// How durations are written in text output
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DurationStyle {
+    /// "1h 30m 0s"
+    #[default]
+    Full,
+    /// "1h 30m"
+    Short,
+    /// "1.50h" for invoicing; cut, not rounded, to hundredths so 59m 59s stays 0.99h unless --round is used
+    Decimal,
+}
+
+pub fn format_duration(seconds: i64, style: DurationStyle) -> String {
+    match style {
+        DurationStyle::Full => format!("{}h {}m {}s", seconds / 3600, (seconds % 3600) / 60, seconds % 60),
+        DurationStyle::Short => format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60),
+        DurationStyle::Decimal => {
+            let hundredths = seconds * 100 / 3600;
+            format!("{}h", locale::current().format_decimal(hundredths as f64 / 100.0, 2))
+        }
+    }
+}
+
+fn format_working_days(total_seconds: i64, working_day_hours: f64) -> String {
+    let total_hours = total_seconds as f64 / 3600.0;
+    let days = (total_hours / working_day_hours).floor();
+    let remaining_hours = total_hours - days * working_day_hours;
+    
+    let hours_text = locale::current().format_decimal(remaining_hours, 2);
+    let hours_text = hours_text.trim_end_matches('0').trim_end_matches(['.', ',']);
+    format!("{}d {}h", days as i64, if hours_text.is_empty() { "0" } else { hours_text })
+}
+
+/// How a session that was started but not yet stopped counts towards totals
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunningPolicy {
+    /// Only completed start/end pairs are counted
+    Exclude,
+    /// The running session counts up to now (default)
+    Include,
+    /// The running session counts up to the given unix timestamp
+    ClosedAt(i64),
+}
+
+impl RunningPolicy {
+    pub fn from_flags(exclude_running: bool, closed_at: Option<i64>) -> Self {
+        match (exclude_running, closed_at) {
+            (true, _) => RunningPolicy::Exclude,
+            (false, Some(ts)) => RunningPolicy::ClosedAt(ts),
+            (false, None) => RunningPolicy::Include,
+        }
+    }
+}
+
+fn calculate_total_time(entries: &[TimeEntry], running_policy: RunningPolicy) -> i64 {
+    session_durations(entries, running_policy).iter().sum()
+}
+
+/// Total with every session rounded to the billing increment first
+fn calculate_rounded_total(entries: &[TimeEntry], running_policy: RunningPolicy, rounding: Rounding) -> i64 {
+    session_durations(entries, running_policy).into_iter().map(|seconds| rounding.apply(seconds)).sum()
+}
+
+/// Worked seconds of each session, the running one last (depending on the policy)
+fn session_durations(entries: &[TimeEntry], running_policy: RunningPolicy) -> Vec<i64> {
+    let (sessions, running) = pair_sessions(entries);
+    let mut durations: Vec<i64> = sessions.iter().map(Session::duration).collect();
+    
+    if let Some(running) = running {
+        let closed_at = match running_policy {
+            RunningPolicy::Exclude => running.start,
+            RunningPolicy::Include => Utc::now().timestamp(),
+            RunningPolicy::ClosedAt(ts) => ts,
+        };
+        let paused: i64 = running.pauses.iter().map(|(from, to)| to - from).sum();
+        let open_pause = running.pause_start.map(|pause| (closed_at - pause).max(0)).unwrap_or(0);
+        durations.push((closed_at - running.start - paused - open_pause).max(0));
+    }
+    
+    durations
+}
+
+struct Session {
+    start: i64,
+    end: i64,
+    description: Option<String>,
+    /// Pause/resume intervals within the session
+    pauses: Vec<(i64, i64)>,
+    /// Tags of the start and end entry
+    tags: Vec<String>,
+}
+
+impl Session {
+    /// Worked seconds, without pauses
+    fn duration(&self) -> i64 {
+        self.end - self.start - self.pauses.iter().map(|(from, to)| to - from).sum::<i64>()
+    }
+    
+    /// The intervals actually worked, i.e. the session with its pauses cut out
+    fn worked_intervals(&self) -> Vec<(i64, i64)> {
+        let mut intervals = Vec::new();
+        let mut current = self.start;
+        for (from, to) in &self.pauses {
+            intervals.push((current, *from));
+            current = *to;
+        }
+        intervals.push((current, self.end));
+        intervals.retain(|(from, to)| to > from);
+        intervals
+    }
+}
+
+/// Tags as " [dev, meeting]", or nothing for an untagged session
+fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", tags.join(", "))
+    }
+}
+
+/// Trimmed tags without empty or repeated ones (compared case-insensitively)
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !normalized.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Only the entries of sessions whose start entry is tagged with `tag`
+fn entries_with_tag(entries: &[TimeEntry], tag: &str) -> Vec<TimeEntry> {
+    entries_of_sessions(entries, |session| {
+        session.first().is_some_and(|start| start.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    })
+}
+
+/// Only the entries of sessions that started at or after `from` and before `to`, including the running one
+fn entries_started_between(entries: &[TimeEntry], from: Option<i64>, to: Option<i64>) -> Vec<TimeEntry> {
+    entries_of_sessions(entries, |session| session.first().is_some_and(|start| {
+        from.is_none_or(|from| start.timestamp >= from) && to.is_none_or(|to| start.timestamp < to)
+    }))
+}
+
+/// The entries of every session (from its start up to its end, or the running one) that `keep` accepts
+fn entries_of_sessions(entries: &[TimeEntry], keep: impl Fn(&[&TimeEntry]) -> bool) -> Vec<TimeEntry> {
+    let mut sorted_entries: Vec<&TimeEntry> = entries.iter().collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
+    
+    let mut kept = Vec::new();
+    let mut session: Vec<&TimeEntry> = Vec::new();
+    for entry in sorted_entries {
+        match entry.entry_type.as_str() {
+            // A start replaces an unfinished one, as in the totals
+            "start" => session = vec![entry],
+            "end" if !session.is_empty() => {
+                session.push(entry);
+                if keep(&session) {
+                    kept.extend(session.iter().map(|e| (*e).clone()));
+                }
+                session.clear();
+            }
+            _ if !session.is_empty() => session.push(entry),
+            _ => {}
+        }
+    }
+    
+    if !session.is_empty() && keep(&session) {
+        kept.extend(session.iter().map(|e| (*e).clone()));
+    }
+    
+    kept
+}
+
+fn reconstruct_sessions(entries: &[TimeEntry]) -> Vec<Session> {
+    pair_sessions(entries).0
+}
+
+/// The completed sessions plus the running one, closed where the policy says (left out for Exclude)
+fn sessions_with_running(entries: &[TimeEntry], running_policy: RunningPolicy) -> Vec<Session> {
+    let (mut sessions, running) = pair_sessions(entries);
+    let Some(running) = running else {
+        return sessions;
+    };
+    let closed_at = match running_policy {
+        RunningPolicy::Exclude => return sessions,
+        RunningPolicy::Include => Utc::now().timestamp(),
+        RunningPolicy::ClosedAt(ts) => ts,
+    };
+    if closed_at <= running.start {
+        return sessions;
+    }
+    
+    // Pauses count up to the close, an open one ends there
+    let mut pauses: Vec<(i64, i64)> = running.pauses.iter()
+        .filter(|(from, _)| *from < closed_at)
+        .map(|(from, to)| (*from, (*to).min(closed_at)))
+        .collect();
+    if let Some(pause) = running.pause_start.filter(|pause| *pause < closed_at) {
+        pauses.push((pause, closed_at));
+    }
+    sessions.push(Session {
+        start: running.start,
+        end: closed_at,
+        description: running.description,
+        pauses,
+        tags: running.tags,
+    });
+    sessions
+}
+
+/// A session that was started but not yet stopped
+struct RunningSession {
+    start: i64,
+    description: Option<String>,
+    /// Pause/resume intervals so far
+    pauses: Vec<(i64, i64)>,
+    /// Start of a pause that hasn't been resumed
+    pause_start: Option<i64>,
+    tags: Vec<String>,
+}
+
+/// Completed sessions and the running one, paired from start/pause/resume/end entries
+fn pair_sessions(entries: &[TimeEntry]) -> (Vec<Session>, Option<RunningSession>) {
+    let mut sessions = Vec::new();
+    let mut open_start: Option<&TimeEntry> = None;
+    let mut pauses = Vec::new();
+    let mut pause_start: Option<i64> = None;
+    
+    // Sort entries by timestamp
+    let mut sorted_entries: Vec<&TimeEntry> = entries.iter().collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
+    
+    for entry in sorted_entries {
+        match entry.entry_type.as_str() {
+            "start" => {
+                open_start = Some(entry);
+                pauses = Vec::new();
+                pause_start = None;
+            }
+            "pause" if open_start.is_some() && pause_start.is_none() => {
+                pause_start = Some(entry.timestamp);
+            }
+            "resume" => {
+                // A resume without a pause is ignored
+                if let Some(pause) = pause_start.take() {
+                    pauses.push((pause, entry.timestamp));
+                }
+            }
+            "end" => {
+                // An end without a preceding start is ignored
+                if let Some(start) = open_start.take() {
+                    // Stopping while paused ends the session at the pause
+                    if let Some(pause) = pause_start.take() {
+                        pauses.push((pause, entry.timestamp));
+                    }
+                    let description = session_description(start, entry);
+                    sessions.push(Session {
+                        start: start.timestamp,
+                        end: entry.timestamp,
+                        description,
+                        pauses: std::mem::take(&mut pauses),
+                        tags: normalize_tags(&[start.tags.clone(), entry.tags.clone()].concat()),
+                    });
+                }
+            }
+            _ => {} // Ignore unknown types
+        }
+    }
+    
+    // A start without an end is the running session
+    let running = open_start.map(|start| RunningSession {
+        start: start.timestamp,
+        description: start.description.clone().filter(|d| !d.trim().is_empty()),
+        pauses,
+        pause_start,
+        tags: normalize_tags(&start.tags),
+    });
+    (sessions, running)
+}
+
+/// What a session was about: the "what was done" of its end entry, else what was given on start
+fn session_description(start: &TimeEntry, end: &TimeEntry) -> Option<String> {
+    let given = |entry: &TimeEntry| entry.description.clone().filter(|d| !d.trim().is_empty());
+    given(end).or_else(|| given(start))
+}
+
+fn session_json(session: &Session) -> serde_json::Value {
+    serde_json::json!({
+        "start": session.start,
+        "end": session.end,
+        "duration": session.duration(),
+        "description": session.description,
+        "tags": session.tags,
+    })
+}
+
+/// Options for `time report`
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub matrix: bool,
+    pub csv: bool,
+    /// Paste-ready markdown: a table and the session descriptions per period for every project
+    pub markdown: bool,
+    pub group_by: Option<GroupBy>,
+    pub rounding: Option<Rounding>,
+    /// Show hours as decimals ("1.50h") in the tables
+    pub decimal: bool,
+    pub running_policy: RunningPolicy,
+}
+
+/// Output of `time report`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    /// Aligned text tables
+    Table,
+    /// Comma-separated values with decimal hours
+    Csv,
+    /// A heading, table and description bullets per project, for pasting into chats or issues
+    Markdown,
+}
+
+/// Period that report columns and rows are bucketed into
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GroupBy {
+    Day,
+    Week,
+    Month,
+}
+
+impl GroupBy {
+    /// First day of the period containing `date` (weeks start on Monday)
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => date,
+            GroupBy::Week => date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64),
+            GroupBy::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+    
+    fn label(self, bucket: NaiveDate, csv: bool) -> String {
+        match self {
+            // CSV keeps plain ISO dates so spreadsheets parse them
+            GroupBy::Day if csv => bucket.format("%Y-%m-%d").to_string(),
+            GroupBy::Day => bucket.format(locale::current().date_format()).to_string(),
+            GroupBy::Week => bucket.format("%G-W%V").to_string(),
+            GroupBy::Month => bucket.format("%Y-%m").to_string(),
+        }
+    }
+    
+    /// Default first day when --from is not given: 7 days, 4 weeks or 3 months
+    fn default_from(self, to: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => to - chrono::Duration::days(6),
+            GroupBy::Week => self.bucket_start(to) - chrono::Duration::weeks(3),
+            GroupBy::Month => to.with_day(1)
+                .and_then(|first| first.checked_sub_months(chrono::Months::new(2)))
+                .unwrap_or(to),
+        }
+    }
+}
+
+pub async fn show_report(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: Option<&str>,
+    options: &ReportOptions,
+) -> Result<()> {
+    logger.log(&format!("Generating time report for {}", project_slug.unwrap_or("all projects"))).await?;
+    
+    // Default to the last 7 days including today (or 4 weeks / 3 months when grouping)
+    let group_by = options.group_by.unwrap_or(GroupBy::Day);
+    let today = tz::today();
+    let range = options.to.as_deref().map(parse_date).transpose()
+        .and_then(|to| {
+            let to = to.unwrap_or(today);
+            let from = options.from.as_deref().map(parse_date).transpose()?
+                .unwrap_or_else(|| group_by.default_from(to));
+            Ok((from, to))
+        });
+    let (from, to) = match range {
+        Ok((from, to)) if from <= to => (from, to),
+        Ok(_) => {
+            eprintln!("{} --from must not be after --to", icons::error());
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+        Err(e) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let projects = match project_slug {
+        Some(slug) => api_client.get_project(slug).await.map(|project| vec![project]),
+        None => api_client.get_projects().await,
+    };
+    let projects = match projects {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
+            logger.log(&format!("Failed to get projects for report: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // Seconds per project and period, sessions split at local midnight
+    let mut rows: Vec<(String, BTreeMap<NaiveDate, i64>)> = Vec::new();
+    let mut raw_total = 0i64;
+    // Seconds per tag; a session with several tags counts towards each of them
+    let mut tag_totals: BTreeMap<String, i64> = BTreeMap::new();
+    // Names and session descriptions per project and period, for --format markdown
+    let mut names: Vec<&str> = Vec::new();
+    let mut descriptions: Vec<BTreeMap<NaiveDate, Vec<String>>> = Vec::new();
+    // The report still shows the other projects, the first failure decides the exit code
+    let mut failure = None;
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::error(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project.slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+                continue;
+            }
+        };
+        
+        let mut days = BTreeMap::new();
+        let mut project_descriptions: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+        for session in sessions_with_running(&entries, options.running_policy) {
+            // Rounding applies to the part of a session in each period, e.g. both halves across midnight
+            let mut session_days: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+            for (start, end) in session.worked_intervals() {
+                for (date, seconds) in split_by_day(start, end) {
+                    if date >= from && date <= to {
+                        *session_days.entry(group_by.bucket_start(date)).or_insert(0) += seconds;
+                    }
+                }
+            }
+            // Listed under the period the session started in, once per period
+            if let (Some(bucket), Some(description)) = (session_days.keys().next(), &session.description) {
+                let listed = project_descriptions.entry(*bucket).or_default();
+                if !listed.contains(description) {
+                    listed.push(description.clone());
+                }
+            }
+            for (bucket, seconds) in session_days {
+                raw_total += seconds;
+                let seconds = options.rounding.map_or(seconds, |rounding| rounding.apply(seconds));
+                *days.entry(bucket).or_insert(0) += seconds;
+                if session.tags.is_empty() {
+                    *tag_totals.entry("(untagged)".to_string()).or_insert(0) += seconds;
+                }
+                for tag in &session.tags {
+                    *tag_totals.entry(tag.to_lowercase()).or_insert(0) += seconds;
+                }
+            }
+        }
+        rows.push((project.slug.clone(), days));
+        names.push(&project.name);
+        descriptions.push(project_descriptions);
+    }
+    
+    let mut buckets: Vec<NaiveDate> = Vec::new();
+    let mut date = from;
+    while date <= to {
+        let bucket = group_by.bucket_start(date);
+        if buckets.last() != Some(&bucket) {
+            buckets.push(bucket);
+        }
+        date = match date.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    
+    if options.markdown {
+        print_report_markdown(&rows, &names, &descriptions, (from, to), group_by, options.decimal);
+        return failure.map_or(Ok(()), Err);
+    } else if options.matrix {
+        print_report_matrix(&rows, &buckets, (from, to), group_by, options.csv);
+    } else if options.group_by.is_some() {
+        print_report_grouped(&rows, &buckets, (from, to), group_by, options);
+    } else {
+        print_report_totals(&rows, (from, to), options);
+    }
+    
+    if let Some(rounding) = options.rounding.filter(|_| !options.csv) {
+        println!("{}  {}, raw total: {}", icons::timer(), rounding.describe(), report_duration(raw_total, options.decimal));
+    }
+    
+    // Only worth a breakdown once something is tagged
+    if !options.csv && tag_totals.keys().any(|tag| tag != "(untagged)") {
+        println!();
+        println!("{} By tag:", icons::bookmark());
+        let mut table = Table::new(vec![Align::Left, Align::Right]).truncate(0);
+        for (tag, seconds) in &tag_totals {
+            table.row(vec![tag.clone(), report_duration(*seconds, options.decimal)]);
+        }
+        table.print();
+    }
+    
+    failure.map_or(Ok(()), Err)
+}
+
+/// Split a session into the seconds it covers on each local calendar day
+fn split_by_day(start: i64, end: i64) -> Vec<(NaiveDate, i64)> {
+    let mut parts = Vec::new();
+    let mut current = start;
+    
+    while current < end {
+        let date = tz::localize(DateTime::from_timestamp(current, 0).unwrap_or_else(Utc::now))
+            .date_naive();
+        let next_midnight = date.succ_opt()
+            .and_then(|next| tz::timestamp_of(&next.and_hms_opt(0, 0, 0)?))
+            .filter(|ts| *ts > current)
+            .unwrap_or(end);
+        let part_end = next_midnight.min(end);
+        
+        parts.push((date, part_end - current));
+        current = part_end;
+    }
+    
+    parts
+}
+
+fn print_report_totals(rows: &[(String, BTreeMap<NaiveDate, i64>)], range: (NaiveDate, NaiveDate), options: &ReportOptions) {
+    let grand_total: i64 = rows.iter().map(|(_, days)| days.values().sum::<i64>()).sum();
+    
+    if options.csv {
+        println!("project,hours");
+        for (slug, days) in rows {
+            println!("{},{:.2}", csv_field(slug), days.values().sum::<i64>() as f64 / 3600.0);
+        }
+        println!("total,{:.2}", grand_total as f64 / 3600.0);
+        return;
+    }
+    
+    print_report_header(range);
+    let mut table = Table::new(vec![Align::Left, Align::Right]).truncate(0);
+    for (slug, days) in rows {
+        table.row(vec![slug.clone(), report_duration(days.values().sum(), options.decimal)]);
+    }
+    table.row(vec!["Total".to_string(), report_duration(grand_total, options.decimal)]);
+    table.print();
+}
+
+/// One row per day, week or month with the time of all reported projects combined
+fn print_report_grouped(
+    rows: &[(String, BTreeMap<NaiveDate, i64>)],
+    buckets: &[NaiveDate],
+    range: (NaiveDate, NaiveDate),
+    group_by: GroupBy,
+    options: &ReportOptions,
+) {
+    let bucket_total = |bucket: &NaiveDate| -> i64 {
+        rows.iter().map(|(_, days)| days.get(bucket).copied().unwrap_or(0)).sum()
+    };
+    let grand_total: i64 = buckets.iter().map(bucket_total).sum();
+    
+    if options.csv {
+        println!("period,hours");
+        for bucket in buckets {
+            println!("{},{:.2}", group_by.label(*bucket, true), bucket_total(bucket) as f64 / 3600.0);
+        }
+        println!("total,{:.2}", grand_total as f64 / 3600.0);
+        return;
+    }
+    
+    print_report_header(range);
+    let mut table = Table::new(vec![Align::Left, Align::Right]);
+    for bucket in buckets {
+        table.row(vec![group_by.label(*bucket, false), report_duration(bucket_total(bucket), options.decimal)]);
+    }
+    table.row(vec!["Total".to_string(), report_duration(grand_total, options.decimal)]);
+    table.print();
+}
+
+/// A section per project with a table of the periods worked and what was done in each,
+/// followed by the grand total
+fn print_report_markdown(
+    rows: &[(String, BTreeMap<NaiveDate, i64>)],
+    names: &[&str],
+    descriptions: &[BTreeMap<NaiveDate, Vec<String>>],
+    (from, to): (NaiveDate, NaiveDate),
+    group_by: GroupBy,
+    decimal: bool,
+) {
+    let duration = |seconds: i64| format_duration(seconds, if decimal { DurationStyle::Decimal } else { DurationStyle::Short });
+    let period = match group_by {
+        GroupBy::Day => "Day",
+        GroupBy::Week => "Week",
+        GroupBy::Month => "Month",
+    };
+    let date_format = locale::current().date_format();
+    println!("_Time report {} {} {}_", from.format(date_format), icons::dash(), to.format(date_format));
+    
+    let mut grand_total = 0;
+    for (((slug, days), name), project_descriptions) in rows.iter().zip(names).zip(descriptions) {
+        let total: i64 = days.values().sum();
+        if total == 0 {
+            continue;
+        }
+        grand_total += total;
+        
+        println!();
+        println!("## {}", if name.is_empty() { slug.as_str() } else { name });
+        println!();
+        println!("| {} | Time |", period);
+        println!("|---|---:|");
+        for (bucket, seconds) in days.iter().filter(|(_, seconds)| **seconds > 0) {
+            println!("| {} | {} |", group_by.label(*bucket, false), duration(*seconds));
+        }
+        println!("| **Total** | **{}** |", duration(total));
+        
+        for (bucket, done) in project_descriptions {
+            println!();
+            println!("**{}**", group_by.label(*bucket, false));
+            for description in done {
+                println!("- {}", description.replace('\n', " "));
+            }
+        }
+    }
+    
+    println!();
+    println!("**Total: {}**", duration(grand_total));
+}
+
+fn report_duration(seconds: i64, decimal: bool) -> String {
+    if decimal {
+        format_duration(seconds, DurationStyle::Decimal)
+    } else {
+        // Padded minutes keep the column aligned
+        format!("{}h {:>2}m", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
+
+fn print_report_matrix(
+    rows: &[(String, BTreeMap<NaiveDate, i64>)],
+    dates: &[NaiveDate],
+    range: (NaiveDate, NaiveDate),
+    group_by: GroupBy,
+    csv: bool,
+) {
+    let day_total = |date: &NaiveDate| -> i64 {
+        rows.iter().map(|(_, days)| days.get(date).copied().unwrap_or(0)).sum()
+    };
+    let grand_total: i64 = dates.iter().map(day_total).sum();
+    
+    if csv {
+        // Plain ISO dates and decimal points so spreadsheets parse the values
+        let hours = |seconds: i64| format!("{:.2}", seconds as f64 / 3600.0);
+        let header: Vec<String> = dates.iter().map(|d| group_by.label(*d, true)).collect();
+        println!("project,{},total", header.join(","));
+        for (slug, days) in rows {
+            let cells: Vec<String> = dates.iter().map(|d| hours(days.get(d).copied().unwrap_or(0))).collect();
+            println!("{},{},{}", csv_field(slug), cells.join(","), hours(days.values().sum()));
+        }
+        let totals: Vec<String> = dates.iter().map(|d| hours(day_total(d))).collect();
+        println!("total,{},{}", totals.join(","), hours(grand_total));
+        return;
+    }
+    
+    let hours = |seconds: i64| {
+        if seconds == 0 {
+            "-".to_string()
+        } else {
+            locale::current().format_decimal(seconds as f64 / 3600.0, 2)
+        }
+    };
+    let mut aligns = vec![Align::Right; dates.len() + 2];
+    aligns[0] = Align::Left;
+    let mut table = Table::new(aligns).truncate(0);
+    
+    let mut header = vec!["Project".to_string()];
+    header.extend(dates.iter().map(|d| group_by.label(*d, false)));
+    header.push("Total".to_string());
+    table.row(header);
+    
+    for (slug, days) in rows {
+        let mut row = vec![slug.clone()];
+        row.extend(dates.iter().map(|d| hours(days.get(d).copied().unwrap_or(0))));
+        row.push(hours(days.values().sum()));
+        table.row(row);
+    }
+    
+    let mut totals = vec!["Total".to_string()];
+    totals.extend(dates.iter().map(|d| hours(day_total(d))));
+    totals.push(hours(grand_total));
+    table.row(totals);
+    
+    print_report_header(range);
+    table.print();
+}
+
+fn print_report_header((from, to): (NaiveDate, NaiveDate)) {
+    let date_format = locale::current().date_format();
+    println!("{} Time report {} {} {}", icons::report(), from.format(date_format), icons::dash(), to.format(date_format));
+    println!();
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
     }
+}
 
-    Ok(())
+fn parse_date(input: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}'. Use the format YYYY-MM-DD", input))
 }
 
-pub async fn end_tracking(
-    api_client: &ApiClient,
-    logger: &Logger,
-    project_slug: &str,
-    description: String,
-) -> Result<()> {
-    // Check current status before stopping
-    let project_display = get_project_display_name(api_client, project_slug).await;
-    match api_client.get_time_entries(project_slug).await {
-        Ok(entries) => {
-            if entries.is_empty() {
-                eprintln!("❌ No time entries found for project {}!", project_display);
-                eprintln!("   💡 Use 'timetracker start {}' to start tracking first", project_slug);
-                logger.log(&format!("Attempted to stop project with no entries: {}", project_slug)).await?;
-                return Ok(());
-            }
-            
-            if !is_project_running(&entries) {
-                eprintln!("❌ Project {} is not currently running!", project_display);
-                eprintln!("   💡 Use 'timetracker start {}' to start tracking first", project_slug);
-                logger.log(&format!("Attempted to stop already stopped project: {}", project_slug)).await?;
-                return Ok(());
-            }
-        }
-        Err(e) => {
-            eprintln!("❌ Failed to check project status: {}", e);
-            logger.log(&format!("Failed to check status before stopping {}: {}", project_slug, e)).await?;
-            return Ok(());
+/// Parse a point in time: ISO-8601 with offset, or local "YYYY-MM-DD HH:MM[:SS]" (a "T" separator works too)
+fn parse_datetime(input: &str) -> Result<i64> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(datetime.timestamp());
+    }
+    
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return tz::timestamp_of(&naive)
+                .ok_or_else(|| anyhow::anyhow!("Invalid local time '{}'", input));
         }
     }
-
-    let timestamp = Utc::now().timestamp();
     
-    let entry = TimeEntry {
-        timestamp,
-        entry_type: "end".to_string(),
-        description: Some(description.clone()),
-    };
+    Err(anyhow::anyhow!("Invalid time '{}'. Use YYYY-MM-DD HH:MM or ISO-8601 (2024-01-31T09:00:00+01:00)", input))
+}
 
-    match api_client.add_time_entry(project_slug, entry).await {
-        Ok(_) => {
-            println!("⏹️  Stopped tracking time for project {}", project_display);
-            println!("   What was done: {}", description);
-            let log_msg = format!("Stopped tracking time for project '{}' with description: {}", project_slug, description);
-            logger.log(&log_msg).await?;
-        }
-        Err(e) => {
-            eprintln!("❌ Failed to stop tracking: {}", e);
-            logger.log(&format!("Failed to stop tracking for {}: {}", project_slug, e)).await?;
+/// Parse a duration like "20m", "2h" or "1h30m" into seconds
+fn parse_duration(input: &str) -> Result<i64> {
+    let invalid = || anyhow::anyhow!("Invalid duration '{}'. Use e.g. 20m, 2h or 1h30m", input);
+    let mut total = 0i64;
+    let mut number = String::new();
+    
+    for c in input.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
         }
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        total += amount * match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return Err(invalid()),
+        };
+        number.clear();
+    }
+    
+    if !number.is_empty() || total == 0 {
+        return Err(invalid());
     }
+    Ok(total)
+}
 
-    Ok(())
+/// Parse a start time: "HH:MM" means today, anything else goes through parse_datetime
+fn parse_start_time(input: &str) -> Result<i64> {
+    if let Ok(time) = chrono::NaiveTime::parse_from_str(input, "%H:%M") {
+        let today = tz::today().and_time(time);
+        return tz::timestamp_of(&today)
+            .ok_or_else(|| anyhow::anyhow!("Invalid local time '{}'", input));
+    }
+    parse_datetime(input)
 }
 
-pub async fn list_projects(api_client: &ApiClient, logger: &Logger) -> Result<()> {
-    logger.log("Listed all projects").await?;
-    
-    match api_client.get_projects().await {
-        Ok(projects) => {
-            if projects.is_empty() {
-                println!("📋 No projects found");
-            } else {
-                println!("📋 Projects:");
-                for project in projects {
-                    println!("  • {} ({}) - {}", project.name, project.slug, project.description);
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("❌ Failed to list projects: {}", e);
-            logger.log(&format!("Failed to list projects: {}", e)).await?;
+/// All projects that currently have a running session
+async fn running_projects(api_client: &impl KvStore) -> Result<Vec<Project>> {
+    let projects = api_client.get_projects().await?;
+    let entries = api_client.get_time_entries_of(&projects).await;
+    let mut running = Vec::new();
+    for (project, entries) in projects.into_iter().zip(entries) {
+        if is_project_running(&entries?) {
+            running.push(project);
         }
     }
+    Ok(running)
+}
 
-    Ok(())
+/// Unix timestamp of local midnight at the start of `date`
+fn local_day_start(date: NaiveDate) -> i64 {
+    let midnight = date.and_time(chrono::NaiveTime::MIN);
+    tz::timestamp_of(&midnight)
+        // Midnight can fall into a DST gap in a few time zones
+        .unwrap_or_else(|| midnight.and_utc().timestamp())
 }
 
-pub async fn list_times(api_client: &ApiClient, logger: &Logger, project_slug: &str) -> Result<()> {
-    logger.log(&format!("Listed times for project '{}'", project_slug)).await?;
+fn is_project_running(entries: &[TimeEntry]) -> bool {
+    // The most recent start or end decides; notes, pauses and unknown types are skipped
+    entries.iter()
+        .filter(|e| e.entry_type == "start" || e.entry_type == "end")
+        .max_by_key(|e| e.timestamp)
+        .is_some_and(|e| e.entry_type == "start")
+}
+
+/// When the running session is paused, the time the pause began
+fn paused_since(entries: &[TimeEntry]) -> Option<i64> {
+    let mut sorted_entries: Vec<_> = entries.iter()
+        .filter(|e| matches!(e.entry_type.as_str(), "start" | "end" | "pause" | "resume"))
+        .collect();
+    sorted_entries.sort_by_key(|e| e.timestamp);
     
-    match api_client.get_time_entries(project_slug).await {
-        Ok(entries) => {
-            if entries.is_empty() {
-                println!("⏱️  No time entries found for project '{}'", project_slug);
-            } else {
-                println!("⏱️  Time entries for project '{}':", project_slug);
-                for entry in entries {
-                    let utc_datetime = DateTime::from_timestamp(entry.timestamp, 0)
-                        .unwrap_or_else(|| Utc::now());
-                    let local_datetime = utc_datetime.with_timezone(&Local);
-                    let type_icon = if entry.entry_type == "start" { "▶️" } else { "⏹️" };
-                    
-                    print!("  {} {} {} [ts:{}]", 
-                           type_icon, 
-                           entry.entry_type.to_uppercase(), 
-                           local_datetime.format("%Y-%m-%d %H:%M:%S %Z"),
-                           entry.timestamp);
-                    if let Some(desc) = &entry.description {
-                        print!(" - {}", desc);
-                    }
-                    println!();
-                }
-                println!("");
-                println!("💡 To delete a specific entry: timetracker delete times {} --timestamp <ts>", project_slug);
-            }
+    sorted_entries.last()
+        .filter(|e| e.entry_type == "pause")
+        .map(|e| e.timestamp)
+}
+
+/// File format written by `export`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// The raw stored values, pretty printed
+    Json,
+    /// One row per session (start, end, duration_seconds, description)
+    Csv,
+    /// A single calendar file with one event per session
+    Ical,
+}
+
+/// Reverse the newest journaled action (`time undo`), refusing when the data changed since
+pub async fn undo_last(api_client: &impl KvStore, logger: &Logger) -> Result<()> {
+    let action = match journal::last() {
+        Ok(Some(action)) => action,
+        Ok(None) => {
+            println!("{} Nothing to undo", icons::hint());
+            return Ok(());
         }
         Err(e) => {
-            eprintln!("❌ Failed to list times: {}", e);
-            logger.log(&format!("Failed to list times for {}: {}", project_slug, e)).await?;
+            let path = journal::path().map(|path| path.display().to_string()).unwrap_or_default();
+            eprintln!("{} Failed to read the undo journal {}: {}", icons::error(), path, e);
+            logger.log(&format!("Failed to read the undo journal {}: {}", path, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // Check every key before writing any, so an action is undone completely or not at all
+    for change in &action.changes {
+        if let Err(e) = check_undo(api_client, change).await {
+            eprintln!("{} Can't undo 'timetracker {}': {}", icons::error(), action.command, e);
+            eprintln!("   {} It was changed again since; use 'time edit' or 'time delete' instead", icons::hint());
+            logger.log(&format!("Refused to undo '{}': {}", action.command, e)).await?;
+            return Err(exit_code::reported(&e));
         }
     }
-
+    
+    println!("{} Undoing 'timetracker {}' from {}:", icons::undo(), action.command,
+             format_local_time(action.at, &locale::current().datetime_format()));
+    for change in action.changes.iter().rev() {
+        for line in describe_undo(change) {
+            println!("   {} {}", icons::bullet(), line);
+        }
+        if let Err(e) = apply_undo(api_client, change).await {
+            eprintln!("{} Failed to undo '{}': {}", icons::error(), change.key(), e);
+            logger.log(&format!("Failed to undo '{}' at {}: {}", action.command, change.key(), e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    journal::pop()?;
+    
+    println!("{} Undone", icons::ok());
+    logger.log(&format!("Undid '{}'", action.command)).await?;
     Ok(())
 }
 
-pub async fn show_total(api_client: &ApiClient, logger: &Logger, project_slug: &str) -> Result<()> {
-    logger.log(&format!("Calculated total time for project '{}'", project_slug)).await?;
-    
-    match api_client.get_time_entries(project_slug).await {
-        Ok(entries) => {
-            let total_seconds = calculate_total_time(&entries);
-            let hours = total_seconds / 3600;
-            let minutes = (total_seconds % 3600) / 60;
-            let seconds = total_seconds % 60;
-            
-            println!("📊 Total time for project '{}': {}h {}m {}s", 
-                     project_slug, hours, minutes, seconds);
+/// The decoded value of `key`, or None if it doesn't exist
+async fn current_value(api_client: &impl KvStore, key: &str) -> Result<Option<serde_json::Value>> {
+    Ok(api_client.get_key_raw(key).await?.map(decode_value))
+}
+
+async fn check_undo(api_client: &impl KvStore, change: &Change) -> Result<()> {
+    let current = current_value(api_client, change.key()).await?;
+    match change {
+        Change::List { key, removed, added } => {
+            let mut items = match current {
+                Some(serde_json::Value::Array(items)) => items,
+                _ => return Err(anyhow::anyhow!("'{}' no longer exists", key)),
+            };
+            for item in added {
+                let index = items.iter().position(|current| current == item)
+                    .ok_or_else(|| anyhow::anyhow!("{} is no longer there", describe_item(key, item)))?;
+                items.remove(index);
+            }
+            // Putting back an item that was added again since would duplicate it
+            if let Some((_, item)) = removed.iter().find(|(_, item)| items.contains(item)) {
+                return Err(anyhow::anyhow!("{} was added again", describe_item(key, item)));
+            }
         }
-        Err(e) => {
-            eprintln!("❌ Failed to calculate total time: {}", e);
-            logger.log(&format!("Failed to calculate total time for {}: {}", project_slug, e)).await?;
+        Change::Value { key, after, .. } => {
+            if current != *after {
+                return Err(anyhow::anyhow!("'{}' has changed", key));
+            }
         }
     }
-
     Ok(())
 }
 
-pub async fn show_status(api_client: &ApiClient, logger: &Logger, project_slug: &str) -> Result<()> {
-    logger.log(&format!("Checked status for project '{}'", project_slug)).await?;
-    
-    match api_client.get_time_entries(project_slug).await {
-        Ok(entries) => {
-            let is_running = is_project_running(&entries);
-            
-            if is_running {
-                println!("🟢 Project '{}' is currently running", project_slug);
-                // Find the last start entry
-                if let Some(last_start) = entries.iter()
-                    .filter(|e| e.entry_type == "start")
-                    .max_by_key(|e| e.timestamp) {
-                    let utc_start_time = DateTime::from_timestamp(last_start.timestamp, 0)
-                        .unwrap_or_else(|| Utc::now());
-                    let local_start_time = utc_start_time.with_timezone(&Local);
-                    let duration = Utc::now().timestamp() - last_start.timestamp;
-                    let hours = duration / 3600;
-                    let minutes = (duration % 3600) / 60;
-                    println!("   Started at: {}", local_start_time.format("%Y-%m-%d %H:%M:%S %Z"));
-                    println!("   Running for: {}h {}m", hours, minutes);
+async fn apply_undo(api_client: &impl KvStore, change: &Change) -> Result<()> {
+    match change {
+        Change::List { key, removed, added } => {
+            api_client.modify_key(key, |items: &mut Vec<serde_json::Value>| {
+                for item in added {
+                    let index = items.iter().position(|current| current == item)
+                        .ok_or_else(|| anyhow::anyhow!("{} is no longer there", describe_item(key, item)))?;
+                    items.remove(index);
                 }
-            } else {
-                println!("🔴 Project '{}' is not currently running", project_slug);
-            }
+                // Ascending, so every item lands where it was
+                for (index, item) in removed {
+                    items.insert((*index).min(items.len()), item.clone());
+                }
+                Ok(())
+            }).await
         }
-        Err(e) => {
-            eprintln!("❌ Failed to check status: {}", e);
-            logger.log(&format!("Failed to check status for {}: {}", project_slug, e)).await?;
+        Change::Value { key, before: Some(before), after: Some(_) } => api_client.update_key(key, before.clone()).await,
+        Change::Value { key, before: Some(before), after: None } => api_client.set_key(key, before.clone()).await,
+        Change::Value { key, before: None, after: Some(_) } => api_client.delete_key(key).await,
+        Change::Value { before: None, after: None, .. } => Ok(()),
+    }
+}
+
+/// What undoing `change` does, one line per project or entry
+fn describe_undo(change: &Change) -> Vec<String> {
+    let owner = change.key().strip_prefix("projects/")
+        .map(|slug| format!(" of '{}'", slug))
+        .unwrap_or_default();
+    match change {
+        Change::List { key, removed, added } => added.iter()
+            .map(|item| format!("Remove {}{}", describe_item(key, item), owner))
+            .chain(removed.iter().map(|(_, item)| format!("Restore {}{}", describe_item(key, item), owner)))
+            .collect(),
+        Change::Value { key, before, after } => {
+            let count = |value: &serde_json::Value| value.as_array().map(|items| items.len()).unwrap_or(1);
+            let line = match (before, after) {
+                (Some(before), None) => format!("Restore '{}' ({} items)", key, count(before)),
+                (None, Some(after)) => format!("Remove '{}' ({} items)", key, count(after)),
+                _ => format!("Restore the previous value of '{}'", key),
+            };
+            vec![line]
         }
     }
+}
 
-    Ok(())
+/// A stored project or time entry in words, e.g. "start entry at 2024-01-31 09:00:00"
+fn describe_item(key: &str, item: &serde_json::Value) -> String {
+    if key == "projects" {
+        if let Ok(project) = serde_json::from_value::<Project>(item.clone()) {
+            return format!("project '{}'", project.slug);
+        }
+    } else if let Ok(entry) = serde_json::from_value::<TimeEntry>(item.clone()) {
+        return format!("{} entry at {}", entry.entry_type, format_local_time(entry.timestamp, "%Y-%m-%d %H:%M:%S"));
+    }
+    item.to_string()
 }
 
-fn calculate_total_time(entries: &[TimeEntry]) -> i64 {
-    let mut total = 0i64;
-    let mut start_time: Option<i64> = None;
+/// Replay time entries recorded while offline, in the order they were recorded
+pub async fn sync_queue(api_client: &impl KvStore, logger: &Logger) -> Result<()> {
+    let Some(queue) = OfflineQueue::from_env() else {
+        eprintln!("{} No offline queue configured, set OFFLINE_QUEUE to a file path", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     
-    // Sort entries by timestamp
-    let mut sorted_entries = entries.to_vec();
-    sorted_entries.sort_by_key(|e| e.timestamp);
+    let mut queued = match queue.load() {
+        Ok(queued) => queued,
+        Err(e) => {
+            eprintln!("{} Failed to read offline queue {}: {}", icons::error(), queue.path().display(), e);
+            logger.log(&format!("Failed to read offline queue {}: {}", queue.path().display(), e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
     
-    for entry in sorted_entries {
-        match entry.entry_type.as_str() {
-            "start" => {
-                start_time = Some(entry.timestamp);
+    let pending = queued.iter().filter(|item| !item.done).count();
+    if pending == 0 {
+        println!("{} Offline queue is empty, nothing to sync", icons::ok());
+        return Ok(());
+    }
+    
+    println!("{} Replaying {} queued entr{}...", icons::sync(), pending, if pending == 1 { "y" } else { "ies" });
+    
+    // One write per project keeps its entries together; a failed project stays queued as a whole
+    let mut projects: Vec<String> = Vec::new();
+    for item in queued.iter().filter(|item| !item.done) {
+        if !projects.contains(&item.project) {
+            projects.push(item.project.clone());
+        }
+    }
+    
+    let mut replayed = 0;
+    let mut skipped = 0;
+    let mut failure = None;
+    for project in &projects {
+        let entries: Vec<TimeEntry> = queued.iter()
+            .filter(|item| !item.done && &item.project == project)
+            .map(|item| item.entry.clone())
+            .collect();
+        
+        // Stop, pause and resume were queued without looking at the project, so check them now
+        let written = match api_client.get_time_entries(project).await {
+            Ok(existing) => {
+                let (valid, invalid) = plan_replay(&existing, &entries);
+                if valid.is_empty() {
+                    Ok((valid, invalid))
+                } else {
+                    api_client.add_time_entries(project, valid.clone()).await.map(|_| (valid, invalid))
+                }
             }
-            "end" => {
-                if let Some(start) = start_time {
-                    total += entry.timestamp - start;
-                    start_time = None;
+            Err(e) => Err(e),
+        };
+        
+        match written {
+            Ok((valid, invalid)) => {
+                for entry in &valid {
+                    let time = format_local_time(entry.timestamp, "%Y-%m-%d %H:%M:%S");
+                    println!("   {} {} {} at {}", entry_type_icon(&entry.entry_type), project, entry.entry_type, time);
                 }
+                for (entry, reason) in &invalid {
+                    let time = format_local_time(entry.timestamp, "%Y-%m-%d %H:%M:%S");
+                    eprintln!("   {}  Skipped {} {} at {}: {}", icons::warning(), project, entry.entry_type, time, reason);
+                    logger.log(&format!("Skipped queued {} entry for {} at {}: {}", entry.entry_type, project, entry.timestamp, reason)).await?;
+                }
+                skipped += invalid.len();
+                logger.log(&format!("Replayed {} queued entries for {}", valid.len(), project)).await?;
+                // Skipped entries would be skipped again on the next run, so they are done as well
+                for item in queued.iter_mut().filter(|item| !item.done && &item.project == project) {
+                    item.done = true;
+                }
+                replayed += valid.len();
+            }
+            Err(e) => {
+                eprintln!("   {} {} ({} entr{}): {}", icons::error(), project, entries.len(),
+                          if entries.len() == 1 { "y" } else { "ies" }, e);
+                logger.log(&format!("Failed to replay {} queued entries for {}: {}", entries.len(), project, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
             }
-            _ => {} // Ignore unknown types
         }
     }
     
-    total
-}
-
-fn is_project_running(entries: &[TimeEntry]) -> bool {
-    if entries.is_empty() {
-        return false;
+    // Mark replayed entries as done right away, failed ones stay for the next run
+    if let Err(e) = queue.save(&queued) {
+        eprintln!("{} Failed to update offline queue {}: {}", icons::error(), queue.path().display(), e);
+        logger.log(&format!("Failed to update offline queue {}: {}", queue.path().display(), e)).await?;
+        return Err(exit_code::reported(&e));
     }
     
-    // Sort entries by timestamp and get the last one
-    let mut sorted_entries = entries.to_vec();
-    sorted_entries.sort_by_key(|e| e.timestamp);
-    
-    if let Some(last_entry) = sorted_entries.last() {
-        last_entry.entry_type == "start"
+    let remaining = pending - replayed - skipped;
+    if remaining == 0 && skipped == 0 {
+        println!("{} Synced {} queued entr{}", icons::ok(), replayed, if replayed == 1 { "y" } else { "ies" });
+    } else if remaining == 0 {
+        println!("{}  Synced {} of {} queued entries, {} skipped", icons::warning(), replayed, pending, skipped);
+        failure.get_or_insert(CommandError::reported(ExitCode::Failure).into());
     } else {
-        false
+        println!("{}  Synced {} of {} queued entries, {} kept for the next 'timetracker sync'", icons::warning(), replayed, pending, remaining);
+    }
+    
+    failure.map_or(Ok(()), Err)
+}
+
+/// Queued entries that are valid on top of a project's `entries`, checked in order like the commands
+/// would have online, and the others with the reason they are left out
+fn plan_replay(entries: &[TimeEntry], queued: &[TimeEntry]) -> (Vec<TimeEntry>, Vec<(TimeEntry, &'static str)>) {
+    let mut current = entries.to_vec();
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for entry in queued {
+        let latest = current.iter().map(|e| e.timestamp).max();
+        let running = is_project_running(&current);
+        let paused = paused_since(&current).is_some();
+        let problem = if current.iter().any(|e| e.timestamp == entry.timestamp && e.entry_type == entry.entry_type) {
+            // The write went through before the connection dropped
+            Some("already recorded")
+        } else if latest.is_some_and(|latest| entry.timestamp <= latest) {
+            Some("not after the project's latest entry")
+        } else {
+            match entry.entry_type.as_str() {
+                "start" if running => Some("the project was already running"),
+                "end" | "pause" if !running => Some("the project was not running"),
+                "pause" if paused => Some("the project was already paused"),
+                "resume" if !paused => Some("the project was not paused"),
+                _ => None,
+            }
+        };
+        match problem {
+            Some(reason) => invalid.push((entry.clone(), reason)),
+            None => {
+                current.push(entry.clone());
+                valid.push(entry.clone());
+            }
+        }
     }
+    (valid, invalid)
+}
+
+/// Options for `export`
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub output_dir: String,
+    pub filename_template: String,
+    pub summary_only: bool,
+    pub since: Option<i64>,
+    pub format: ExportFormat,
+    /// Whether the running session is in the summary totals and the session rows
+    pub running_policy: RunningPolicy,
 }
 
-pub async fn export_data(api_client: &ApiClient, logger: &Logger, output_dir: &str, filename_template: &str) -> Result<()> {
+pub async fn export_data(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    options: &ExportOptions,
+) -> Result<()> {
+    let output_dir = options.output_dir.as_str();
+    let since = options.since;
+    let csv = options.format == ExportFormat::Csv;
+    // The default template ends in .json, which would be misleading for CSV and calendar files
+    let filename_template = match (options.filename_template.strip_suffix(".json"), options.format) {
+        (Some(stem), ExportFormat::Csv) => format!("{}.csv", stem),
+        (Some(stem), ExportFormat::Ical) => format!("{}.ics", stem),
+        _ => options.filename_template.clone(),
+    };
+    let filename_template = filename_template.as_str();
     logger.log(&format!("Exporting data to directory: {} with template: {}", output_dir, filename_template)).await?;
     
     // Create output directory if it doesn't exist
@@ -315,10 +3932,23 @@ pub async fn export_data(api_client: &ApiClient, logger: &Logger, output_dir: &s
     // Generate export timestamp for filename templates
     let export_timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
     
+    if options.summary_only {
+        return export_summary(api_client, logger, output_dir, filename_template, &export_timestamp, csv, options.running_policy).await;
+    }
+    if options.format == ExportFormat::Ical {
+        return export_ical(api_client, logger, output_dir, filename_template, &export_timestamp, since, options.running_policy).await;
+    }
+    
     match api_client.get_all_keys().await {
-        Ok(keys) => {
+        Ok(mut keys) => {
+            // Same order on every run, whatever order the backend lists the keys in
+            keys.sort_by(|a, b| a.key.cmp(&b.key));
             let keys_count = keys.len();
-            println!("📁 Exporting {} keys to {} using template '{}'", keys_count, output_dir, filename_template);
+            println!("{} Exporting {} keys to {} using template '{}'", icons::project(), keys_count, output_dir, filename_template);
+            
+            // Latest entry timestamp seen, used as the marker for the next incremental export
+            let mut latest_timestamp: Option<i64> = None;
+            let mut failure = None;
             
             for key_data in keys {
                 // Generate filename from template
@@ -329,29 +3959,253 @@ pub async fn export_data(api_client: &ApiClient, logger: &Logger, output_dir: &s
                 );
                 let file_path = Path::new(output_dir).join(filename);
                 
-                // Parse the value (which is stored as a JSON string) and pretty print it
-                let value = match serde_json::from_str::<serde_json::Value>(&key_data.value.as_str().unwrap_or("{}")) {
-                    Ok(parsed) => parsed,
-                    Err(_) => key_data.value.clone(),
-                };
+                // Parse the value if it is stored as a JSON string, otherwise keep it as it is
+                let mut value = decode_value(key_data.value);
+                
+                // Incremental export: only keep time entries added since the marker
+                if let (Some(since), true) = (since, key_data.key.starts_with("projects/")) {
+                    if let Some(entries) = value.as_array_mut() {
+                        entries.retain(|entry| entry["timestamp"].as_i64().is_some_and(|ts| ts >= since));
+                        if entries.is_empty() {
+                            println!("  {}  Skipped: {} (no new entries)", icons::skip(), key_data.key);
+                            continue;
+                        }
+                        let key_latest = entries.iter().filter_map(|entry| entry["timestamp"].as_i64()).max();
+                        latest_timestamp = latest_timestamp.max(key_latest);
+                    }
+                }
                 
-                let pretty_json = serde_json::to_string_pretty(&value)?;
-                fs::write(&file_path, pretty_json)?;
+                if csv {
+                    // A value that doesn't decode would otherwise give an empty CSV that looks like a valid export
+                    let content = match value_to_csv(&key_data.key, &value, options.running_policy) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            eprintln!("  {} Failed: {}: {}", icons::error(), key_data.key, e);
+                            logger.log(&format!("Failed to export {} as CSV: {}", key_data.key, e)).await?;
+                            failure.get_or_insert(exit_code::reported(&e));
+                            continue;
+                        }
+                    };
+                    fs::write(&file_path, content)?;
+                } else {
+                    let pretty_json = serde_json::to_string_pretty(&value)?;
+                    fs::write(&file_path, pretty_json)?;
+                }
                 
-                println!("  ✅ Exported: {} -> {}", key_data.key, file_path.display());
+                println!("  {} Exported: {} -> {}", icons::ok(), key_data.key, file_path.display());
+            }
+            
+            if since.is_some() {
+                match latest_timestamp {
+                    Some(latest) => {
+                        println!("{} Latest exported entry: {} (use --since {} for the next export)", icons::bookmark(), latest, latest + 1);
+                    }
+                    None => println!("{} No new entries since the marker", icons::bookmark()),
+                }
             }
             
+            if let Some(error) = failure {
+                return Err(error);
+            }
             logger.log(&format!("Successfully exported {} keys", keys_count)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to export data: {}", e);
+            eprintln!("{} Failed to export data: {}", icons::error(), e);
             logger.log(&format!("Failed to export data: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+async fn export_summary(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    output_dir: &str,
+    filename_template: &str,
+    export_timestamp: &str,
+    csv: bool,
+    running_policy: RunningPolicy,
+) -> Result<()> {
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("{} Failed to export summary: {}", icons::error(), e);
+            logger.log(&format!("Failed to export summary: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    // Aggregates only - one object per project instead of every entry
+    let mut summaries = Vec::new();
+    // A project that can't be read is left out rather than exported with zero time
+    let mut failure = None;
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}', left out of the summary: {}", icons::error(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {} for the summary: {}", project.slug, e)).await?;
+                failure.get_or_insert(exit_code::reported(&e));
+                continue;
+            }
+        };
+        summaries.push(serde_json::json!({
+            "slug": project.slug,
+            "name": project.name,
+            "total_seconds": calculate_total_time(&entries, running_policy),
+            "entry_count": entries.len(),
+            "first_entry": entries.iter().map(|e| e.timestamp).min(),
+            "last_entry": entries.iter().map(|e| e.timestamp).max(),
+        }));
+    }
+    
+    let filename = generate_filename_from_template(filename_template, "summary", export_timestamp);
+    let file_path = Path::new(output_dir).join(filename);
+    if csv {
+        let mut content = String::from("slug,name,total_seconds,entry_count,first_entry,last_entry\n");
+        for summary in &summaries {
+            content.push_str(&format!("{},{},{},{},{},{}\n",
+                                      csv_field(summary["slug"].as_str().unwrap_or_default()),
+                                      csv_field(summary["name"].as_str().unwrap_or_default()),
+                                      summary["total_seconds"],
+                                      summary["entry_count"],
+                                      summary["first_entry"].as_i64().map(|ts| ts.to_string()).unwrap_or_default(),
+                                      summary["last_entry"].as_i64().map(|ts| ts.to_string()).unwrap_or_default()));
+        }
+        fs::write(&file_path, content)?;
+    } else {
+        fs::write(&file_path, serde_json::to_string_pretty(&summaries)?)?;
+    }
+    
+    println!("{} Exported summary of {} projects -> {}", icons::project(), summaries.len(), file_path.display());
+    logger.log(&format!("Successfully exported summary of {} projects", summaries.len())).await?;
+    
+    failure.map_or(Ok(()), Err)
+}
+
+/// All sessions as one calendar, so logged time shows up in any calendar app
+async fn export_ical(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    output_dir: &str,
+    filename_template: &str,
+    export_timestamp: &str,
+    since: Option<i64>,
+    running_policy: RunningPolicy,
+) -> Result<()> {
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("{} Failed to export calendar: {}", icons::error(), e);
+            logger.log(&format!("Failed to export calendar: {}", e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let utc_format = "%Y%m%dT%H%M%SZ";
+    let stamp = Utc::now().format(utc_format).to_string();
+    let utc = |ts: i64| DateTime::from_timestamp(ts, 0).map(|dt| dt.format(utc_format).to_string()).unwrap_or_default();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//signalwerk//timetracker//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let mut events = 0;
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{}  Skipped {}: {}", icons::warning(), project.slug, e);
+                continue;
+            }
+        };
+        for session in sessions_with_running(&entries, running_policy) {
+            if since.is_some_and(|since| session.start < since) {
+                continue;
+            }
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}@timetracker", project.slug, session.start),
+                format!("DTSTAMP:{}", stamp),
+                format!("DTSTART:{}", utc(session.start)),
+                format!("DTEND:{}", utc(session.end)),
+                format!("SUMMARY:{}", ical_text(session.description.as_deref().unwrap_or(&project.name))),
+                format!("CATEGORIES:{}", ical_text(&project.name)),
+                "END:VEVENT".to_string(),
+            ]);
+            events += 1;
         }
     }
+    lines.push("END:VCALENDAR".to_string());
+    
+    let content: String = lines.iter().map(|line| ical_fold(line)).collect();
+    let filename = generate_filename_from_template(filename_template, "calendar", export_timestamp);
+    let file_path = Path::new(output_dir).join(filename);
+    fs::write(&file_path, content)?;
+    
+    println!("{} Exported {} sessions of {} projects -> {}", icons::date(), events, projects.len(), file_path.display());
+    logger.log(&format!("Successfully exported {} sessions as calendar", events)).await?;
     
     Ok(())
 }
 
+/// Escape text for an iCalendar property value
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// A content line ending in CRLF, folded so no line is longer than 75 bytes
+fn ical_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// CSV for one stored key: sessions for a project's entries, one row per project for the project list
+fn value_to_csv(key: &str, value: &serde_json::Value, running_policy: RunningPolicy) -> Result<String> {
+    if key.starts_with("projects/") {
+        let entries: Vec<TimeEntry> = serde_json::from_value(value.clone())
+            .map_err(|e| anyhow::anyhow!("'{}' is not a list of time entries: {}", key, e))?;
+        let datetime_format = "%Y-%m-%d %H:%M:%S";
+        let mut content = String::from("start,end,duration_seconds,description\n");
+        for session in sessions_with_running(&entries, running_policy) {
+            content.push_str(&format!("{},{},{},{}\n",
+                                      format_local_time(session.start, datetime_format),
+                                      format_local_time(session.end, datetime_format),
+                                      session.duration(),
+                                      csv_field(session.description.as_deref().unwrap_or_default())));
+        }
+        Ok(content)
+    } else {
+        let projects: Vec<Project> = serde_json::from_value(value.clone())
+            .map_err(|e| anyhow::anyhow!("'{}' is not a list of projects: {}", key, e))?;
+        let mut content = String::from("slug,name,description\n");
+        for project in projects {
+            content.push_str(&format!("{},{},{}\n",
+                                      csv_field(&project.slug),
+                                      csv_field(&project.name),
+                                      csv_field(&project.description)));
+        }
+        Ok(content)
+    }
+}
+
 fn generate_filename_from_template(template: &str, key: &str, timestamp: &str) -> String {
     let mut filename = template.to_string();
     
@@ -387,45 +4241,29 @@ fn extract_project_name_from_key(key: &str) -> String {
     "general".to_string()
 }
 
-pub async fn delete_project(api_client: &ApiClient, logger: &Logger, slug: &str) -> Result<()> {
-    logger.log(&format!("Deleting project: {}", slug)).await?;
-    
-    match api_client.delete_project(slug).await {
-        Ok(_) => {
-            println!("🗑️  Successfully deleted project '{}' and all its time entries", slug);
-            logger.log(&format!("Successfully deleted project: {}", slug)).await?;
-        }
-        Err(e) => {
-            eprintln!("❌ Failed to delete project: {}", e);
-            logger.log(&format!("Failed to delete project {}: {}", slug, e)).await?;
-        }
-    }
-    
-    Ok(())
-}
-
-pub async fn delete_project_with_selection(api_client: &ApiClient, logger: &Logger) -> Result<()> {
+pub async fn delete_project_with_selection(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, dry_run: bool) -> Result<()> {
     logger.log("Deleting project with selection").await?;
     
     // Get all projects
-    let projects = match api_client.get_projects().await {
+    let mut projects = match api_client.get_projects().await {
         Ok(projects) => {
             if projects.is_empty() {
-                println!("❌ No projects found");
-                return Ok(());
+                println!("{} No projects found", icons::error());
+                return Err(CommandError::reported(ExitCode::NotFound).into());
             }
             projects
         }
         Err(e) => {
-            eprintln!("❌ Failed to get projects: {}", e);
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
             logger.log(&format!("Failed to get projects: {}", e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
         }
     };
+    sort_by_recent_use(api_client, &mut projects).await;
     
-    // Display all projects
-    println!("🗑️  Select a project to delete:");
-    println!("");
+    // Display all projects, most recently used first
+    println!("{}  Select a project to delete:", icons::delete());
+    println!();
     for (index, project) in projects.iter().enumerate() {
         println!("  {}. {} ({}) - {}", 
                  index + 1, 
@@ -434,8 +4272,8 @@ pub async fn delete_project_with_selection(api_client: &ApiClient, logger: &Logg
                  project.description);
     }
     
-    println!("");
-    print!("Select project to delete (1-{}), or 'q' to quit: ", projects.len());
+    println!();
+    print!("Select project to delete (1-{} or slug), or 'q' to quit: ", projects.len());
     io::stdout().flush()?;
     
     // Get user selection
@@ -444,168 +4282,451 @@ pub async fn delete_project_with_selection(api_client: &ApiClient, logger: &Logg
     let input = input.trim();
     
     if input.eq_ignore_ascii_case("q") {
-        println!("❌ Delete cancelled");
-        return Ok(());
+        println!("{} Delete cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
-    let selection: usize = match input.parse::<usize>() {
-        Ok(num) if num >= 1 && num <= projects.len() => num - 1,
-        _ => {
-            println!("❌ Invalid selection. Please enter a number between 1 and {}", projects.len());
-            return Ok(());
+    let selection: usize = match parse_project_selection(input, &projects) {
+        Some(index) => index,
+        None => {
+            println!("{} Invalid selection. Please enter a number between 1 and {} or a project slug", icons::error(), projects.len());
+            return Err(CommandError::reported(ExitCode::Failure).into());
         }
     };
     
     let selected_project = &projects[selection];
+    if dry_run {
+        return print_project_dry_run(api_client, logger, selected_project).await;
+    }
     
     // Show selected project and strong warning
-    println!("");
-    println!("🚨 ⚠️  DANGER WARNING ⚠️  🚨");
-    println!("═══════════════════════════════════════════════════════════════");
+    println!();
+    println!("{} {}  DANGER WARNING {}  {}", icons::danger(), icons::warning(), icons::warning(), icons::danger());
+    println!("{}", icons::rule());
     println!("  You are about to DELETE the entire project:");
-    println!("  📁 Name: {}", selected_project.name);
-    println!("  📁 Slug: {}", selected_project.slug);
-    println!("  📁 Description: {}", selected_project.description);
-    println!("");
-    println!("  ❌ This action CANNOT be undone!");
-    println!("  ❌ ALL time entries will be permanently lost!");
-    println!("  ❌ ALL tracking history will be permanently lost!");
-    println!("");
-    println!("  💡 Consider using 'timetracker export' to backup data first");
-    println!("═══════════════════════════════════════════════════════════════");
-    println!("");
-    
-    print!("Are you absolutely sure? Type 'DELETE PROJECT' to confirm: ");
-    io::stdout().flush()?;
-    
-    let mut confirmation = String::new();
-    io::stdin().read_line(&mut confirmation)?;
-    let confirmation = confirmation.trim();
+    println!("  {} Name: {}", icons::project(), selected_project.name);
+    println!("  {} Slug: {}", icons::project(), selected_project.slug);
+    println!("  {} Description: {}", icons::project(), selected_project.description);
+    println!();
+    println!("  {} This action CANNOT be undone!", icons::error());
+    println!("  {} ALL time entries will be permanently lost!", icons::error());
+    println!("  {} ALL tracking history will be permanently lost!", icons::error());
+    println!();
+    println!("  {} Consider using 'timetracker export' to backup data first", icons::hint());
+    println!("{}", icons::rule());
+    println!();
     
-    if confirmation != "DELETE PROJECT" {
-        println!("❌ Operation cancelled. Project is safe.");
-        return Ok(());
+    if !confirm.typed(logger, "DELETE PROJECT").await? {
+        println!("{} Operation cancelled. Project is safe.", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
-    println!("⚠️  Proceeding with project deletion...");
+    println!("{}  Proceeding with project deletion...", icons::warning());
     
     // Delete the project via API
     match api_client.delete_project(&selected_project.slug).await {
         Ok(_) => {
-            println!("🗑️  Successfully deleted project '{}' and all its time entries", selected_project.slug);
+            println!("{}  Successfully deleted project '{}' and all its time entries", icons::delete(), selected_project.slug);
             logger.log(&format!("Successfully deleted project: {} ({})", selected_project.slug, selected_project.name)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to delete project: {}", e);
+            eprintln!("{} Failed to delete project: {}", icons::error(), e);
             logger.log(&format!("Failed to delete project {}: {}", selected_project.slug, e)).await?;
+            return Err(exit_code::reported(&e));
         }
     }
     
     Ok(())
 }
 
+/// Options for `time delete`
+#[derive(Debug, Clone, Default)]
+pub struct DeleteOptions {
+    pub timestamp: Option<i64>,
+    pub all: bool,
+    pub older_than: Option<String>,
+    /// Inclusive date range (YYYY-MM-DD), either end may be left open
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub force: bool,
+    /// Do all lookups and report what would go, without deleting
+    pub dry_run: bool,
+}
+
 pub async fn delete_times(
-    api_client: &ApiClient, 
+    api_client: &impl KvStore, 
     logger: &Logger, 
+    confirm: Confirm,
     project_slug: &str, 
-    timestamp: Option<i64>, 
-    all: bool
+    options: &DeleteOptions,
 ) -> Result<()> {
-    if let Some(ts) = timestamp {
+    let force = options.force;
+    if let Some(ts) = options.timestamp {
+        if options.dry_run {
+            let matching = match api_client.get_time_entries(project_slug).await {
+                Ok(entries) => entries.iter().filter(|e| e.timestamp == ts).count(),
+                Err(e) => {
+                    eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+                    logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+                    return Err(exit_code::reported(&e));
+                }
+            };
+            println!("{} Dry run: would delete {} time entries at {} for project '{}'", icons::hint(), matching,
+                     format_local_time(ts, "%Y-%m-%d %H:%M:%S %Z"), project_slug);
+            logger.log(&format!("Dry run: would delete {} time entries at {} for project: {}", matching, ts, project_slug)).await?;
+            return Ok(());
+        }
+        
         // Delete specific timestamp - this is safer
         logger.log(&format!("Deleting time entry with timestamp {} for project: {}", ts, project_slug)).await?;
         
         match api_client.delete_time_entry_by_timestamp(project_slug, ts).await {
             Ok(_) => {
                 let utc_datetime = DateTime::from_timestamp(ts, 0)
-                    .unwrap_or_else(|| Utc::now());
-                let local_datetime = utc_datetime.with_timezone(&Local);
-                println!("🗑️  Successfully deleted time entry from {} for project '{}'", 
+                    .unwrap_or_else(Utc::now);
+                let local_datetime = tz::localize(utc_datetime);
+                println!("{}  Successfully deleted time entry from {} for project '{}'", icons::delete(), 
                          local_datetime.format("%Y-%m-%d %H:%M:%S %Z"), project_slug);
                 logger.log(&format!("Successfully deleted time entry {} for project: {}", ts, project_slug)).await?;
             }
             Err(e) => {
-                eprintln!("❌ Failed to delete time entry: {}", e);
+                eprintln!("{} Failed to delete time entry: {}", icons::error(), e);
                 logger.log(&format!("Failed to delete time entry {} for {}: {}", ts, project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        }
+    } else if options.from.is_some() || options.to.is_some() {
+        delete_times_in_range(api_client, logger, confirm, project_slug, options).await?;
+    } else if let Some(older_than) = &options.older_than {
+        // Delete everything before a cutoff - for data retention
+        let cutoff = match parse_cutoff(older_than) {
+            Ok(cutoff) => cutoff,
+            Err(e) => {
+                eprintln!("{} {}", icons::error(), e);
+                return Err(exit_code::reported(&e));
+            }
+        };
+        
+        let entries = match api_client.get_time_entries(project_slug).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        };
+        
+        let old_entries: Vec<&TimeEntry> = entries.iter()
+            .filter(|e| e.timestamp < cutoff)
+            .collect();
+        if old_entries.is_empty() {
+            println!("{} No time entries older than {} for project '{}'", icons::ok(),
+                     format_local_time(cutoff, "%Y-%m-%d %H:%M:%S %Z"), project_slug);
+            return Ok(());
+        }
+        
+        // Refuse to orphan the start of a running session unless forced
+        if is_project_running(&entries) {
+            let running_start = entries.iter()
+                .filter(|e| e.entry_type == "start")
+                .map(|e| e.timestamp)
+                .max();
+            if running_start.is_some_and(|start| start < cutoff) {
+                eprintln!("{}  The running session started before the cutoff and its start would be deleted.", icons::warning());
+                if !force {
+                    eprintln!("   {} Stop the session first, or use --force to delete anyway", icons::hint());
+                    return Err(CommandError::reported(ExitCode::Failure).into());
+                }
+            }
+        }
+        
+        let oldest = old_entries.iter().map(|e| e.timestamp).min().unwrap_or(cutoff);
+        let newest = old_entries.iter().map(|e| e.timestamp).max().unwrap_or(cutoff);
+        if options.dry_run {
+            println!("{} Dry run: would delete {} time entries from {} to {} for project '{}'", icons::hint(), old_entries.len(),
+                     format_local_time(oldest, "%Y-%m-%d %H:%M:%S %Z"), format_local_time(newest, "%Y-%m-%d %H:%M:%S %Z"), project_slug);
+            logger.log(&format!("Dry run: would delete {} time entries older than {} for project: {}", old_entries.len(), cutoff, project_slug)).await?;
+            return Ok(());
+        }
+        show_older_than_warning_and_confirm(logger, confirm, project_slug, old_entries.len(), oldest, newest).await?;
+        
+        logger.log(&format!("⚠️ DANGER: Deleting time entries older than {} for project: {}", cutoff, project_slug)).await?;
+        
+        match api_client.delete_time_entries_before(project_slug, cutoff).await {
+            Ok(removed) => {
+                println!("{}  Successfully deleted {} time entries older than {} for project '{}'", icons::delete(),
+                         removed, format_local_time(cutoff, "%Y-%m-%d %H:%M:%S %Z"), project_slug);
+                logger.log(&format!("⚠️ Successfully deleted {} time entries older than {} for project: {}", removed, cutoff, project_slug)).await?;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to delete time entries: {}", icons::error(), e);
+                logger.log(&format!("Failed to delete old time entries for {}: {}", project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        }
+    } else if options.all {
+        if options.dry_run {
+            match api_client.get_time_entries(project_slug).await {
+                Ok(entries) => {
+                    println!("{} Dry run: would delete ALL {} time entries for project '{}'", icons::hint(), entries.len(), project_slug);
+                    logger.log(&format!("Dry run: would delete all {} time entries for project: {}", entries.len(), project_slug)).await?;
+                }
+                Err(e) => {
+                    eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+                    logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+                    return Err(exit_code::reported(&e));
+                }
             }
+            return Ok(());
         }
-    } else if all {
+        
         // Delete ALL entries - this is DANGEROUS!
-        show_danger_warning_and_confirm(project_slug).await?;
+        show_danger_warning_and_confirm(logger, confirm, project_slug).await?;
         
         logger.log(&format!("⚠️ DANGER: Deleting ALL time entries for project: {}", project_slug)).await?;
         
         match api_client.delete_project_times(project_slug).await {
             Ok(_) => {
-                println!("🗑️  Successfully deleted ALL time entries for project '{}'", project_slug);
+                println!("{}  Successfully deleted ALL time entries for project '{}'", icons::delete(), project_slug);
                 logger.log(&format!("⚠️ Successfully deleted ALL time entries for project: {}", project_slug)).await?;
             }
             Err(e) => {
-                eprintln!("❌ Failed to delete time entries: {}", e);
+                eprintln!("{} Failed to delete time entries: {}", icons::error(), e);
                 logger.log(&format!("Failed to delete all time entries for {}: {}", project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
             }
         }
     } else {
         // No timestamp provided and --all not specified
-        eprintln!("❌ Safety Error: You must specify either:");
-        eprintln!("   • A specific timestamp to delete: --timestamp <unix_timestamp>");
-        eprintln!("   • Use --all flag to delete ALL entries (DANGEROUS!)");
-        eprintln!("");
-        eprintln!("💡 Tip: Use 'timetracker times {}' to see all timestamps first", project_slug);
+        eprintln!("{} Safety Error: You must specify either:", icons::error());
+        eprintln!("   {} A specific timestamp to delete: --timestamp <unix_timestamp>", icons::bullet());
+        eprintln!("   {} A cutoff to delete everything before: --older-than <YYYY-MM-DD|90d>", icons::bullet());
+        eprintln!("   {} A date range to delete: --from <YYYY-MM-DD> --to <YYYY-MM-DD>", icons::bullet());
+        eprintln!("   {} Use --all flag to delete ALL entries (DANGEROUS!)", icons::bullet());
+        eprintln!();
+        eprintln!("{} Tip: Use 'timetracker times {}' to see all timestamps first", icons::hint(), project_slug);
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    Ok(())
+}
+
+/// Delete every entry within the inclusive --from/--to dates after listing them
+async fn delete_times_in_range(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    confirm: Confirm,
+    project_slug: &str,
+    options: &DeleteOptions,
+) -> Result<()> {
+    let from = options.from.as_deref().map(parse_date).transpose();
+    let to = options.to.as_deref().map(parse_date).transpose();
+    let (from, to) = match (from, to) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            eprintln!("{} --from ({}) is after --to ({})", icons::error(), from, to);
+            return Err(CommandError::reported(ExitCode::Failure).into());
+        }
+    }
+    
+    // Whole local days: from the first midnight up to the last second of the --to day
+    let range_start = from.map(local_day_start).unwrap_or(i64::MIN);
+    let range_end = to.and_then(|to| to.succ_opt()).map(|day| local_day_start(day) - 1).unwrap_or(i64::MAX);
+    
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let mut affected: Vec<&TimeEntry> = entries.iter()
+        .filter(|e| e.timestamp >= range_start && e.timestamp <= range_end)
+        .collect();
+    affected.sort_by_key(|e| e.timestamp);
+    
+    let range_label = format!("{} to {}",
+                              from.map(|d| d.to_string()).unwrap_or_else(|| "the beginning".to_string()),
+                              to.map(|d| d.to_string()).unwrap_or_else(|| "now".to_string()));
+    if affected.is_empty() {
+        println!("{} No time entries from {} for project '{}'", icons::ok(), range_label, project_slug);
+        return Ok(());
+    }
+    
+    // Refuse to orphan the start of a running session unless forced
+    if is_project_running(&entries) {
+        let running_start = entries.iter()
+            .filter(|e| e.entry_type == "start")
+            .map(|e| e.timestamp)
+            .max();
+        if running_start.is_some_and(|start| start >= range_start && start <= range_end) {
+            eprintln!("{}  The running session started within the range and its start would be deleted.", icons::warning());
+            if !options.force {
+                eprintln!("   {} Stop the session first, or use --force to delete anyway", icons::hint());
+                return Err(CommandError::reported(ExitCode::Failure).into());
+            }
+        }
+    }
+    
+    println!("{}  {} time entries from {} for project '{}' will be deleted:", icons::delete(), affected.len(), range_label, project_slug);
+    for entry in &affected {
+        println!("   {} {} {}{}",
+                 entry_type_icon(&entry.entry_type),
+                 format_local_time(entry.timestamp, "%Y-%m-%d %H:%M:%S"),
+                 entry.entry_type.to_uppercase(),
+                 entry.description.as_ref().map(|d| format!(" - {}", d)).unwrap_or_default());
+    }
+    
+    println!();
+    if options.dry_run {
+        println!("{} Dry run: nothing was deleted", icons::hint());
+        logger.log(&format!("Dry run: would delete {} time entries from {} to {} for project: {}", affected.len(), range_start, range_end, project_slug)).await?;
         return Ok(());
     }
+    if !confirm.ask(logger, &format!("Delete {} time entries?", affected.len())).await? {
+        println!("{} Delete cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    logger.log(&format!("Deleting time entries from {} to {} for project: {}", range_start, range_end, project_slug)).await?;
+    
+    match api_client.delete_time_entries_in_range(project_slug, range_start, range_end).await {
+        Ok(removed) => {
+            println!("{}  Successfully deleted {} time entries from {} for project '{}'", icons::delete(), removed, range_label, project_slug);
+            logger.log(&format!("Successfully deleted {} time entries from {} to {} for project: {}", removed, range_start, range_end, project_slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to delete time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to delete time entries in range for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+async fn show_older_than_warning_and_confirm(logger: &Logger, confirm: Confirm, project_slug: &str, count: usize, oldest: i64, newest: i64) -> Result<()> {
+    println!();
+    println!("{} {}  DANGER WARNING {}  {}", icons::danger(), icons::warning(), icons::warning(), icons::danger());
+    println!("{}", icons::rule());
+    println!("  You are about to DELETE {} TIME ENTRIES for project:", count);
+    println!("  {} '{}'", icons::project(), project_slug);
+    println!("  {} From {} to {}", icons::date(),
+             format_local_time(oldest, "%Y-%m-%d %H:%M:%S %Z"),
+             format_local_time(newest, "%Y-%m-%d %H:%M:%S %Z"));
+    println!();
+    println!("  {} This action CANNOT be undone!", icons::error());
+    println!();
+    println!("  {} Use 'timetracker export' to backup data first", icons::hint());
+    println!("{}", icons::rule());
+    println!();
+    
+    if !confirm.typed(logger, "DELETE OLD").await? {
+        println!("{} Operation cancelled. Data is safe.", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
     
+    println!("{}  Proceeding with deletion...", icons::warning());
     Ok(())
 }
 
-async fn show_danger_warning_and_confirm(project_slug: &str) -> Result<()> {
-    println!("");
-    println!("🚨 ⚠️  DANGER WARNING ⚠️  🚨");
-    println!("═══════════════════════════════════════════════════════════════");
+/// Parse a cutoff given as a date (YYYY-MM-DD, local midnight) or a duration ago (36h, 90d, 12w)
+fn parse_cutoff(input: &str) -> Result<i64> {
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow::anyhow!("Invalid date '{}'", input))?;
+        return tz::timestamp_of(&midnight)
+            .ok_or_else(|| anyhow::anyhow!("Invalid local date '{}'", input));
+    }
+    
+    let invalid = || anyhow::anyhow!("Invalid cutoff '{}'. Use a date (2024-01-31) or a duration (36h, 90d, 12w)", input);
+    let unit = input.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = input[..input.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 7 * 86400,
+        _ => return Err(invalid()),
+    };
+    
+    Ok(Utc::now().timestamp() - amount * seconds)
+}
+
+async fn show_danger_warning_and_confirm(logger: &Logger, confirm: Confirm, project_slug: &str) -> Result<()> {
+    println!();
+    println!("{} {}  DANGER WARNING {}  {}", icons::danger(), icons::warning(), icons::warning(), icons::danger());
+    println!("{}", icons::rule());
     println!("  You are about to DELETE ALL TIME ENTRIES for project:");
-    println!("  📁 '{}'", project_slug);
-    println!("");
-    println!("  ❌ This action CANNOT be undone!");
-    println!("  ❌ All tracking history will be permanently lost!");
-    println!("  ❌ This includes start/stop times and descriptions!");
-    println!("");
-    println!("  💡 Consider using --timestamp to delete specific entries instead");
-    println!("  💡 Use 'timetracker export' to backup data first");
-    println!("═══════════════════════════════════════════════════════════════");
-    println!("");
-    
-    print!("Are you absolutely sure? Type 'DELETE ALL' to confirm: ");
-    io::stdout().flush()?;
+    println!("  {} '{}'", icons::project(), project_slug);
+    println!();
+    println!("  {} This action CANNOT be undone!", icons::error());
+    println!("  {} All tracking history will be permanently lost!", icons::error());
+    println!("  {} This includes start/stop times and descriptions!", icons::error());
+    println!();
+    println!("  {} Consider using --timestamp to delete specific entries instead", icons::hint());
+    println!("  {} Use 'timetracker export' to backup data first", icons::hint());
+    println!("{}", icons::rule());
+    println!();
     
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let input = input.trim();
+    if !confirm.typed(logger, "DELETE ALL").await? {
+        println!("{} Operation cancelled. Data is safe.", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
     
-    if input != "DELETE ALL" {
-        println!("❌ Operation cancelled. Data is safe.");
-        return Err(anyhow::anyhow!("User cancelled dangerous operation"));
+    println!("{}  Proceeding with deletion...", icons::warning());
+    Ok(())
+}
+
+/// Non-interactive `time edit`: set or clear the description of the entry at `timestamp`
+pub async fn set_time_entry_description(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    project_slug: &str,
+    timestamp: i64,
+    description: Option<String>,
+) -> Result<()> {
+    match api_client.update_time_entry_by_timestamp(project_slug, timestamp, description.clone()).await {
+        Ok(_) => {
+            let desc_text = description.as_ref()
+                .map(|d| format!("'{}'", d))
+                .unwrap_or_else(|| "(no description)".to_string());
+            println!("{} Successfully updated description to: {}", icons::ok(), desc_text);
+            logger.log(&format!("Updated time entry {} description for project {}", timestamp, project_slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to update description: {}", icons::error(), e);
+            logger.log(&format!("Failed to update time entry {} for {}: {}", timestamp, project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
     }
     
-    println!("⚠️  Proceeding with deletion...");
     Ok(())
 }
 
-pub async fn edit_time_entry(api_client: &ApiClient, logger: &Logger, project_slug: &str) -> Result<()> {
+pub async fn edit_time_entry(api_client: &impl KvStore, logger: &Logger, project_slug: &str) -> Result<()> {
     logger.log(&format!("Editing time entry for project '{}'", project_slug)).await?;
     
     // Get time entries for the project
     let entries = match api_client.get_time_entries(project_slug).await {
         Ok(entries) => {
             if entries.is_empty() {
-                println!("❌ No time entries found for project '{}'", project_slug);
-                return Ok(());
+                println!("{} No time entries found for project '{}'", icons::error(), project_slug);
+                return Err(CommandError::reported(ExitCode::NotFound).into());
             }
             entries
         }
         Err(e) => {
-            eprintln!("❌ Failed to get time entries: {}", e);
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
             logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
         }
     };
     
@@ -615,13 +4736,13 @@ pub async fn edit_time_entry(api_client: &ApiClient, logger: &Logger, project_sl
     let recent_entries: Vec<_> = sorted_entries.into_iter().take(5).collect();
     
     // Display the recent entries
-    println!("📝 Recent time entries for project '{}':", project_slug);
-    println!("");
+    println!("{} Recent time entries for project '{}':", icons::note(), project_slug);
+    println!();
     for (index, entry) in recent_entries.iter().enumerate() {
         let utc_datetime = DateTime::from_timestamp(entry.timestamp, 0)
-            .unwrap_or_else(|| Utc::now());
-        let local_datetime = utc_datetime.with_timezone(&Local);
-        let type_icon = if entry.entry_type == "start" { "▶️" } else { "⏹️" };
+            .unwrap_or_else(Utc::now);
+        let local_datetime = tz::localize(utc_datetime);
+        let type_icon = entry_type_icon(&entry.entry_type);
         let description = entry.description.as_ref()
             .map(|d| format!(" - {}", d))
             .unwrap_or_else(|| " - (no description)".to_string());
@@ -634,7 +4755,7 @@ pub async fn edit_time_entry(api_client: &ApiClient, logger: &Logger, project_sl
                  description);
     }
     
-    println!("");
+    println!();
     print!("Select entry to edit (1-{}), or 'q' to quit: ", recent_entries.len());
     io::stdout().flush()?;
     
@@ -644,51 +4765,58 @@ pub async fn edit_time_entry(api_client: &ApiClient, logger: &Logger, project_sl
     let input = input.trim();
     
     if input.eq_ignore_ascii_case("q") {
-        println!("❌ Edit cancelled");
-        return Ok(());
+        println!("{} Edit cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
     let selection: usize = match input.parse::<usize>() {
         Ok(num) if num >= 1 && num <= recent_entries.len() => num - 1,
         _ => {
-            println!("❌ Invalid selection. Please enter a number between 1 and {}", recent_entries.len());
-            return Ok(());
+            println!("{} Invalid selection. Please enter a number between 1 and {}", icons::error(), recent_entries.len());
+            return Err(CommandError::reported(ExitCode::Failure).into());
         }
     };
     
     let selected_entry = &recent_entries[selection];
     
     // Show current description and allow editing
-    println!("");
+    println!();
     println!("Selected entry:");
     let utc_datetime = DateTime::from_timestamp(selected_entry.timestamp, 0)
-        .unwrap_or_else(|| Utc::now());
-    let local_datetime = utc_datetime.with_timezone(&Local);
-    let type_icon = if selected_entry.entry_type == "start" { "▶️" } else { "⏹️" };
+        .unwrap_or_else(Utc::now);
+    let local_datetime = tz::localize(utc_datetime);
+    let type_icon = entry_type_icon(&selected_entry.entry_type);
     println!("  {} {} {}", type_icon, selected_entry.entry_type.to_uppercase(), local_datetime.format("%Y-%m-%d %H:%M:%S %Z"));
     
-    let current_desc = selected_entry.description.as_ref()
-        .map(|d| d.as_str())
+    let current_desc = selected_entry.description.as_deref()
         .unwrap_or("(no description)");
     println!("  Current description: {}", current_desc);
-    println!("");
+    println!();
     
-    print!("Enter new description (press Enter to keep current, or type 'CLEAR' to remove): ");
-    io::stdout().flush()?;
+    // The current description is pre-filled; deleting it (or typing CLEAR) removes it
+    let Some(new_description) = line_edit::edit("Description (empty or CLEAR to remove)", selected_entry.description.as_deref().unwrap_or_default())? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
+    let updated_description = Some(new_description).filter(|d| !d.is_empty() && !d.eq_ignore_ascii_case("CLEAR"));
     
-    let mut new_description = String::new();
-    io::stdin().read_line(&mut new_description)?;
-    let new_description = new_description.trim();
+    // Late starts and forgotten stops are corrected by moving the entry
+    let current_time = local_datetime.format("%Y-%m-%d %H:%M:%S").to_string();
+    let Some(new_time) = line_edit::edit("Time (YYYY-MM-DD HH:MM[:SS])", &current_time)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     
-    let updated_description = if new_description.is_empty() {
-        // Keep current description
-        selected_entry.description.clone()
-    } else if new_description.eq_ignore_ascii_case("CLEAR") {
-        // Clear description
-        None
+    let updated_timestamp = if new_time.is_empty() || new_time == current_time {
+        selected_entry.timestamp
     } else {
-        // Set new description
-        Some(new_description.to_string())
+        match parse_datetime(&new_time) {
+            Ok(timestamp) => timestamp,
+            Err(e) => {
+                eprintln!("{} {}", icons::error(), e);
+                return Err(exit_code::reported(&e));
+            }
+        }
     };
     
     // Update the entry via API
@@ -697,40 +4825,178 @@ pub async fn edit_time_entry(api_client: &ApiClient, logger: &Logger, project_sl
             let desc_text = updated_description.as_ref()
                 .map(|d| format!("'{}'", d))
                 .unwrap_or_else(|| "(no description)".to_string());
-            println!("✅ Successfully updated description to: {}", desc_text);
+            println!("{} Successfully updated description to: {}", icons::ok(), desc_text);
             logger.log(&format!("Updated time entry {} description for project {}", selected_entry.timestamp, project_slug)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to update description: {}", e);
+            eprintln!("{} Failed to update description: {}", icons::error(), e);
             logger.log(&format!("Failed to update time entry {} for {}: {}", selected_entry.timestamp, project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    if updated_timestamp != selected_entry.timestamp {
+        match api_client.update_time_entry_timestamp(project_slug, selected_entry.timestamp, updated_timestamp).await {
+            Ok(_) => {
+                println!("{} Moved {} from {} to {}", icons::ok(),
+                         selected_entry.entry_type,
+                         local_datetime.format("%Y-%m-%d %H:%M:%S"),
+                         format_local_time(updated_timestamp, "%Y-%m-%d %H:%M:%S"));
+                logger.log(&format!("Moved time entry {} to {} for project {}", selected_entry.timestamp, updated_timestamp, project_slug)).await?;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to update time: {}", icons::error(), e);
+                logger.log(&format!("Failed to move time entry {} for {}: {}", selected_entry.timestamp, project_slug, e)).await?;
+                return Err(exit_code::reported(&e));
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Walk through unmatched starts and ends and close the sessions that never ended
+pub async fn fix_sessions(api_client: &impl KvStore, logger: &Logger, project_slug: &str) -> Result<()> {
+    logger.log(&format!("Checking sessions of project '{}' for unmatched entries", project_slug)).await?;
+    
+    let entries = match api_client.get_time_entries(project_slug).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", project_slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    let anomalies = session_anomalies(&entries);
+    if anomalies.is_empty() {
+        println!("{} Every start in '{}' has a matching end", icons::ok(), project_slug);
+        return Ok(());
+    }
+    
+    println!("{} Found {} unmatched entr{} in '{}'", icons::inspect(), anomalies.len(),
+             if anomalies.len() == 1 { "y" } else { "ies" }, project_slug);
+    
+    let datetime_format = locale::current().datetime_format();
+    let mut repaired = 0;
+    for (index, anomaly) in anomalies.iter().enumerate() {
+        println!();
+        let (start, latest_end) = match anomaly {
+            Anomaly::DoubleStart { start, next_start } => {
+                println!("{}. {} Started {}{}, but the next start at {} came first",
+                         index + 1, icons::start(), format_local_time(start.timestamp, &datetime_format),
+                         format_description(&start.description), format_local_time(*next_start, &datetime_format));
+                // The end has to sort before the next start
+                (start, next_start - 1)
+            }
+            Anomaly::OpenStart { start } => {
+                let running = Utc::now().timestamp() - start.timestamp;
+                println!("{}. {} Started {}{} and never stopped ({}h {}m ago)",
+                         index + 1, icons::start(), format_local_time(start.timestamp, &datetime_format),
+                         format_description(&start.description), running / 3600, (running % 3600) / 60);
+                (start, Utc::now().timestamp())
+            }
+            Anomaly::StrayEnd { end } => {
+                println!("{}. {} Stopped {}{} without a start",
+                         index + 1, icons::stop(), format_local_time(end.timestamp, &datetime_format),
+                         format_description(&end.description));
+                println!("   {} Remove it with: timetracker time delete {} --timestamp {}", icons::hint(), project_slug, end.timestamp);
+                continue;
+            }
+        };
+        
+        print!("   End time (HH:MM on the start day, YYYY-MM-DD HH:MM, or Enter to skip): ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        if input.is_empty() {
+            println!("   {} Skipped", icons::skip());
+            continue;
+        }
+        
+        let end_timestamp = match parse_time_on_day(input, start.timestamp) {
+            Ok(timestamp) if timestamp > start.timestamp && timestamp <= latest_end => timestamp,
+            Ok(_) => {
+                eprintln!("   {} The end must be after {} and no later than {}", icons::error(),
+                          format_local_time(start.timestamp, &datetime_format),
+                          format_local_time(latest_end, &datetime_format));
+                continue;
+            }
+            Err(e) => {
+                eprintln!("   {} {}", icons::error(), e);
+                continue;
+            }
+        };
+        
+        print!("   Description (optional): ");
+        io::stdout().flush()?;
+        let mut description = String::new();
+        io::stdin().read_line(&mut description)?;
+        let description = description.trim();
+        
+        let entry = TimeEntry {
+            timestamp: end_timestamp,
+            entry_type: "end".to_string(),
+            description: (!description.is_empty()).then(|| description.to_string()),
+            tags: Vec::new(),
+        };
+        // The end goes before later entries on purpose, so it skips the order check of add_time_entry
+        match api_client.add_time_entries(project_slug, vec![entry]).await {
+            Ok(_) => {
+                repaired += 1;
+                println!("   {} Closed the session at {}", icons::ok(), format_local_time(end_timestamp, &datetime_format));
+                logger.log(&format!("Repaired session of project {}: added end at {} for start at {}",
+                                    project_slug, end_timestamp, start.timestamp)).await?;
+            }
+            Err(e) => {
+                eprintln!("   {} Failed to add the end entry: {}", icons::error(), e);
+                logger.log(&format!("Failed to repair session of project {} started at {}: {}", project_slug, start.timestamp, e)).await?;
+            }
         }
     }
     
+    println!();
+    println!("{} Repaired {} of {} unmatched entries", icons::list(), repaired, anomalies.len());
     Ok(())
 }
 
-pub async fn edit_project_details(api_client: &ApiClient, logger: &Logger) -> Result<()> {
+/// Parse "HH:MM[:SS]" on the local day of `reference`, anything else goes through parse_datetime
+fn parse_time_on_day(input: &str, reference: i64) -> Result<i64> {
+    let time = chrono::NaiveTime::parse_from_str(input, "%H:%M")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(input, "%H:%M:%S"));
+    if let Ok(time) = time {
+        let day = tz::localize(DateTime::from_timestamp(reference, 0).unwrap_or_else(Utc::now))
+            .date_naive();
+        return tz::timestamp_of(&day.and_time(time))
+            .ok_or_else(|| anyhow::anyhow!("Invalid local time '{}'", input));
+    }
+    parse_datetime(input)
+}
+
+pub async fn edit_project_details(api_client: &impl KvStore, logger: &Logger, confirm: Confirm) -> Result<()> {
     logger.log("Editing project details").await?;
     
     // Get all projects
-    let projects = match api_client.get_projects().await {
+    let mut projects = match api_client.get_projects().await {
         Ok(projects) => {
             if projects.is_empty() {
-                println!("❌ No projects found");
-                return Ok(());
+                println!("{} No projects found", icons::error());
+                return Err(CommandError::reported(ExitCode::NotFound).into());
             }
             projects
         }
         Err(e) => {
-            eprintln!("❌ Failed to get projects: {}", e);
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
             logger.log(&format!("Failed to get projects: {}", e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
         }
     };
+    sort_by_recent_use(api_client, &mut projects).await;
     
-    // Display all projects
-    println!("📝 Select a project to edit:");
-    println!("");
+    // Display all projects, most recently used first
+    println!("{} Select a project to edit:", icons::note());
+    println!();
     for (index, project) in projects.iter().enumerate() {
         println!("  {}. {} ({}) - {}", 
                  index + 1, 
@@ -739,8 +5005,8 @@ pub async fn edit_project_details(api_client: &ApiClient, logger: &Logger) -> Re
                  project.description);
     }
     
-    println!("");
-    print!("Select project to edit (1-{}), or 'q' to quit: ", projects.len());
+    println!();
+    print!("Select project to edit (1-{} or slug), or 'q' to quit: ", projects.len());
     io::stdout().flush()?;
     
     // Get user selection
@@ -749,74 +5015,91 @@ pub async fn edit_project_details(api_client: &ApiClient, logger: &Logger) -> Re
     let input = input.trim();
     
     if input.eq_ignore_ascii_case("q") {
-        println!("❌ Edit cancelled");
-        return Ok(());
+        println!("{} Edit cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
-    let selection: usize = match input.parse::<usize>() {
-        Ok(num) if num >= 1 && num <= projects.len() => num - 1,
-        _ => {
-            println!("❌ Invalid selection. Please enter a number between 1 and {}", projects.len());
-            return Ok(());
+    let selection: usize = match parse_project_selection(input, &projects) {
+        Some(index) => index,
+        None => {
+            println!("{} Invalid selection. Please enter a number between 1 and {} or a project slug", icons::error(), projects.len());
+            return Err(CommandError::reported(ExitCode::Failure).into());
         }
     };
     
     let selected_project = &projects[selection];
     
     // Show current project details and allow editing
-    println!("");
+    println!();
     println!("Selected project:");
     println!("  Name: {}", selected_project.name);
     println!("  Slug: {}", selected_project.slug);
     println!("  Description: {}", selected_project.description);
-    println!("");
+    println!("  Rate: {}", format_rate(selected_project.rate, selected_project.currency.as_deref()));
+    println!();
     
-    // Edit name
-    print!("Enter new name (press Enter to keep '{}'): ", selected_project.name);
-    io::stdout().flush()?;
-    let mut new_name = String::new();
-    io::stdin().read_line(&mut new_name)?;
-    let new_name = new_name.trim();
+    // Edit name, slug and description in place; clearing a field keeps its value
+    let Some(new_name) = line_edit::edit("Name", &selected_project.name)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_name = if new_name.is_empty() {
         selected_project.name.clone()
     } else {
-        new_name.to_string()
+        new_name
     };
     
-    // Edit slug
-    print!("Enter new slug (press Enter to keep '{}'): ", selected_project.slug);
-    io::stdout().flush()?;
-    let mut new_slug = String::new();
-    io::stdin().read_line(&mut new_slug)?;
-    let new_slug = new_slug.trim();
+    let Some(new_slug) = line_edit::edit("Slug", &selected_project.slug)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_slug = if new_slug.is_empty() {
         selected_project.slug.clone()
     } else {
-        // Validate slug format (alphanumeric, hyphens, underscores)
-        if !new_slug.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
-            println!("❌ Invalid slug format. Slug can only contain letters, numbers, hyphens, and underscores.");
-            return Ok(());
+        if let Err(e) = validate_slug(&new_slug) {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
         }
-        new_slug.to_string()
+        new_slug
     };
     
-    // Edit description
-    print!("Enter new description (press Enter to keep '{}'): ", selected_project.description);
-    io::stdout().flush()?;
-    let mut new_description = String::new();
-    io::stdin().read_line(&mut new_description)?;
-    let new_description = new_description.trim();
+    let Some(new_description) = line_edit::edit("Description", &selected_project.description)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_description = if new_description.is_empty() {
         selected_project.description.clone()
     } else {
-        new_description.to_string()
+        new_description
+    };
+    
+    // Edit rate
+    let (updated_rate, updated_currency) = match prompt_rate(selected_project) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    let rate_changed = updated_rate != selected_project.rate || updated_currency != selected_project.currency;
+    
+    // Edit budget
+    let updated_budget = match prompt_budget(selected_project) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
     };
+    let budget_changed = updated_budget != selected_project.budget_hours;
     
     // Check if anything changed
     if updated_name == selected_project.name && 
        updated_slug == selected_project.slug && 
-       updated_description == selected_project.description {
-        println!("❌ No changes made");
+       updated_description == selected_project.description &&
+       !rate_changed &&
+       !budget_changed {
+        println!("{} No changes made", icons::error());
         return Ok(());
     }
     
@@ -825,55 +5108,273 @@ pub async fn edit_project_details(api_client: &ApiClient, logger: &Logger) -> Re
         name: updated_name.clone(),
         slug: updated_slug.clone(),
         description: updated_description.clone(),
+        last_touched: selected_project.last_touched,
+        rate: updated_rate,
+        currency: updated_currency.clone(),
+        budget_hours: updated_budget,
+        archived: selected_project.archived,
     };
     
     // Confirm changes
-    println!("");
+    println!();
     println!("Proposed changes:");
     if updated_name != selected_project.name {
-        println!("  Name: '{}' → '{}'", selected_project.name, updated_name);
+        print_field_change("Name", &selected_project.name, &updated_name);
     }
     if updated_slug != selected_project.slug {
-        println!("  Slug: '{}' → '{}'", selected_project.slug, updated_slug);
-        println!("  ⚠️  Note: Changing slug will move all time entries to new key");
+        print_field_change("Slug", &selected_project.slug, &updated_slug);
+        println!("  {}  Note: Changing slug will move all time entries to new key", icons::warning());
     }
     if updated_description != selected_project.description {
-        println!("  Description: '{}' → '{}'", selected_project.description, updated_description);
+        print_description_change(&selected_project.description, &updated_description);
+    }
+    if rate_changed {
+        print_field_change("Rate", &format_rate(selected_project.rate, selected_project.currency.as_deref()),
+                           &format_rate(updated_rate, updated_currency.as_deref()));
+    }
+    if budget_changed {
+        print_field_change("Budget", &format_budget(selected_project.budget_hours), &format_budget(updated_budget));
+    }
+    println!();
+    
+    if !confirm.ask(logger, "Apply these changes?").await? {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    }
+    
+    // Update the project via API
+    match api_client.update_project(&selected_project.slug, updated_project).await {
+        Ok(_) => {
+            println!("{} Successfully updated project", icons::ok());
+            if updated_slug != selected_project.slug {
+                println!("   {} Project slug changed from '{}' to '{}'", icons::hint(), selected_project.slug, updated_slug);
+                println!("   {} Use '{}' for future commands", icons::hint(), updated_slug);
+            }
+            logger.log(&format!("Updated project: {} → name:'{}', slug:'{}', desc:'{}'", 
+                               selected_project.slug, updated_name, updated_slug, updated_description)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to update project: {}", icons::error(), e);
+            logger.log(&format!("Failed to update project {}: {}", selected_project.slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
     }
-    println!("");
     
-    print!("Apply these changes? (y/N): ");
+    Ok(())
+}
+
+/// Prompt for an hourly rate like "120" or "120 CHF"; Enter keeps the current one, CLEAR removes it
+fn prompt_rate(project: &Project) -> Result<(Option<f64>, Option<String>)> {
+    print!("Enter hourly rate, optionally with currency (press Enter to keep '{}', 'CLEAR' to remove): ",
+           format_rate(project.rate, project.currency.as_deref()));
     io::stdout().flush()?;
-    let mut confirmation = String::new();
-    io::stdin().read_line(&mut confirmation)?;
-    let confirmation = confirmation.trim();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
     
-    if !confirmation.eq_ignore_ascii_case("y") && !confirmation.eq_ignore_ascii_case("yes") {
-        println!("❌ Changes cancelled");
-        return Ok(());
+    if input.is_empty() {
+        return Ok((project.rate, project.currency.clone()));
+    }
+    if input.eq_ignore_ascii_case("CLEAR") {
+        return Ok((None, None));
+    }
+    
+    let mut parts = input.split_whitespace();
+    let rate = parts.next().and_then(|rate| rate.parse::<f64>().ok())
+        .filter(|rate| rate.is_finite() && *rate >= 0.0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid rate '{}'. Use a number like 120 or 120 CHF", input))?;
+    let currency = parts.next().map(|currency| currency.to_uppercase()).or_else(|| project.currency.clone());
+    Ok((Some(rate), currency))
+}
+
+/// Prompt for an hour budget like "40"; Enter keeps the current one, CLEAR removes it
+fn prompt_budget(project: &Project) -> Result<Option<f64>> {
+    print!("Enter budget in hours (press Enter to keep '{}', 'CLEAR' to remove): ", format_budget(project.budget_hours));
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    
+    if input.is_empty() {
+        return Ok(project.budget_hours);
+    }
+    if input.eq_ignore_ascii_case("CLEAR") {
+        return Ok(None);
+    }
+    input.trim_end_matches('h').parse::<f64>().ok()
+        .filter(|hours| hours.is_finite() && *hours > 0.0)
+        .map(Some)
+        .ok_or_else(|| anyhow::anyhow!("Invalid budget '{}'. Use a number of hours like 40", input))
+}
+
+fn format_budget(budget_hours: Option<f64>) -> String {
+    match budget_hours {
+        Some(hours) => format!("{}h", locale::current().format_decimal(hours, 1)),
+        None => "(none)".to_string(),
+    }
+}
+
+/// Share of the budget used up by `seconds`, e.g. 0.85 for 85%
+fn budget_used(budget_hours: f64, seconds: i64) -> f64 {
+    seconds as f64 / 3600.0 / budget_hours
+}
+
+/// Budget consumption, marked from 80% on and in red once the budget is used up
+fn print_budget(budget_hours: f64, seconds: i64) {
+    let used = budget_used(budget_hours, seconds);
+    let message = format!("{}% of the {} budget used ({})", (used * 100.0).round(),
+                          format_budget(Some(budget_hours)), format_duration(seconds, DurationStyle::Short));
+    if used >= 1.0 {
+        println!("   {} {}", icons::danger(), style::red(&format!("Over budget: {}", message)));
+    } else if used >= 0.8 {
+        println!("   {}  {}", icons::warning(), message);
+    } else {
+        println!("   {} {}", icons::report(), message);
+    }
+}
+
+fn format_rate(rate: Option<f64>, currency: Option<&str>) -> String {
+    match (rate, currency) {
+        (Some(rate), Some(currency)) => format!("{} {}/h", locale::current().format_decimal(rate, 2), currency),
+        (Some(rate), None) => format!("{}/h", locale::current().format_decimal(rate, 2)),
+        (None, _) => "(none)".to_string(),
+    }
+}
+
+/// "CHF 360.00", or just the number without a currency
+fn format_amount(amount: f64, currency: Option<&str>) -> String {
+    let amount = locale::current().format_decimal(amount, 2);
+    match currency {
+        Some(currency) => format!("{} {}", currency, amount),
+        None => amount,
+    }
+}
+
+fn print_field_change(label: &str, old: &str, new: &str) {
+    println!("  {}: '{}' {} '{}'", label, style::red(old), icons::arrow(), style::green(new));
+}
+
+fn print_description_change(old: &str, new: &str) {
+    // Highlight individual words when only part of the description changed
+    match word_diff(old, new) {
+        Some(diff) => println!("  Description: {}", diff),
+        None => print_field_change("Description", old, new),
+    }
+}
+
+/// Word-level diff of two texts, or None if they have no words in common
+fn word_diff(old: &str, new: &str) -> Option<String> {
+    let old_words: Vec<&str> = old.split_whitespace().collect();
+    let new_words: Vec<&str> = new.split_whitespace().collect();
+    
+    // lcs[i][j] = longest common subsequence of old_words[i..] and new_words[j..]
+    let mut lcs = vec![vec![0usize; new_words.len() + 1]; old_words.len() + 1];
+    for i in (0..old_words.len()).rev() {
+        for j in (0..new_words.len()).rev() {
+            lcs[i][j] = if old_words[i] == new_words[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    if lcs[0][0] == 0 {
+        return None;
+    }
+    
+    let mut parts = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_words.len() || j < new_words.len() {
+        if i < old_words.len() && j < new_words.len() && old_words[i] == new_words[j] {
+            parts.push(old_words[i].to_string());
+            i += 1;
+            j += 1;
+        } else if i < old_words.len() && (j == new_words.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            parts.push(style::removed(old_words[i]));
+            i += 1;
+        } else {
+            parts.push(style::added(new_words[j]));
+            j += 1;
+        }
+    }
+    
+    Some(parts.join(" "))
+}
+
+/// The slug to work with: `slug` itself if the project exists, else a close match the user confirms at the terminal.
+/// Fails with exit code 2 (after telling the user) when there is nothing to work with.
+async fn resolve_slug(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, slug: &str) -> Result<String> {
+    let projects = match api_client.get_projects().await {
+        Ok(projects) => projects,
+        // Let the command itself report the connection problem
+        Err(_) => return Ok(slug.to_string()),
+    };
+    if projects.iter().any(|p| p.slug == slug) {
+        return Ok(slug.to_string());
+    }
+    
+    let slugs: Vec<&str> = projects.iter().map(|p| p.slug.as_str()).collect();
+    if let Some(suggestion) = closest_slug(slug, &slugs).filter(|_| !output::json()) {
+        eprintln!("{} Project '{}' not found", icons::error(), slug);
+        // Only someone at the terminal may switch to another project, --yes never does
+        if confirm.assume_yes() || !io::stdin().is_terminal() {
+            eprintln!("   {} Did you mean '{}'?", icons::hint(), suggestion);
+            logger.log(&format!("Project '{}' not found, suggested '{}'", slug, suggestion)).await?;
+            return Err(CommandError::reported(ExitCode::NotFound).into());
+        }
+        if confirm.ask(logger, &format!("Did you mean '{}'?", suggestion)).await? {
+            logger.log(&format!("Using project '{}' for mistyped slug '{}'", suggestion, slug)).await?;
+            return Ok(suggestion);
+        }
+        return Err(CommandError::reported(ExitCode::NotFound).into());
     }
     
-    // Update the project via API
-    match api_client.update_project(&selected_project.slug, updated_project).await {
-        Ok(_) => {
-            println!("✅ Successfully updated project");
-            if updated_slug != selected_project.slug {
-                println!("   💡 Project slug changed from '{}' to '{}'", selected_project.slug, updated_slug);
-                println!("   💡 Use '{}' for future commands", updated_slug);
-            }
-            logger.log(&format!("Updated project: {} → name:'{}', slug:'{}', desc:'{}'", 
-                               selected_project.slug, updated_name, updated_slug, updated_description)).await?;
+    logger.log(&format!("Project '{}' not found", slug)).await?;
+    let error = output::failure_with(ExitCode::NotFound, format!("Project '{}' not found", slug));
+    if !output::json() {
+        eprintln!("   {} Use 'timetracker project list' to see all projects", icons::hint());
+    }
+    Err(error)
+}
+
+/// The known slug closest to a mistyped one, if any is within a few edits
+fn closest_slug(input: &str, slugs: &[&str]) -> Option<String> {
+    let input = input.to_lowercase();
+    // Roughly one typo per three characters, at least one
+    let max_distance = (input.chars().count() / 3).max(1);
+    slugs.iter()
+        .map(|slug| (levenshtein(&input, &slug.to_lowercase()), *slug))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, slug)| slug.to_string())
+}
+
+/// Number of single-character insertions, deletions and substitutions to turn `a` into `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
         }
-        Err(e) => {
-            eprintln!("❌ Failed to update project: {}", e);
-            logger.log(&format!("Failed to update project {}: {}", selected_project.slug, e)).await?;
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Slugs for shell completion; stays silent on errors so a failed lookup doesn't garble the prompt
+pub async fn complete_projects(api_client: &impl KvStore) {
+    if let Ok(projects) = api_client.get_projects().await {
+        for project in projects {
+            println!("{}", project.slug);
         }
     }
-    
-    Ok(())
 }
 
-async fn get_project_display_name(api_client: &ApiClient, project_slug: &str) -> String {
+async fn get_project_display_name(api_client: &impl KvStore, project_slug: &str) -> String {
     match api_client.get_projects().await {
         Ok(projects) => {
             if let Some(project) = projects.iter().find(|p| p.slug == project_slug) {
@@ -886,73 +5387,90 @@ async fn get_project_display_name(api_client: &ApiClient, project_slug: &str) ->
     }
 }
 
-pub async fn edit_project_by_slug(api_client: &ApiClient, logger: &Logger, slug: &str) -> Result<()> {
+pub async fn edit_project_by_slug(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, slug: &str) -> Result<()> {
     logger.log(&format!("Editing project: {}", slug)).await?;
     
     // Get project details
     let project = match api_client.get_project(slug).await {
         Ok(project) => project,
         Err(e) => {
-            eprintln!("❌ Failed to get project: {}", e);
+            eprintln!("{} Failed to get project: {}", icons::error(), e);
             logger.log(&format!("Failed to get project {}: {}", slug, e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
         }
     };
     
     // Show current project details and allow editing
-    println!("");
+    println!();
     println!("Selected project:");
     println!("  Name: {}", project.name);
     println!("  Slug: {}", project.slug);
     println!("  Description: {}", project.description);
-    println!("");
+    println!("  Rate: {}", format_rate(project.rate, project.currency.as_deref()));
+    println!();
     
-    // Edit name
-    print!("Enter new name (press Enter to keep '{}'): ", project.name);
-    io::stdout().flush()?;
-    let mut new_name = String::new();
-    io::stdin().read_line(&mut new_name)?;
-    let new_name = new_name.trim();
+    // Edit name, slug and description in place; clearing a field keeps its value
+    let Some(new_name) = line_edit::edit("Name", &project.name)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_name = if new_name.is_empty() {
         project.name.clone()
     } else {
-        new_name.to_string()
+        new_name
     };
     
-    // Edit slug
-    print!("Enter new slug (press Enter to keep '{}'): ", project.slug);
-    io::stdout().flush()?;
-    let mut new_slug = String::new();
-    io::stdin().read_line(&mut new_slug)?;
-    let new_slug = new_slug.trim();
+    let Some(new_slug) = line_edit::edit("Slug", &project.slug)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_slug = if new_slug.is_empty() {
         project.slug.clone()
     } else {
-        // Validate slug format (alphanumeric, hyphens, underscores)
-        if !new_slug.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
-            println!("❌ Invalid slug format. Slug can only contain letters, numbers, hyphens, and underscores.");
-            return Ok(());
+        if let Err(e) = validate_slug(&new_slug) {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
         }
-        new_slug.to_string()
+        new_slug
     };
     
-    // Edit description
-    print!("Enter new description (press Enter to keep '{}'): ", project.description);
-    io::stdout().flush()?;
-    let mut new_description = String::new();
-    io::stdin().read_line(&mut new_description)?;
-    let new_description = new_description.trim();
+    let Some(new_description) = line_edit::edit("Description", &project.description)? else {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
+    };
     let updated_description = if new_description.is_empty() {
         project.description.clone()
     } else {
-        new_description.to_string()
+        new_description
+    };
+    
+    // Edit rate
+    let (updated_rate, updated_currency) = match prompt_rate(&project) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
     };
+    let rate_changed = updated_rate != project.rate || updated_currency != project.currency;
+    
+    // Edit budget
+    let updated_budget = match prompt_budget(&project) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{} {}", icons::error(), e);
+            return Err(exit_code::reported(&e));
+        }
+    };
+    let budget_changed = updated_budget != project.budget_hours;
     
     // Check if anything changed
     if updated_name == project.name && 
        updated_slug == project.slug && 
-       updated_description == project.description {
-        println!("❌ No changes made");
+       updated_description == project.description &&
+       !rate_changed &&
+       !budget_changed {
+        println!("{} No changes made", icons::error());
         return Ok(());
     }
     
@@ -961,133 +5479,453 @@ pub async fn edit_project_by_slug(api_client: &ApiClient, logger: &Logger, slug:
         name: updated_name.clone(),
         slug: updated_slug.clone(),
         description: updated_description.clone(),
+        last_touched: project.last_touched,
+        rate: updated_rate,
+        currency: updated_currency.clone(),
+        budget_hours: updated_budget,
+        archived: project.archived,
     };
     
     // Confirm changes
-    println!("");
+    println!();
     println!("Proposed changes:");
     if updated_name != project.name {
-        println!("  Name: '{}' → '{}'", project.name, updated_name);
+        print_field_change("Name", &project.name, &updated_name);
     }
     if updated_slug != project.slug {
-        println!("  Slug: '{}' → '{}'", project.slug, updated_slug);
-        println!("  ⚠️  Note: Changing slug will move all time entries to new key");
+        print_field_change("Slug", &project.slug, &updated_slug);
+        println!("  {}  Note: Changing slug will move all time entries to new key", icons::warning());
     }
     if updated_description != project.description {
-        println!("  Description: '{}' → '{}'", project.description, updated_description);
+        print_description_change(&project.description, &updated_description);
     }
-    println!("");
-    
-    print!("Apply these changes? (y/N): ");
-    io::stdout().flush()?;
-    let mut confirmation = String::new();
-    io::stdin().read_line(&mut confirmation)?;
-    let confirmation = confirmation.trim();
+    if rate_changed {
+        print_field_change("Rate", &format_rate(project.rate, project.currency.as_deref()),
+                           &format_rate(updated_rate, updated_currency.as_deref()));
+    }
+    if budget_changed {
+        print_field_change("Budget", &format_budget(project.budget_hours), &format_budget(updated_budget));
+    }
+    println!();
     
-    if !confirmation.eq_ignore_ascii_case("y") && !confirmation.eq_ignore_ascii_case("yes") {
-        println!("❌ Changes cancelled");
-        return Ok(());
+    if !confirm.ask(logger, "Apply these changes?").await? {
+        println!("{} Changes cancelled", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
     // Update the project via API
     match api_client.update_project(&project.slug, updated_project).await {
         Ok(_) => {
-            println!("✅ Successfully updated project");
+            println!("{} Successfully updated project", icons::ok());
             if updated_slug != project.slug {
-                println!("   💡 Project slug changed from '{}' to '{}'", project.slug, updated_slug);
-                println!("   💡 Use '{}' for future commands", updated_slug);
+                println!("   {} Project slug changed from '{}' to '{}'", icons::hint(), project.slug, updated_slug);
+                println!("   {} Use '{}' for future commands", icons::hint(), updated_slug);
             }
             logger.log(&format!("Updated project: {} → name:'{}', slug:'{}', desc:'{}'", 
                                project.slug, updated_name, updated_slug, updated_description)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to update project: {}", e);
+            eprintln!("{} Failed to update project: {}", icons::error(), e);
+            logger.log(&format!("Failed to update project {}: {}", project.slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Changes for `project edit` given as flags; fields left as None keep their value
+#[derive(Debug, Clone, Default)]
+pub struct ProjectChanges {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub slug: Option<String>,
+}
+
+impl ProjectChanges {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.slug.is_none()
+    }
+}
+
+/// Edit a project without prompting, for scripts
+pub async fn apply_project_changes(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, slug: &str, changes: &ProjectChanges) -> Result<()> {
+    let slug = resolve_slug(api_client, logger, confirm, slug).await?;
+    let project = match api_client.get_project(&slug).await {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("{} Failed to get project: {}", icons::error(), e);
+            logger.log(&format!("Failed to get project {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    if let Some(new_slug) = &changes.slug {
+        if let Err(e) = validate_slug(new_slug) {
+            return Err(output::failure(e.to_string()));
+        }
+    }
+    
+    let updated_project = Project {
+        name: changes.name.clone().unwrap_or_else(|| project.name.clone()),
+        slug: changes.slug.clone().unwrap_or_else(|| project.slug.clone()),
+        description: changes.description.clone().unwrap_or_else(|| project.description.clone()),
+        ..project.clone()
+    };
+    if updated_project.name == project.name && updated_project.slug == project.slug && updated_project.description == project.description {
+        println!("{} No changes made", icons::hint());
+        return Ok(());
+    }
+    
+    let (updated_name, updated_slug, updated_description) = (updated_project.name.clone(), updated_project.slug.clone(), updated_project.description.clone());
+    match api_client.update_project(&project.slug, updated_project).await {
+        Ok(_) => {
+            println!("{} Successfully updated project", icons::ok());
+            if updated_name != project.name {
+                print_field_change("Name", &project.name, &updated_name);
+            }
+            if updated_slug != project.slug {
+                print_field_change("Slug", &project.slug, &updated_slug);
+            }
+            if updated_description != project.description {
+                print_description_change(&project.description, &updated_description);
+            }
+            logger.log(&format!("Updated project: {} → name:'{}', slug:'{}', desc:'{}'",
+                               project.slug, updated_name, updated_slug, updated_description)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to update project: {}", icons::error(), e);
             logger.log(&format!("Failed to update project {}: {}", project.slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+pub async fn project_info(api_client: &impl KvStore, logger: &Logger, slug: &str, raw: bool) -> Result<()> {
+    logger.log(&format!("Showing info for project '{}'", slug)).await?;
+    
+    let project = match api_client.get_project(slug).await {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("{} Failed to get project: {}", icons::error(), e);
+            logger.log(&format!("Failed to get project {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    
+    println!("{} {} ({})", icons::project(), project.name, project.slug);
+    println!("   Description: {}", project.description);
+    if let Some(touched_at) = project.last_touched {
+        println!("   Last touched: {}", format_local_time(touched_at, &locale::current().datetime_format()));
+    }
+    if project.rate.is_some() {
+        println!("   Rate: {}", format_rate(project.rate, project.currency.as_deref()));
+    }
+    if project.budget_hours.is_some() {
+        println!("   Budget: {}", format_budget(project.budget_hours));
+    }
+    if project.archived {
+        println!("   Archived: yes (hidden from lists and menus)");
+    }
+    
+    match api_client.get_time_entries(slug).await {
+        Ok(entries) => {
+            println!("   Time entries: {}", entries.len());
+            println!("   Running: {}", if is_project_running(&entries) { "yes" } else { "no" });
+        }
+        Err(e) => {
+            eprintln!("{} Failed to get time entries: {}", icons::error(), e);
+            logger.log(&format!("Failed to get time entries for {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    if raw {
+        // Show the stored value next to its parsed form to debug the stringified JSON
+        for key in ["projects".to_string(), format!("projects/{}", slug)] {
+            println!();
+            println!("{} Key '{}':", icons::inspect(), key);
+            match api_client.get_key_raw(&key).await {
+                Ok(Some(stored)) => {
+                    println!("   Stored: {}", stored);
+                    let parsed = match &stored {
+                        serde_json::Value::String(s) => serde_json::from_str(s).unwrap_or_else(|_| stored.clone()),
+                        _ => stored.clone(),
+                    };
+                    println!("   Parsed: {}", serde_json::to_string_pretty(&parsed)?);
+                }
+                Ok(None) => println!("   (key does not exist)"),
+                Err(e) => eprintln!("{} Failed to get raw value: {}", icons::error(), e),
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+pub async fn touch_project(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    slug: &str,
+    timestamp: Option<i64>,
+) -> Result<()> {
+    let touched_at = timestamp.unwrap_or_else(|| Utc::now().timestamp());
+    
+    match api_client.touch_project(slug, touched_at).await {
+        Ok(_) => {
+            let utc_datetime = DateTime::from_timestamp(touched_at, 0)
+                .unwrap_or_else(Utc::now);
+            let local_datetime = tz::localize(utc_datetime);
+            println!("{} Touched project '{}' at {}", icons::touch(), slug, local_datetime.format("%Y-%m-%d %H:%M:%S %Z"));
+            println!("   {} Only project metadata was updated, no time entry was added", icons::hint());
+            logger.log(&format!("Touched project metadata: {} (last_touched: {})", slug, touched_at)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to touch project: {}", icons::error(), e);
+            logger.log(&format!("Failed to touch project {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Hide or show a project in lists and menus; its time entries are never touched
+pub async fn set_archived(api_client: &impl KvStore, logger: &Logger, slug: &str, archived: bool) -> Result<()> {
+    let action = if archived { "archive" } else { "unarchive" };
+    
+    let project = match api_client.get_project(slug).await {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("{} Failed to get project: {}", icons::error(), e);
+            logger.log(&format!("Failed to {} project {}: {}", action, slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    };
+    if project.archived == archived {
+        println!("{} Project '{}' is already {}d", icons::hint(), slug, action);
+        return Ok(());
+    }
+    
+    match api_client.update_project(slug, Project { archived, ..project }).await {
+        Ok(_) => {
+            println!("{} Project '{}' {}d", icons::ok(), slug, action);
+            if archived {
+                println!("   {} Its time entries are kept; use --include-archived to see it in lists and menus", icons::hint());
+            }
+            logger.log(&format!("{}d project: {}", if archived { "Archive" } else { "Unarchive" }, slug)).await?;
+        }
+        Err(e) => {
+            eprintln!("{} Failed to {} project: {}", icons::error(), action, e);
+            logger.log(&format!("Failed to {} project {}: {}", action, slug, e)).await?;
+            return Err(exit_code::reported(&e));
+        }
+    }
+    
+    Ok(())
+}
+
+/// One line of `project stats`
+struct ProjectStats<'a> {
+    project: &'a Project,
+    /// The running session counts up to now
+    total_seconds: i64,
+    sessions: usize,
+    last_activity: Option<i64>,
+    running: bool,
+    paused: bool,
+}
+
+/// Every project with its total time, number of sessions, last activity and state, most worked first
+pub async fn show_project_stats(api_client: &impl KvStore, logger: &Logger) -> Result<()> {
+    logger.log("Showed project stats").await?;
+    
+    let projects = match api_client.get_projects().await.map(visible_projects) {
+        Ok(projects) => projects,
+        Err(e) => {
+            logger.log(&format!("Failed to get projects: {}", e)).await?;
+            return Err(output::failure_from(&e, format!("Failed to get projects: {}", e)));
         }
+    };
+    
+    let mut stats: Vec<ProjectStats> = Vec::new();
+    for (project, entries) in projects.iter().zip(api_client.get_time_entries_of(&projects).await) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{} Failed to get time entries for '{}': {}", icons::warning(), project.slug, e);
+                logger.log(&format!("Failed to get time entries for {}: {}", project.slug, e)).await?;
+                continue;
+            }
+        };
+        let running = is_project_running(&entries);
+        let sessions = reconstruct_sessions(&entries).len() + usize::from(running);
+        stats.push(ProjectStats {
+            project,
+            total_seconds: calculate_total_time(&entries, RunningPolicy::Include),
+            sessions,
+            last_activity: entries.iter().map(|e| e.timestamp).max(),
+            running,
+            paused: paused_since(&entries).is_some(),
+        });
+    }
+    stats.sort_by_key(|stat| Reverse(stat.total_seconds));
+    
+    if output::json() {
+        let projects: Vec<serde_json::Value> = stats.iter()
+            .map(|stat| serde_json::json!({
+                "project": stat.project.slug,
+                "total_seconds": stat.total_seconds,
+                "sessions": stat.sessions,
+                "last_activity": stat.last_activity,
+                "running": stat.running,
+                "paused": stat.paused,
+            }))
+            .collect();
+        output::print_json(&serde_json::json!({ "projects": projects }))?;
+        return Ok(());
+    }
+    
+    if stats.is_empty() {
+        println!("{} No projects found", icons::list());
+        return Ok(());
     }
     
+    println!("{} Project stats:", icons::report());
+    let mut table = Table::new(vec![Align::Left, Align::Left, Align::Right, Align::Right, Align::Left, Align::Left]).truncate(0);
+    table.row(vec!["Project".to_string(), String::new(), "Total".to_string(), "Sessions".to_string(),
+                   "Last activity".to_string(), "State".to_string()]);
+    for stat in &stats {
+        let state = match (stat.running, stat.paused) {
+            (true, true) => format!("{} paused", icons::pause()),
+            (true, false) => format!("{} running", icons::running()),
+            _ => format!("{} stopped", icons::stopped()),
+        };
+        table.row(vec![
+            format!("{} {}", icons::bullet(), stat.project.name),
+            format!("({})", stat.project.slug),
+            format_duration(stat.total_seconds, DurationStyle::Short),
+            stat.sessions.to_string(),
+            stat.last_activity.map(|ts| format_local_time(ts, locale::current().date_format())).unwrap_or_else(|| "-".to_string()),
+            state,
+        ]);
+    }
+    table.print();
+    
+    Ok(())
+}
+
+/// What `project delete --dry-run` would remove; nothing is written
+async fn print_project_dry_run(api_client: &impl KvStore, logger: &Logger, project: &Project) -> Result<()> {
+    let entries = match api_client.get_time_entries(&project.slug).await {
+        Ok(entries) => entries.len().to_string(),
+        Err(e) => format!("an unknown number of ({})", e),
+    };
+    println!("{} Dry run: would delete project '{}' ({}) and its {} time entries", icons::hint(), project.slug, project.name, entries);
+    logger.log(&format!("Dry run: would delete project {}", project.slug)).await?;
     Ok(())
 }
 
-pub async fn delete_project_with_confirmation(api_client: &ApiClient, logger: &Logger, slug: &str) -> Result<()> {
+pub async fn delete_project_with_confirmation(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, slug: &str, dry_run: bool) -> Result<()> {
     logger.log(&format!("Deleting project: {}", slug)).await?;
     
     // Get project details
     let project = match api_client.get_project(slug).await {
         Ok(project) => project,
         Err(e) => {
-            eprintln!("❌ Failed to get project: {}", e);
+            eprintln!("{} Failed to get project: {}", icons::error(), e);
             logger.log(&format!("Failed to get project {}: {}", slug, e)).await?;
-            return Ok(());
+            return Err(exit_code::reported(&e));
         }
     };
+    if dry_run {
+        return print_project_dry_run(api_client, logger, &project).await;
+    }
     
     // Show selected project and strong warning
-    println!("");
-    println!("🚨 ⚠️  DANGER WARNING ⚠️  🚨");
-    println!("═══════════════════════════════════════════════════════════════");
+    println!();
+    println!("{} {}  DANGER WARNING {}  {}", icons::danger(), icons::warning(), icons::warning(), icons::danger());
+    println!("{}", icons::rule());
     println!("  You are about to DELETE the entire project:");
-    println!("  📁 Name: {}", project.name);
-    println!("  📁 Slug: {}", project.slug);
-    println!("  📁 Description: {}", project.description);
-    println!("");
-    println!("  ❌ This action CANNOT be undone!");
-    println!("  ❌ ALL time entries will be permanently lost!");
-    println!("  ❌ ALL tracking history will be permanently lost!");
-    println!("");
-    println!("  💡 Consider using 'timetracker export' to backup data first");
-    println!("═══════════════════════════════════════════════════════════════");
-    println!("");
-    
-    print!("Are you absolutely sure? Type 'DELETE PROJECT' to confirm: ");
-    io::stdout().flush()?;
-    
-    let mut confirmation = String::new();
-    io::stdin().read_line(&mut confirmation)?;
-    let confirmation = confirmation.trim();
+    println!("  {} Name: {}", icons::project(), project.name);
+    println!("  {} Slug: {}", icons::project(), project.slug);
+    println!("  {} Description: {}", icons::project(), project.description);
+    println!();
+    println!("  {} This action CANNOT be undone!", icons::error());
+    println!("  {} ALL time entries will be permanently lost!", icons::error());
+    println!("  {} ALL tracking history will be permanently lost!", icons::error());
+    println!();
+    println!("  {} Consider using 'timetracker export' to backup data first", icons::hint());
+    println!("{}", icons::rule());
+    println!();
     
-    if confirmation != "DELETE PROJECT" {
-        println!("❌ Operation cancelled. Project is safe.");
-        return Ok(());
+    if !confirm.typed(logger, "DELETE PROJECT").await? {
+        println!("{} Operation cancelled. Project is safe.", icons::error());
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
-    println!("⚠️  Proceeding with project deletion...");
+    println!("{}  Proceeding with project deletion...", icons::warning());
     
     // Delete the project via API
     match api_client.delete_project(slug).await {
         Ok(_) => {
-            println!("🗑️  Successfully deleted project '{}' and all its time entries", slug);
+            println!("{}  Successfully deleted project '{}' and all its time entries", icons::delete(), slug);
             logger.log(&format!("Successfully deleted project: {} ({})", slug, project.name)).await?;
         }
         Err(e) => {
-            eprintln!("❌ Failed to delete project: {}", e);
+            eprintln!("{} Failed to delete project: {}", icons::error(), e);
             logger.log(&format!("Failed to delete project {}: {}", slug, e)).await?;
+            return Err(exit_code::reported(&e));
         }
     }
     
     Ok(())
 }
 
-async fn select_project(api_client: &ApiClient, logger: &Logger, action_name: &str) -> Result<Option<String>> {
+/// Order projects by their last use (latest entry or touch), most recent first; unused ones keep their order at the end
+async fn sort_by_recent_use(api_client: &impl KvStore, projects: &mut [Project]) {
+    let entries = api_client.get_time_entries_of(projects).await;
+    let last_used: HashMap<String, i64> = projects.iter()
+        .zip(entries)
+        .filter_map(|(project, entries)| {
+            let latest_entry = entries.ok()?.iter().map(|e| e.timestamp).max();
+            latest_entry.max(project.last_touched).map(|timestamp| (project.slug.clone(), timestamp))
+        })
+        .collect();
+    projects.sort_by_key(|project| Reverse(last_used.get(&project.slug).copied()));
+}
+
+/// Index of the chosen project: a number from the menu, or a slug (case-insensitive)
+fn parse_project_selection(input: &str, projects: &[Project]) -> Option<usize> {
+    match input.parse::<usize>() {
+        Ok(num) if num >= 1 && num <= projects.len() => Some(num - 1),
+        // Slugs can be numbers too, e.g. "2024"
+        _ => projects.iter().position(|project| project.slug.eq_ignore_ascii_case(input)),
+    }
+}
+
+async fn select_project(api_client: &impl KvStore, logger: &Logger, action_name: &str) -> Result<String> {
     // Get all projects
-    let projects = match api_client.get_projects().await {
+    let mut projects = match api_client.get_projects().await.map(visible_projects) {
         Ok(projects) => {
             if projects.is_empty() {
-                println!("❌ No projects found");
-                return Ok(None);
+                println!("{} No projects found", icons::error());
+                return Err(CommandError::reported(ExitCode::NotFound).into());
             }
             projects
         }
         Err(e) => {
-            eprintln!("❌ Failed to get projects: {}", e);
+            eprintln!("{} Failed to get projects: {}", icons::error(), e);
             logger.log(&format!("Failed to get projects: {}", e)).await?;
-            return Ok(None);
+            return Err(exit_code::reported(&e));
         }
     };
+    sort_by_recent_use(api_client, &mut projects).await;
     
-    // Display all projects
-    println!("📋 Select a project to {}:", action_name);
-    println!("");
+    // Display all projects, most recently used first
+    println!("{} Select a project to {}:", icons::list(), action_name);
+    println!();
     for (index, project) in projects.iter().enumerate() {
         println!("  {}. {} ({}) - {}", 
                  index + 1, 
@@ -1096,8 +5934,8 @@ async fn select_project(api_client: &ApiClient, logger: &Logger, action_name: &s
                  project.description);
     }
     
-    println!("");
-    print!("Select project (1-{}), or 'q' to quit: ", projects.len());
+    println!();
+    print!("Select project (1-{} or slug), or 'q' to quit: ", projects.len());
     io::stdout().flush()?;
     
     // Get user selection
@@ -1106,92 +5944,391 @@ async fn select_project(api_client: &ApiClient, logger: &Logger, action_name: &s
     let input = input.trim();
     
     if input.eq_ignore_ascii_case("q") {
-        println!("❌ {} cancelled", action_name);
-        return Ok(None);
+        println!("{} {} cancelled", icons::error(), action_name);
+        return Err(CommandError::reported(ExitCode::Failure).into());
     }
     
-    let selection: usize = match input.parse::<usize>() {
-        Ok(num) if num >= 1 && num <= projects.len() => num - 1,
-        _ => {
-            println!("❌ Invalid selection. Please enter a number between 1 and {}", projects.len());
-            return Ok(None);
+    let selection: usize = match parse_project_selection(input, &projects) {
+        Some(index) => index,
+        None => {
+            println!("{} Invalid selection. Please enter a number between 1 and {} or a project slug", icons::error(), projects.len());
+            return Err(CommandError::reported(ExitCode::Failure).into());
         }
     };
     
     let selected_project = &projects[selection];
-    Ok(Some(selected_project.slug.clone()))
+    Ok(selected_project.slug.clone())
 }
 
 pub async fn start_tracking_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
+    confirm: Confirm,
     description: Option<String>,
+    options: &StartOptions,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "start tracking").await? {
-        start_tracking(api_client, logger, &project_slug, description).await?;
-    }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "start tracking").await?;
+    start_tracking(api_client, logger, confirm, &project_slug, description, options).await
 }
 
 pub async fn end_tracking_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
-    description: String,
+    confirm: Confirm,
+    description: Option<String>,
+    tags: &[String],
+    force: bool,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "stop tracking").await? {
-        end_tracking(api_client, logger, &project_slug, description).await?;
+    // With a single running project there is nothing to choose
+    if let Ok([project]) = running_projects(api_client).await.as_deref() {
+        println!("{} Stopping '{}', the only running project", icons::stop(), project.slug);
+        return end_tracking(api_client, logger, confirm, &project.slug, description, tags, force).await;
     }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "stop tracking").await?;
+    end_tracking(api_client, logger, confirm, &project_slug, description, tags, force).await
+}
+
+pub async fn pause_tracking_with_selection(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    description: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let project_slug = select_project(api_client, logger, "pause").await?;
+    pause_tracking(api_client, logger, &project_slug, description, force).await
+}
+
+pub async fn resume_tracking_with_selection(
+    api_client: &impl KvStore,
+    logger: &Logger,
+    force: bool,
+) -> Result<()> {
+    let project_slug = select_project(api_client, logger, "resume").await?;
+    resume_tracking(api_client, logger, &project_slug, force).await
 }
 
 pub async fn show_status_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
+    confirm: Confirm,
+    auto_stop: bool,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "check status").await? {
-        show_status(api_client, logger, &project_slug).await?;
-    }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "check status").await?;
+    show_status(api_client, logger, confirm, &project_slug, auto_stop).await
 }
 
 pub async fn list_times_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
+    options: &ListOptions,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "list times").await? {
-        list_times(api_client, logger, &project_slug).await?;
-    }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "list times").await?;
+    list_times(api_client, logger, &project_slug, options).await
 }
 
 pub async fn show_total_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
+    options: &TotalOptions,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "show total").await? {
-        show_total(api_client, logger, &project_slug).await?;
-    }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "show total").await?;
+    show_total(api_client, logger, &project_slug, options).await
 }
 
 pub async fn edit_time_entry_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "edit time entry").await? {
-        edit_time_entry(api_client, logger, &project_slug).await?;
-    }
-    Ok(())
+    let project_slug = select_project(api_client, logger, "edit time entry").await?;
+    edit_time_entry(api_client, logger, &project_slug).await
 }
 
 pub async fn delete_times_with_selection(
-    api_client: &ApiClient,
+    api_client: &impl KvStore,
     logger: &Logger,
-    timestamp: Option<i64>,
-    all: bool,
+    confirm: Confirm,
+    options: &DeleteOptions,
 ) -> Result<()> {
-    if let Some(project_slug) = select_project(api_client, logger, "delete times").await? {
-        delete_times(api_client, logger, &project_slug, timestamp, all).await?;
+    let project_slug = select_project(api_client, logger, "delete times").await?;
+    delete_times(api_client, logger, confirm, &project_slug, options).await
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    
+    fn entry(timestamp: i64, entry_type: &str) -> TimeEntry {
+        TimeEntry { timestamp, entry_type: entry_type.to_string(), description: None, tags: Vec::new() }
     }
-    Ok(())
-} 
\ No newline at end of file
+    
+    fn project(slug: &str) -> Project {
+        Project {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            description: String::new(),
+            last_touched: None,
+            rate: None,
+            budget_hours: None,
+            archived: false,
+            currency: None,
+        }
+    }
+    
+    #[test]
+    fn running_policy_matrix() {
+        let now = Utc::now().timestamp();
+        let completed = vec![entry(1_000, "start"), entry(1_600, "end")];
+        let mut running = completed.clone();
+        running.push(entry(now - 300, "start"));
+        let mut paused = running.clone();
+        paused.push(entry(now - 100, "pause"));
+        
+        // Without a running session every policy gives the completed sessions only
+        for policy in [RunningPolicy::Exclude, RunningPolicy::Include, RunningPolicy::ClosedAt(now)] {
+            assert_eq!(calculate_total_time(&completed, policy), 600);
+        }
+        
+        assert_eq!(calculate_total_time(&running, RunningPolicy::Exclude), 600);
+        let included = calculate_total_time(&running, RunningPolicy::Include);
+        assert!((900..=905).contains(&included), "{}", included);
+        assert_eq!(calculate_total_time(&running, RunningPolicy::ClosedAt(now - 200)), 700);
+        // Closing before the running session started counts nothing for it
+        assert_eq!(calculate_total_time(&running, RunningPolicy::ClosedAt(now - 400)), 600);
+        
+        // An open pause stops the clock, whichever way the session is closed
+        assert_eq!(calculate_total_time(&paused, RunningPolicy::Exclude), 600);
+        assert_eq!(calculate_total_time(&paused, RunningPolicy::Include), 800);
+        assert_eq!(calculate_total_time(&paused, RunningPolicy::ClosedAt(now - 200)), 700);
+        
+        assert_eq!(RunningPolicy::from_flags(true, Some(now)), RunningPolicy::Exclude);
+        assert_eq!(RunningPolicy::from_flags(false, Some(now)), RunningPolicy::ClosedAt(now));
+        assert_eq!(RunningPolicy::from_flags(false, None), RunningPolicy::Include);
+    }
+    
+    #[tokio::test]
+    async fn totals_and_sessions_from_memory_store() {
+        let store = MemoryStore::default();
+        store.add_time_entries("demo", vec![
+            entry(1_000, "start"),
+            entry(1_300, "pause"),
+            entry(1_500, "resume"),
+            entry(2_000, "end"),
+            entry(3_000, "start"),
+        ]).await.unwrap();
+        let entries = store.get_time_entries("demo").await.unwrap();
+        
+        let sessions = reconstruct_sessions(&entries);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!((sessions[0].start, sessions[0].end), (1_000, 2_000));
+        assert_eq!(sessions[0].pauses, vec![(1_300, 1_500)]);
+        assert_eq!(sessions[0].duration(), 800);
+        
+        assert_eq!(calculate_total_time(&entries, RunningPolicy::Exclude), 800);
+        assert_eq!(calculate_total_time(&entries, RunningPolicy::ClosedAt(3_600)), 1_400);
+    }
+    
+    #[test]
+    fn sessions_with_running_follow_the_policy() {
+        let mut entries = vec![entry(1_000, "start"), entry(1_600, "end"), entry(2_000, "start"), entry(2_300, "pause")];
+        entries[2].description = Some("Draft".to_string());
+        
+        assert_eq!(sessions_with_running(&entries, RunningPolicy::Exclude).len(), 1);
+        
+        let sessions = sessions_with_running(&entries, RunningPolicy::ClosedAt(2_500));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!((sessions[1].start, sessions[1].end), (2_000, 2_500));
+        assert_eq!(sessions[1].description.as_deref(), Some("Draft"));
+        // The open pause ends where the session is closed
+        assert_eq!(sessions[1].duration(), 300);
+        
+        // Closed before it started, the running session adds nothing
+        assert_eq!(sessions_with_running(&entries, RunningPolicy::ClosedAt(1_900)).len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn failed_commands_return_their_exit_code() {
+        let store = MemoryStore::default();
+        let logger = Logger::disabled();
+        store.add_project(project("demo")).await.unwrap();
+        
+        let error = end_tracking(&store, &logger, Confirm::default(), "demo", Some("done".to_string()), &[], false).await.unwrap_err();
+        assert_eq!(exit_code::code_of(&error), Some(ExitCode::NotFound));
+        // Already explained on stderr, main only sets the exit code
+        assert!(exit_code::is_reported(&error));
+        
+        let error = end_tracking(&store, &logger, Confirm::default(), "unrelated-project", Some("done".to_string()), &[], false).await.unwrap_err();
+        assert_eq!(exit_code::code_of(&error), Some(ExitCode::NotFound));
+        
+        // --yes never switches to a suggested project, even one that could be stopped
+        store.add_time_entry("demo", entry(Utc::now().timestamp() - 60, "start"), false).await.unwrap();
+        let error = end_tracking(&store, &logger, Confirm::new(true), "dmeo", Some("done".to_string()), &[], false).await.unwrap_err();
+        assert_eq!(exit_code::code_of(&error), Some(ExitCode::NotFound));
+    }
+    
+    #[tokio::test]
+    async fn summary_export_leaves_out_unreadable_projects() {
+        let store = MemoryStore::default();
+        for slug in ["broken", "demo"] {
+            store.add_project(project(slug)).await.unwrap();
+        }
+        store.add_time_entries("demo", vec![entry(1_000, "start"), entry(1_600, "end")]).await.unwrap();
+        store.set_key("projects/broken", serde_json::json!("not a list")).await.unwrap();
+        let dir = std::env::temp_dir().join(format!("timetracker-test-summary-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        
+        let result = export_summary(&store, &Logger::disabled(), dir.to_str().unwrap(), "summary.json", "now", false, RunningPolicy::Exclude).await;
+        let summaries: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(dir.join("summary.json")).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        
+        assert!(exit_code::is_reported(&result.unwrap_err()));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0]["slug"], "demo");
+        assert_eq!(summaries[0]["total_seconds"], 600);
+    }
+    
+    #[test]
+    fn running_state_skips_interleaved_unknown_types() {
+        let running = vec![
+            entry(100, "start"),
+            entry(150, "marker"),
+            entry(200, "end"),
+            entry(250, "break"),
+            entry(300, "start"),
+            entry(350, "note"),
+            entry(400, "marker"),
+        ];
+        assert!(is_project_running(&running));
+        
+        let stopped = vec![
+            entry(100, "start"),
+            entry(150, "marker"),
+            entry(200, "end"),
+            entry(250, "break"),
+        ];
+        assert!(!is_project_running(&stopped));
+        assert!(!is_project_running(&[entry(100, "marker")]));
+        
+        // Unknown types neither end a session nor add time
+        assert_eq!(calculate_total_time(&stopped, RunningPolicy::Include), 100);
+        assert_eq!(calculate_total_time(&running, RunningPolicy::ClosedAt(450)), 250);
+    }
+    
+    #[test]
+    fn rounding_at_increment_boundaries() {
+        // 15 minute increments
+        assert_eq!(round_duration(900, 15, RoundMode::Up), 900);
+        assert_eq!(round_duration(901, 15, RoundMode::Up), 1_800);
+        assert_eq!(round_duration(900, 15, RoundMode::Nearest), 900);
+        assert_eq!(round_duration(901, 15, RoundMode::Nearest), 900);
+        // Halfway rounds up, just below stays
+        assert_eq!(round_duration(1_349, 15, RoundMode::Nearest), 900);
+        assert_eq!(round_duration(1_350, 15, RoundMode::Nearest), 1_800);
+        // No increment or nothing worked leaves the duration as it is
+        assert_eq!(round_duration(901, 0, RoundMode::Up), 901);
+        assert_eq!(round_duration(0, 15, RoundMode::Up), 0);
+    }
+    
+    #[test]
+    fn slugs_with_spaces_or_slashes_are_rejected() {
+        assert!(validate_slug("my-project_2").is_ok());
+        assert!(validate_slug("my project").is_err());
+        assert!(validate_slug("a/b").is_err());
+        assert!(validate_slug("").is_err());
+    }
+    
+    #[test]
+    fn queued_entries_are_checked_before_replay() {
+        let existing = vec![entry(1_000, "start"), entry(1_600, "end")];
+        let queued = vec![
+            // Written before the connection dropped
+            entry(1_600, "end"),
+            entry(2_000, "end"),
+            entry(2_100, "resume"),
+            entry(2_200, "start"),
+            entry(2_300, "pause"),
+            entry(2_400, "pause"),
+            entry(2_500, "resume"),
+            entry(2_600, "start"),
+            entry(2_700, "end"),
+            entry(2_650, "note"),
+        ];
+        let (valid, invalid) = plan_replay(&existing, &queued);
+        let timestamps = |entries: Vec<TimeEntry>| entries.iter().map(|e| e.timestamp).collect::<Vec<_>>();
+        assert_eq!(timestamps(valid), vec![2_200, 2_300, 2_500, 2_700]);
+        let reasons: Vec<_> = invalid.iter().map(|(e, reason)| (e.timestamp, *reason)).collect();
+        assert_eq!(reasons, vec![
+            (1_600, "already recorded"),
+            (2_000, "the project was not running"),
+            (2_100, "the project was not paused"),
+            (2_400, "the project was already paused"),
+            (2_600, "the project was already running"),
+            (2_650, "not after the project's latest entry"),
+        ]);
+    }
+    
+    #[test]
+    fn tag_filter_looks_at_the_start_entry() {
+        let mut tagged_start = entry(1_000, "start");
+        tagged_start.tags = vec!["Dev".to_string()];
+        let mut tagged_end = entry(3_600, "end");
+        tagged_end.tags = vec!["dev".to_string()];
+        let entries = vec![
+            tagged_start, entry(1_600, "end"),
+            // Tagged on stop only
+            entry(3_000, "start"), tagged_end,
+        ];
+        
+        let filtered = entries_with_tag(&entries, "dev");
+        assert_eq!(filtered.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![1_000, 1_600]);
+        assert_eq!(calculate_total_time(&filtered, RunningPolicy::Exclude), 600);
+    }
+    
+    #[test]
+    fn csv_export_names_the_key_it_cannot_decode() {
+        let entries = serde_json::json!([{ "timestamp": 1_000, "type": "start" }, { "timestamp": 1_600, "type": "end" }]);
+        let csv = value_to_csv("projects/demo", &entries, RunningPolicy::Exclude).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        
+        let error = value_to_csv("projects/broken", &serde_json::json!("not a list"), RunningPolicy::Exclude).unwrap_err();
+        assert!(error.to_string().contains("'projects/broken'"), "{}", error);
+        assert!(value_to_csv("projects", &serde_json::json!({ "slug": "demo" }), RunningPolicy::Exclude).is_err());
+    }
+    
+    #[test]
+    fn merging_into_the_running_session_keeps_its_start_description() {
+        let described = |timestamp: i64, entry_type: &str, description: &str| TimeEntry {
+            description: Some(description.to_string()),
+            ..entry(timestamp, entry_type)
+        };
+        let mut entries = vec![
+            described(1_000, "start", "Layout"),
+            described(1_600, "end", "Header done"),
+            described(1_700, "start", "Footer"),
+        ];
+        join_sessions(&mut entries, 1_000, 1_600, 1_700, None).unwrap();
+        
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].description.as_deref(), Some("Layout; Footer"));
+        assert_eq!((entries[1].entry_type.as_str(), entries[1].description.as_deref()), ("note", Some("Header done")));
+        assert!(is_project_running(&entries));
+        
+        // Changed in the meantime
+        assert!(join_sessions(&mut entries, 1_000, 1_600, 1_700, None).is_err());
+    }
+    
+    #[tokio::test]
+    async fn undo_refuses_to_restore_an_entry_added_again() {
+        let store = MemoryStore::default();
+        let stop = serde_json::to_value(entry(1_600, "end")).unwrap();
+        let deleted = Change::List { key: "projects/demo".to_string(), removed: vec![(1, stop.clone())], added: Vec::new() };
+        
+        store.add_time_entries("demo", vec![entry(1_000, "start")]).await.unwrap();
+        assert!(check_undo(&store, &deleted).await.is_ok());
+        
+        // Recorded again after the delete, e.g. by `time stop --at`
+        store.add_time_entries("demo", vec![entry(1_600, "end")]).await.unwrap();
+        let error = check_undo(&store, &deleted).await.unwrap_err();
+        assert!(error.to_string().contains("added again"), "{}", error);
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Defaults from `~/.config/timetracker/config.toml`. CLI flags and environment variables win over these.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub default_project: Option<String>,
+    pub timezone: Option<String>,
+    pub rounding_minutes: Option<u32>,
+    pub exclusive_tracking: Option<bool>,
+    pub max_session_hours: Option<f64>,
+    /// `workspace.<alias> = "<api project>"` lines, for `--workspace <alias>`
+    #[serde(rename = "workspace")]
+    pub workspaces: BTreeMap<String, String>,
+    /// Keys this version doesn't know, warned about instead of failing on
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+/// Load the config file: the `--config` path (which has to exist), else the default location if present
+pub fn init(path: Option<&str>) -> Result<()> {
+    let config = match path {
+        Some(path) => load(&PathBuf::from(path))?,
+        None => match default_path() {
+            Some(path) if path.exists() => load(&path)?,
+            _ => Config::default(),
+        },
+    };
+    let _ = CONFIG.set(config);
+    Ok(())
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Project used when a time command is given no slug: TIMETRACKER_DEFAULT_PROJECT, else `default_project`
+pub fn default_project() -> Option<String> {
+    env::var("TIMETRACKER_DEFAULT_PROJECT").ok()
+        .filter(|slug| !slug.trim().is_empty())
+        .or_else(|| get().default_project.clone())
+}
+
+/// Whether `time start` refuses to run next to another project: TIMETRACKER_EXCLUSIVE, else `exclusive_tracking`
+pub fn exclusive_tracking() -> bool {
+    env::var("TIMETRACKER_EXCLUSIVE").ok()
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes"))
+        .or(get().exclusive_tracking)
+        .unwrap_or(false)
+}
+
+/// Hours after which a running session looks forgotten: MAX_SESSION_HOURS, else `max_session_hours`, else 16
+pub fn max_session_hours() -> f64 {
+    env::var("MAX_SESSION_HOURS").ok()
+        .and_then(|value| value.trim().parse().ok())
+        .or(get().max_session_hours)
+        .filter(|hours: &f64| *hours > 0.0)
+        .unwrap_or(16.0)
+}
+
+/// API project of `--workspace`: the project an alias in the config maps to, else the name itself
+pub fn workspace_project(name: &str) -> Result<String> {
+    let project = get().workspaces.get(name).cloned().unwrap_or_else(|| name.to_string());
+    // Ends up in the API path and, for the file backend, as a directory name
+    if project.is_empty() || !project.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.')) || project.starts_with('.') {
+        return Err(anyhow!("Invalid workspace '{}'. Use letters, numbers, '-', '_' and '.'", project));
+    }
+    Ok(project)
+}
+
+fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config").join("timetracker").join("config.toml"))
+}
+
+fn load(path: &PathBuf) -> Result<Config> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read config file {}: {}", path.display(), e))?;
+    parse(&content).map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))
+}
+
+fn parse(content: &str) -> Result<Config> {
+    let config: Config = toml::from_str(content)?;
+    for key in config.unknown.keys() {
+        eprintln!("Warning: Ignoring unknown config key '{}'", key);
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_file_is_read_as_toml() {
+        let config = parse(r#"
+            default_project = "my-website" # comment
+            rounding_minutes = 15
+            max_session_hours = 10
+            exclusive_tracking = true
+            workspace.client = "client-api"
+        "#).unwrap();
+        assert_eq!(config.default_project.as_deref(), Some("my-website"));
+        assert_eq!(config.rounding_minutes, Some(15));
+        assert_eq!(config.max_session_hours, Some(10.0));
+        assert_eq!(config.exclusive_tracking, Some(true));
+        assert_eq!(config.workspaces.get("client").map(String::as_str), Some("client-api"));
+
+        let config = parse("[workspace]\npersonal = \"me\"").unwrap();
+        assert_eq!(config.workspaces.get("personal").map(String::as_str), Some("me"));
+
+        assert!(parse("rounding_minutes = -5").is_err());
+        assert!(parse("default_project = my-website").is_err());
+        assert!(parse("colour = \"blue\"").unwrap().default_project.is_none());
+    }
+}
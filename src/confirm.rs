@@ -0,0 +1,52 @@
+use crate::logger::Logger;
+use anyhow::Result;
+use std::io::{self, Write};
+
+/// How confirmations are answered in this run: asked on the terminal, or all yes with `--yes`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Confirm {
+    assume_yes: bool,
+}
+
+impl Confirm {
+    pub fn new(assume_yes: bool) -> Self {
+        Self { assume_yes }
+    }
+
+    pub fn assume_yes(self) -> bool {
+        self.assume_yes
+    }
+
+    /// Ask a y/N question, e.g. "Apply these changes?"
+    pub async fn ask(self, logger: &Logger, question: &str) -> Result<bool> {
+        if self.assume_yes {
+            println!("{} (y/N): y (--yes)", question);
+            logger.log(&format!("⚠️ Auto-confirmed with --yes: {}", question)).await?;
+            return Ok(true);
+        }
+
+        print!("{} (y/N): ", question);
+        io::stdout().flush()?;
+        let mut confirmation = String::new();
+        io::stdin().read_line(&mut confirmation)?;
+        let confirmation = confirmation.trim();
+
+        Ok(confirmation.eq_ignore_ascii_case("y") || confirmation.eq_ignore_ascii_case("yes"))
+    }
+
+    /// Ask for a typed confirmation like "DELETE ALL" before a destructive operation
+    pub async fn typed(self, logger: &Logger, phrase: &str) -> Result<bool> {
+        if self.assume_yes {
+            println!("Are you absolutely sure? Type '{}' to confirm: {} (--yes)", phrase, phrase);
+            logger.log(&format!("⚠️ Auto-confirmed '{}' with --yes", phrase)).await?;
+            return Ok(true);
+        }
+
+        print!("Are you absolutely sure? Type '{}' to confirm: ", phrase);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim() == phrase)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::store::VersionConflict;
+use std::fmt;
+
+/// Exit status of a run, so scripts can tell "nothing to do" from a broken connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    /// Anything not covered below, e.g. invalid input
+    Failure = 1,
+    /// The project, entry or running session to act on doesn't exist
+    NotFound = 2,
+    /// Login failed or the token was rejected
+    Auth = 3,
+    /// The API could not be reached or timed out
+    Network = 4,
+    /// Another client kept changing the same data
+    Conflict = 5,
+}
+
+/// A failed command together with the exit code it ends the run with
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: ExitCode,
+    /// None when the command already explained the failure on stderr
+    message: Option<String>,
+}
+
+impl CommandError {
+    pub fn new(code: ExitCode, message: impl Into<String>) -> Self {
+        Self { code, message: Some(message.into()) }
+    }
+    
+    /// A failure the command already reported to the user, only its exit code is left for `main`
+    pub fn reported(code: ExitCode) -> Self {
+        Self { code, message: None }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{}", message),
+            None => write!(f, "exited with code {}", self.code as i32),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Turn an error the command already printed into one that only carries its exit code
+pub fn reported(error: &anyhow::Error) -> anyhow::Error {
+    CommandError::reported(code_of(error).unwrap_or(ExitCode::Failure)).into()
+}
+
+/// Whether the error was already shown to the user, so `main` only has to exit with its code
+pub fn is_reported(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<CommandError>().is_some_and(|e| e.message.is_none())
+}
+
+/// Exit code for an error that made it all the way up to `main`
+pub fn classify(error: &anyhow::Error) -> i32 {
+    code_of(error).unwrap_or(ExitCode::Failure) as i32
+}
+
+/// The specific exit code of an error, if any cause in its chain has one
+pub fn code_of(error: &anyhow::Error) -> Option<ExitCode> {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<CommandError>() {
+            return Some(e.code);
+        }
+        if cause.is::<VersionConflict>() {
+            return Some(ExitCode::Conflict);
+        }
+        if cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect() || e.is_timeout()) {
+            return Some(ExitCode::Network);
+        }
+    }
+    None
+}
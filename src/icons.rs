@@ -0,0 +1,171 @@
+use std::env;
+use std::sync::OnceLock;
+
+static PLAIN: OnceLock<bool> = OnceLock::new();
+
+/// Use ASCII instead of emoji for this run: `--no-emoji`, or NO_COLOR set to anything non-empty
+pub fn init(no_emoji: bool) {
+    let _ = PLAIN.set(no_emoji || no_color());
+}
+
+pub fn plain() -> bool {
+    *PLAIN.get_or_init(no_color)
+}
+
+/// The NO_COLOR convention (https://no-color.org)
+pub fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+fn pick(icon: &'static str, ascii: &'static str) -> &'static str {
+    if plain() {
+        ascii
+    } else {
+        icon
+    }
+}
+
+pub fn ok() -> &'static str {
+    pick("✅", "OK:")
+}
+
+pub fn error() -> &'static str {
+    pick("❌", "ERROR:")
+}
+
+pub fn warning() -> &'static str {
+    pick("⚠️", "WARNING:")
+}
+
+pub fn hint() -> &'static str {
+    pick("💡", "HINT:")
+}
+
+pub fn danger() -> &'static str {
+    pick("🚨", "!!")
+}
+
+pub fn timer() -> &'static str {
+    pick("⏱️", "*")
+}
+
+pub fn start() -> &'static str {
+    pick("▶️", ">")
+}
+
+pub fn stop() -> &'static str {
+    pick("⏹️", "[]")
+}
+
+pub fn pause() -> &'static str {
+    pick("⏸️", "||")
+}
+
+pub fn resume() -> &'static str {
+    pick("⏯️", ">>")
+}
+
+pub fn note() -> &'static str {
+    pick("📝", "*")
+}
+
+pub fn unknown() -> &'static str {
+    pick("❔", "?")
+}
+
+pub fn running() -> &'static str {
+    pick("🟢", "[on]")
+}
+
+pub fn stopped() -> &'static str {
+    pick("🔴", "[off]")
+}
+
+pub fn project() -> &'static str {
+    pick("📁", "-")
+}
+
+pub fn money() -> &'static str {
+    pick("💰", "$")
+}
+
+pub fn date() -> &'static str {
+    pick("📅", "-")
+}
+
+pub fn list() -> &'static str {
+    pick("📋", "#")
+}
+
+pub fn report() -> &'static str {
+    pick("📊", "#")
+}
+
+pub fn search() -> &'static str {
+    pick("🔎", "?")
+}
+
+pub fn inspect() -> &'static str {
+    pick("🔍", "?")
+}
+
+pub fn bookmark() -> &'static str {
+    pick("🔖", "#")
+}
+
+pub fn sync() -> &'static str {
+    pick("🔄", "~")
+}
+
+pub fn offline() -> &'static str {
+    pick("📴", "OFFLINE:")
+}
+
+pub fn add() -> &'static str {
+    pick("➕", "+")
+}
+
+pub fn delete() -> &'static str {
+    pick("🗑️", "-")
+}
+
+pub fn skip() -> &'static str {
+    pick("⏭️", "-")
+}
+
+pub fn touch() -> &'static str {
+    pick("👆", "*")
+}
+
+pub fn undo() -> &'static str {
+    pick("↩️", "<-")
+}
+
+pub fn bullet() -> &'static str {
+    pick("•", "*")
+}
+
+pub fn arrow() -> &'static str {
+    pick("→", "->")
+}
+
+/// One filled slot of `time timeline`
+pub fn block() -> &'static str {
+    pick("█", "#")
+}
+
+pub fn dash() -> &'static str {
+    pick("–", "-")
+}
+
+pub fn ellipsis() -> &'static str {
+    pick("…", "...")
+}
+
+/// Horizontal rule framing the danger warnings
+pub fn rule() -> &'static str {
+    pick(
+        "═══════════════════════════════════════════════════════════════",
+        "===============================================================",
+    )
+}
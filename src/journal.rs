@@ -0,0 +1,215 @@
+use crate::icons;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Actions kept for `time undo`; older ones are dropped
+const MAX_ACTIONS: usize = 20;
+
+/// A single key written by an action, with what is needed to write it back
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    /// Items dropped from a list (with their former position) and items added to it
+    List {
+        key: String,
+        removed: Vec<(usize, Value)>,
+        added: Vec<Value>,
+    },
+    /// A whole value that was created (no `before`), deleted (no `after`) or replaced
+    Value {
+        key: String,
+        before: Option<Value>,
+        after: Option<Value>,
+    },
+}
+
+impl Change {
+    pub fn key(&self) -> &str {
+        match self {
+            Change::List { key, .. } | Change::Value { key, .. } => key,
+        }
+    }
+}
+
+/// Everything one command changed in the backend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Action {
+    /// Unix timestamp of the command
+    pub at: i64,
+    /// The command line, e.g. "time start my-website"
+    pub command: String,
+    pub changes: Vec<Change>,
+}
+
+/// Local JSON file with the most recent actions, written after every successful change
+struct Journal {
+    path: PathBuf,
+    current: Mutex<Option<Action>>,
+}
+
+static JOURNAL: OnceLock<Option<Journal>> = OnceLock::new();
+
+/// Journal to UNDO_JOURNAL, else .undo_journal.json next to the token cache; without a call (the in-memory
+/// backend) nothing is journaled. Each workspace gets its own file, so `time undo` only reverses actions of the
+/// workspace it runs in.
+pub fn init(workspace: Option<&str>) {
+    let _ = JOURNAL.set(Some(Journal { path: default_path(workspace), current: Mutex::new(None) }));
+}
+
+fn default_path(workspace: Option<&str>) -> PathBuf {
+    let path = match env::var("UNDO_JOURNAL").ok().filter(|path| !path.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let token_cache = env::var("TOKEN_CACHE_FILE").unwrap_or_else(|_| ".token_cache.json".to_string());
+            Path::new(&token_cache).with_file_name(".undo_journal.json")
+        }
+    };
+    match workspace {
+        Some(workspace) => workspace_path(&path, workspace),
+        None => path,
+    }
+}
+
+/// `.undo_journal.json` becomes `.undo_journal.<workspace>.json`
+fn workspace_path(path: &Path, workspace: &str) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    match path.extension() {
+        Some(extension) => path.with_file_name(format!("{}.{}.{}", stem, workspace, extension.to_string_lossy())),
+        None => path.with_file_name(format!("{}.{}", stem, workspace)),
+    }
+}
+
+fn journal() -> Option<&'static Journal> {
+    JOURNAL.get_or_init(|| None).as_ref()
+}
+
+/// File the journal is kept in, if journaling is on
+pub fn path() -> Option<&'static Path> {
+    journal().map(|journal| journal.path.as_path())
+}
+
+/// Collect the following changes as one action; commands outside an action (like `time undo`) aren't journaled
+pub fn begin(command: &str) {
+    if let Some(journal) = journal() {
+        if let Ok(mut current) = journal.current.lock() {
+            *current = Some(Action { at: chrono::Utc::now().timestamp(), command: command.to_string(), changes: Vec::new() });
+        }
+    }
+}
+
+/// Stop collecting changes, so the writes of `time undo` don't end up in the previous command's action
+pub fn end() {
+    if let Some(journal) = journal() {
+        if let Ok(mut current) = journal.current.lock() {
+            *current = None;
+        }
+    }
+}
+
+/// Note a successful write of `key`; lists only keep the items that differ
+pub fn record(key: &str, before: Option<&Value>, after: Option<&Value>) {
+    let Some(journal) = journal() else {
+        return;
+    };
+    let Ok(mut current) = journal.current.lock() else {
+        return;
+    };
+    let Some(action) = current.as_mut() else {
+        return;
+    };
+
+    let change = match (before, after) {
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            let (removed, added) = diff_lists(before, after);
+            Change::List { key: key.to_string(), removed, added }
+        }
+        _ => Change::Value { key: key.to_string(), before: before.cloned(), after: after.cloned() },
+    };
+    action.changes.push(change);
+
+    // Written right away, so the journal is complete even if a later step fails
+    let replace_last = action.changes.len() > 1;
+    if let Err(e) = journal.save_action(action.clone(), replace_last) {
+        eprintln!("{} Failed to write the undo journal {}: {}", icons::warning(), journal.path.display(), e);
+    }
+}
+
+/// Items of `before` missing from `after` (with their index), and items of `after` that are new
+fn diff_lists(before: &[Value], after: &[Value]) -> (Vec<(usize, Value)>, Vec<Value>) {
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for item in after {
+        *remaining.entry(item.to_string()).or_default() += 1;
+    }
+
+    let mut removed = Vec::new();
+    for (index, item) in before.iter().enumerate() {
+        match remaining.get_mut(&item.to_string()) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => removed.push((index, item.clone())),
+        }
+    }
+
+    let added = after.iter()
+        .filter(|item| match remaining.get_mut(&item.to_string()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+        .cloned()
+        .collect();
+
+    (removed, added)
+}
+
+impl Journal {
+    /// Append an action as the newest one, or replace the newest one while it is still being recorded
+    fn save_action(&self, action: Action, replace_last: bool) -> Result<()> {
+        let mut actions = self.load()?;
+        match actions.last_mut() {
+            Some(last) if replace_last => *last = action,
+            _ => actions.push(action),
+        }
+        let excess = actions.len().saturating_sub(MAX_ACTIONS);
+        actions.drain(..excess);
+        self.save(&actions)
+    }
+
+    /// Recorded actions, oldest first; a missing file is an empty journal
+    fn load(&self) -> Result<Vec<Action>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn save(&self, actions: &[Action]) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(actions)?)?;
+        Ok(())
+    }
+}
+
+/// The newest recorded action
+pub fn last() -> Result<Option<Action>> {
+    match journal() {
+        Some(journal) => Ok(journal.load()?.pop()),
+        None => Ok(None),
+    }
+}
+
+/// Drop the newest action once it has been undone
+pub fn pop() -> Result<()> {
+    if let Some(journal) = journal() {
+        let mut actions = journal.load()?;
+        actions.pop();
+        journal.save(&actions)?;
+    }
+    Ok(())
+}
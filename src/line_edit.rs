@@ -0,0 +1,25 @@
+use anyhow::Result;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use std::io::{self, IsTerminal, Write};
+
+/// Ask for a line with `current` already typed in, so a small change doesn't mean retyping it all.
+/// Returns the trimmed line, or None when the edit was cancelled with Ctrl-C or Ctrl-D.
+/// Without a terminal (e.g. piped input) it reads a plain line and an empty answer keeps `current`.
+pub fn edit(prompt: &str, current: &str) -> Result<Option<String>> {
+    if !io::stdin().is_terminal() {
+        print!("{} [{}]: ", prompt, current);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        return Ok(Some(if input.is_empty() { current.to_string() } else { input.to_string() }));
+    }
+
+    let mut editor = DefaultEditor::new()?;
+    match editor.readline_with_initial(&format!("{}: ", prompt), (current, "")) {
+        Ok(line) => Ok(Some(line.trim().to_string())),
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,77 @@
+use std::env;
+use std::sync::OnceLock;
+
+/// Date and decimal conventions used in reports and listings
+pub struct Locale {
+    date_format: &'static str,
+    decimal_separator: char,
+}
+
+static CURRENT: OnceLock<Locale> = OnceLock::new();
+
+impl Locale {
+    const ISO: Locale = Locale { date_format: "%Y-%m-%d", decimal_separator: '.' };
+
+    pub fn from_tag(tag: &str) -> Self {
+        // Accept POSIX ("de_CH.UTF-8") as well as BCP 47 ("de-CH") style tags
+        let tag = tag.split(['.', '@']).next().unwrap_or("");
+        let mut parts = tag.split(['_', '-']);
+        let language = parts.next().unwrap_or("").to_ascii_lowercase();
+        let region = parts.next().unwrap_or("").to_ascii_uppercase();
+
+        let (date_format, decimal_separator) = match (language.as_str(), region.as_str()) {
+            ("en", "US") => ("%m/%d/%Y", '.'),
+            ("en", "GB" | "IE" | "AU" | "NZ") => ("%d/%m/%Y", '.'),
+            ("de" | "fr" | "it", "CH" | "LI") => ("%d.%m.%Y", '.'),
+            ("de" | "da" | "fi" | "nb" | "no" | "pl" | "cs" | "ru", _) => ("%d.%m.%Y", ','),
+            ("fr" | "es" | "it" | "pt", _) => ("%d/%m/%Y", ','),
+            ("nl", _) => ("%d-%m-%Y", ','),
+            _ => return Self::ISO,
+        };
+
+        Self { date_format, decimal_separator }
+    }
+
+    fn detect() -> Self {
+        // Same precedence as the C library: LC_ALL > LC_TIME > LANG
+        for var in ["LC_ALL", "LC_TIME", "LANG"] {
+            if let Ok(value) = env::var(var) {
+                if !value.is_empty() {
+                    return Self::from_tag(&value);
+                }
+            }
+        }
+        Self::ISO
+    }
+
+    pub fn date_format(&self) -> &'static str {
+        self.date_format
+    }
+
+    pub fn datetime_format(&self) -> String {
+        format!("{} %H:%M:%S %Z", self.date_format)
+    }
+
+    pub fn format_decimal(&self, value: f64, precision: usize) -> String {
+        let text = format!("{:.*}", precision, value);
+        if self.decimal_separator == '.' {
+            text
+        } else {
+            text.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+/// Set the locale for this run: a tag like "de_CH", "auto" to read LANG, or None for ISO formatting
+pub fn init(locale: Option<&str>) {
+    let locale = match locale {
+        None => Locale::ISO,
+        Some("auto") => Locale::detect(),
+        Some(tag) => Locale::from_tag(tag),
+    };
+    let _ = CURRENT.set(locale);
+}
+
+pub fn current() -> &'static Locale {
+    CURRENT.get_or_init(|| Locale::ISO)
+}
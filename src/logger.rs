@@ -1,17 +1,42 @@
-use std::path::PathBuf;
-use tokio::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, OpenOptions};
 use tokio::io::AsyncWriteExt;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 
+/// Rotate once the log is this big, unless LOG_MAX_BYTES says otherwise
+const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the log (timetracker.log.1 is the newest), unless LOG_KEEP says otherwise
+const DEFAULT_KEEP: usize = 3;
+
+#[derive(Clone)]
 pub struct Logger {
-    log_path: PathBuf,
+    /// None when logging is turned off (`--no-log`)
+    log_path: Option<PathBuf>,
+    /// 0 turns rotation off
+    max_bytes: u64,
+    keep: usize,
 }
 
 impl Logger {
-    pub fn new() -> Result<Self> {
-        let log_path = Self::get_log_path()?;
-        Ok(Self { log_path })
+    /// Log to `path` (`--log-file`), else TIMETRACKER_LOG, else the development or home location
+    pub fn new(path: Option<&str>) -> Result<Self> {
+        let log_path = match path.map(str::to_string).or_else(|| std::env::var("TIMETRACKER_LOG").ok()) {
+            Some(path) if !path.trim().is_empty() => PathBuf::from(path),
+            _ => Self::get_log_path()?,
+        };
+        let max_bytes = std::env::var("LOG_MAX_BYTES").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_BYTES);
+        let keep = std::env::var("LOG_KEEP").ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_KEEP);
+        Ok(Self { log_path: Some(log_path), max_bytes, keep })
+    }
+
+    /// A logger that writes nothing, so commands can log unconditionally
+    pub fn disabled() -> Self {
+        Self { log_path: None, max_bytes: 0, keep: 0 }
     }
 
     fn get_log_path() -> Result<PathBuf> {
@@ -35,13 +60,25 @@ impl Logger {
     }
 
     pub async fn log(&self, message: &str) -> Result<()> {
+        let Some(log_path) = &self.log_path else {
+            return Ok(());
+        };
         let timestamp: DateTime<Utc> = Utc::now();
         let log_entry = format!("[{}] {}\n", timestamp.format("%Y-%m-%d %H:%M:%S UTC"), message);
         
+        // Only a stat per message; the files are moved just when the limit is reached
+        if self.max_bytes > 0 {
+            if let Ok(metadata) = fs::metadata(log_path).await {
+                if metadata.len() >= self.max_bytes {
+                    self.rotate(log_path).await?;
+                }
+            }
+        }
+        
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&self.log_path)
+            .open(log_path)
             .await?;
         
         file.write_all(log_entry.as_bytes()).await?;
@@ -50,7 +87,26 @@ impl Logger {
         Ok(())
     }
 
-    pub fn get_current_log_path(&self) -> &PathBuf {
-        &self.log_path
+    /// Shift timetracker.log.1 to .2 and so on, dropping the oldest, and move the log to .1
+    async fn rotate(&self, log_path: &Path) -> Result<()> {
+        if self.keep == 0 {
+            fs::remove_file(log_path).await?;
+            return Ok(());
+        }
+        
+        for index in (1..self.keep).rev() {
+            let from = rotated_path(log_path, index);
+            if fs::try_exists(&from).await.unwrap_or(false) {
+                fs::rename(&from, rotated_path(log_path, index + 1)).await?;
+            }
+        }
+        fs::rename(log_path, rotated_path(log_path, 1)).await?;
+        Ok(())
     }
-} 
\ No newline at end of file
+}
+
+fn rotated_path(log_path: &Path, index: usize) -> PathBuf {
+    let mut path = log_path.as_os_str().to_os_string();
+    path.push(format!(".{}", index));
+    PathBuf::from(path)
+}
@@ -1,22 +1,95 @@
-use clap::{Parser, Subcommand};
-use anyhow::Result;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use anyhow::{Result, anyhow};
 
 mod api;
 mod logger;
 mod commands;
+mod config;
+mod confirm;
+mod exit_code;
+mod icons;
+mod journal;
+mod line_edit;
+mod locale;
+mod output;
+mod queue;
+mod store;
+mod style;
+mod table;
+mod tz;
 
-use api::ApiClient;
+use api::{ApiClient, TimeEntry};
+use commands::{DeleteOptions, DurationStyle, ExportFormat, ExportOptions, GroupBy, ImportFormat, ListOptions, ProjectChanges, ProjectSort, ReportFormat, ReportOptions, RoundMode, Rounding, RunningPolicy, StartOptions, TotalOptions};
+use confirm::Confirm;
 use logger::Logger;
+use queue::OfflineQueue;
+use exit_code::{CommandError, ExitCode};
+use store::{FileStore, KvStore, MemoryStore, ProjectsCache};
 
 /// A minimal CLI tool for time tracking
 #[derive(Parser)]
 #[command(name = "timetracker")]
 #[command(about = "A CLI tool for time tracking with REST API backend")]
+// Listed apart from each command's own options in `--help`
+#[command(next_help_heading = "Global options")]
 struct Cli {
     /// Generate markdown documentation for all commands
     #[arg(long, hide = true)]
     markdown_help: bool,
     
+    /// Config file with defaults (defaults to ~/.config/timetracker/config.toml)
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<String>,
+    
+    /// Locale for dates and decimals in reports (e.g. de_CH, en_US, or "auto" to use LANG)
+    #[arg(long, global = true)]
+    locale: Option<String>,
+    
+    /// Time zone for all dates and times, e.g. Europe/Zurich (defaults to `timezone` in the config, else the system zone)
+    #[arg(long, global = true, value_name = "IANA_NAME")]
+    tz: Option<String>,
+    
+    /// Maximum line width for tables (defaults to the terminal width); long descriptions are truncated
+    #[arg(long, global = true)]
+    max_line_width: Option<usize>,
+    
+    /// Print machine-readable JSON instead of text (project list/stats, time list/total/status/current/today)
+    #[arg(long, global = true)]
+    json: bool,
+    
+    /// Print plain ASCII markers instead of emoji and box drawing (also enabled by NO_COLOR)
+    #[arg(long, global = true)]
+    no_emoji: bool,
+    
+    /// Maximum number of parallel requests when reading several projects, e.g. in reports (defaults to 8)
+    #[arg(long, global = true, value_name = "N")]
+    concurrency: Option<usize>,
+    
+    /// Don't write a log file
+    #[arg(long, global = true, conflicts_with = "log_file")]
+    no_log: bool,
+    
+    /// Log file to write to (defaults to TIMETRACKER_LOG, else timetracker.log in development or ~/.timetracker.log)
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<String>,
+    
+    /// Also show archived projects in project lists and selection menus
+    #[arg(long, global = true)]
+    include_archived: bool,
+    
+    /// Workspace to use instead of API_PROJECT: an alias from `workspace.<alias>` in the config file, else the API project itself
+    #[arg(short, long, global = true, value_name = "NAME")]
+    workspace: Option<String>,
+    
+    /// Trace API requests to stderr: -v for method, URL and status, -vv also headers and bodies (the token is redacted)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    
+    /// Answer all confirmations with yes, including "DELETE ALL" prompts (dangerous, for automation)
+    #[arg(short, long, global = true)]
+    yes: bool,
+    
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -35,7 +108,7 @@ enum Commands {
         #[command(subcommand)]
         action: TimeAction,
     },
-    /// Export all data as JSON files
+    /// Export all data as JSON or CSV files, or all sessions as an iCalendar file
     Export {
         /// Output directory
         #[arg(short, long, default_value = "./DATA")]
@@ -43,36 +116,121 @@ enum Commands {
         /// Filename template with placeholders: {project-name}, {timestamp}, {key-name}
         #[arg(short = 't', long, default_value = "{timestamp}_{key-name}.json")]
         filename_template: String,
+        /// Only export per-project totals and metadata into a single summary file
+        #[arg(long)]
+        summary_only: bool,
+        /// Only export time entries with a timestamp at or after this unix timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        since: Option<i64>,
+        /// File format; CSV files contain one row per session (a .json template suffix becomes .csv or .ics)
+        #[arg(long, value_enum, default_value = "json")]
+        format: ExportFormat,
+        /// Only export completed sessions in summaries, CSV and calendars (by default the running session counts up to now)
+        #[arg(long, conflicts_with = "assume_running_closed_at")]
+        exclude_running: bool,
+        /// Export the running session as if it was stopped at this unix timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        assume_running_closed_at: Option<i64>,
+    },
+    /// Replay time entries queued while the API was unreachable (see OFFLINE_QUEUE)
+    Sync,
+    /// Run timetracker subcommands from a script file (one per line)
+    Run {
+        /// Path to the script file
+        script: String,
+        /// Continue with the next line when a command fails
+        #[arg(long)]
+        keep_going: bool,
+    },
+    /// Print a shell completion script (bash, zsh, fish, elvish or powershell)
+    Completions {
+        /// Shell to generate the script for
+        shell: Shell,
     },
+    /// Print all project slugs, one per line, for completion scripts
+    #[command(name = "__complete-projects", hide = true)]
+    CompleteProjects,
 }
 
 #[derive(Subcommand)]
 enum ProjectAction {
     /// Add a new project
     Add {
-        /// Project slug
-        slug: String,
+        /// Project slug (optional - if not provided, asks for slug, name and description)
+        slug: Option<String>,
         /// Project name
         #[arg(short, long)]
         name: Option<String>,
         /// Project description
         #[arg(short, long)]
         description: Option<String>,
+        /// Hourly rate used to show billable amounts in `time total`
+        #[arg(long)]
+        rate: Option<f64>,
+        /// Currency of the rate (e.g. CHF)
+        #[arg(long, requires = "rate")]
+        currency: Option<String>,
     },
     /// List all projects
-    List,
-    /// Edit project details (name, description, slug)
+    List {
+        /// Only show projects whose name, slug or description contains this (case-insensitive)
+        filter: Option<String>,
+        /// Order the projects instead of listing them as stored
+        #[arg(long, value_enum)]
+        sort: Option<ProjectSort>,
+    },
+    /// Edit project details (name, description, slug, rate, budget)
     Edit {
         /// Project slug (optional - if not provided, shows selection list)
         #[arg()]
         project: Option<String>,
+        /// New name, applied without prompting
+        #[arg(long, requires = "project")]
+        name: Option<String>,
+        /// New description, applied without prompting
+        #[arg(long, requires = "project")]
+        description: Option<String>,
+        /// New slug, applied without prompting (time entries move along)
+        #[arg(long, requires = "project")]
+        new_slug: Option<String>,
     },
     /// Delete a project
     Delete {
         /// Project slug (optional - if not provided, shows selection list)
         #[arg()]
         project: Option<String>,
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show project details
+    Info {
+        /// Project slug
+        slug: String,
+        /// Also print the raw stored values next to their parsed form (for debugging)
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Update the project's "last touched" metadata (does not add a time entry)
+    Touch {
+        /// Project slug
+        slug: String,
+        /// Backdate the marker to a specific unix timestamp (defaults to now)
+        #[arg(short, long)]
+        timestamp: Option<i64>,
     },
+    /// Hide a finished project from lists and menus, keeping all its time entries
+    Archive {
+        /// Project slug
+        slug: String,
+    },
+    /// Show an archived project in lists and menus again
+    Unarchive {
+        /// Project slug
+        slug: String,
+    },
+    /// Overview of all projects: total time, sessions, last activity and whether they run
+    Stats,
 }
 
 #[derive(Subcommand)]
@@ -84,34 +242,310 @@ enum TimeAction {
         /// Optional description
         #[arg(short, long)]
         description: Option<String>,
+        /// Continue the previous session if it was stopped within this many minutes
+        #[arg(long, value_name = "MINUTES", conflicts_with_all = ["ago", "at"])]
+        resume_if_stopped_within: Option<i64>,
+        /// Record the start this long ago (e.g. 20m, 1h30m)
+        #[arg(long, value_name = "DURATION", conflicts_with = "at")]
+        ago: Option<String>,
+        /// Record the start at this time (HH:MM today, YYYY-MM-DD HH:MM or ISO-8601)
+        #[arg(long, value_name = "TIME")]
+        at: Option<String>,
+        /// Refuse to start while another project is running (default with TIMETRACKER_EXCLUSIVE=true)
+        #[arg(long, conflicts_with = "no_exclusive")]
+        exclusive: bool,
+        /// Allow parallel tracking even if TIMETRACKER_EXCLUSIVE is set
+        #[arg(long)]
+        no_exclusive: bool,
+        /// Tag the session, e.g. dev or meeting (repeat or separate with commas)
+        #[arg(long = "tag", value_name = "TAG", value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Close a session running longer than MAX_SESSION_HOURS at that boundary before starting
+        #[arg(long)]
+        auto_stop: bool,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
+        /// Keep running as a stopwatch until Enter (or Ctrl-C) is pressed, then stop the session
+        #[arg(long)]
+        watch: bool,
     },
     /// Stop tracking time for a project
     Stop {
-        /// Project slug (optional - if not provided, shows selection list)
+        /// Project slug (optional - stops the only running project, otherwise shows selection list)
+        #[arg(conflicts_with = "all_running")]
         project: Option<String>,
-        /// Description of what was accomplished during this time session
+        /// Description of what was accomplished during this time session (prompted for if omitted, suggesting the session's notes or its start description)
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Stop every running project with the same timestamp
+        #[arg(long)]
+        all_running: bool,
+        /// Tag the session, e.g. dev or meeting (repeat or separate with commas)
+        #[arg(long = "tag", value_name = "TAG", value_delimiter = ',', conflicts_with = "all_running")]
+        tags: Vec<String>,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
+    },
+    /// Stop whatever is running and start another project at the same moment
+    Switch {
+        /// Project slug to switch to
+        to: String,
+        /// Description of what was accomplished in the stopped session
         #[arg(short, long)]
         description: String,
+        /// Optional description for the new session
+        #[arg(long)]
+        start_description: Option<String>,
+        /// Record the entries even if they lie in the future or before the projects' latest entries
+        #[arg(long)]
+        force: bool,
+    },
+    /// Pause the running session, e.g. for lunch; the break is not counted
+    Pause {
+        /// Project slug (optional - if not provided, shows selection list)
+        project: Option<String>,
+        /// Optional reason for the pause
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
+    },
+    /// Resume a paused session
+    Resume {
+        /// Project slug (optional - if not provided, shows selection list)
+        project: Option<String>,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
+    },
+    /// Start the most recently stopped project again
+    ResumeLast {
+        /// Description for the new session (defaults to the last one, asked for interactively)
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
     },
     /// Check if a project is currently running
     Status {
         /// Project slug (optional - if not provided, shows selection list)
         project: Option<String>,
+        /// Close a session running longer than MAX_SESSION_HOURS at that boundary
+        #[arg(long)]
+        auto_stop: bool,
+    },
+    /// Show every project that is running right now
+    Current,
+    /// Draw a project's sessions of a day on an hour scale
+    Timeline {
+        /// Project slug
+        project: String,
+        /// Day to draw instead of today (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
     },
+    /// Time logged today (or on --date) per project, with a grand total
+    Today {
+        /// Day to sum up instead of today (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+    },
+    /// Reverse the last change made with this tool (see UNDO_JOURNAL)
+    Undo,
     /// List time entries for a project
     List {
         /// Project slug (optional - if not provided, shows selection list)
         project: Option<String>,
+        /// Group entries into start/end sessions with notes shown inline
+        #[arg(long)]
+        pairs: bool,
+        /// Prompt for a description for every entry that has none
+        #[arg(long)]
+        fix_missing: bool,
+        /// Only show entries from this day on (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only show entries up to and including this day (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
     },
     /// Show total time for a project
     Total {
         /// Project slug (optional - if not provided, shows selection list)
         project: Option<String>,
+        /// Sum every project, with a grand total, instead of a single one
+        #[arg(long, conflicts_with_all = ["project", "json_lines"])]
+        all: bool,
+        /// Print each completed session as a JSON object, one per line
+        #[arg(long)]
+        json_lines: bool,
+        /// Only count completed sessions (by default the running session counts up to now)
+        #[arg(long, conflicts_with = "assume_running_closed_at")]
+        exclude_running: bool,
+        /// Count the running session up to now (the default, kept for existing scripts)
+        #[arg(long, alias = "no-running-penalty", hide = true, conflicts_with = "exclude_running")]
+        include_running: bool,
+        /// Count the running session as if it was stopped at this unix timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        assume_running_closed_at: Option<i64>,
+        /// Also show the total in working days of this many hours
+        #[arg(long, value_name = "HOURS", num_args = 0..=1, default_missing_value = "8")]
+        working_day_hours: Option<f64>,
+        /// Warn about sessions longer than this many hours
+        #[arg(long, value_name = "HOURS")]
+        max_session_hours: Option<f64>,
+        /// Warn when more than this many starts/ends have no matching partner
+        #[arg(long, value_name = "COUNT", default_value_t = 0)]
+        max_unmatched: usize,
+        /// Round each session to this many minutes before summing (0 turns off rounding_minutes from the config)
+        #[arg(long, value_name = "MINUTES")]
+        round: Option<u32>,
+        /// How sessions are rounded with --round
+        #[arg(long, value_enum, default_value = "up")]
+        round_mode: RoundMode,
+        /// Hourly rate for the billable amount (defaults to the project's rate)
+        #[arg(long)]
+        rate: Option<f64>,
+        /// Currency of the billable amount (defaults to the project's currency)
+        #[arg(long)]
+        currency: Option<String>,
+        /// Only count sessions whose start carries this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only count sessions starting on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only count sessions starting on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Show hours as a decimal (1.50h) instead of hours, minutes and seconds
+        #[arg(long)]
+        decimal: bool,
+    },
+    /// Report time per project over a date range
+    Report {
+        /// Project slug (optional - if not provided, reports all projects)
+        project: Option<String>,
+        /// First day of the report (YYYY-MM-DD, defaults to 7 days, 4 weeks or 3 months before --to depending on --group-by)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the report (YYYY-MM-DD, defaults to today)
+        #[arg(long)]
+        to: Option<String>,
+        /// Show a grid of projects by day with row and column totals
+        #[arg(long)]
+        matrix: bool,
+        /// Print CSV instead of a table (same as --format csv)
+        #[arg(long, conflicts_with = "format")]
+        csv: bool,
+        /// Output format; markdown lists what was done per period below each project's table
+        #[arg(long, value_enum, default_value = "table")]
+        format: ReportFormat,
+        /// Sum the time per day, week or month (columns of --matrix use the same periods)
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+        /// Round each session to this many minutes before summing (0 turns off rounding_minutes from the config)
+        #[arg(long, value_name = "MINUTES")]
+        round: Option<u32>,
+        /// How sessions are rounded with --round
+        #[arg(long, value_enum, default_value = "up")]
+        round_mode: RoundMode,
+        /// Show hours as a decimal (1.50h) instead of hours and minutes
+        #[arg(long)]
+        decimal: bool,
+        /// Only count completed sessions (by default the running session counts up to now)
+        #[arg(long, conflicts_with = "assume_running_closed_at")]
+        exclude_running: bool,
+        /// Count the running session as if it was stopped at this unix timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        assume_running_closed_at: Option<i64>,
     },
-    /// Edit the description of a time entry
+    /// Add a completed session after the fact
+    Add {
+        /// Project slug
+        project: String,
+        /// Start time (YYYY-MM-DD HH:MM or ISO-8601)
+        #[arg(long)]
+        start: String,
+        /// End time (YYYY-MM-DD HH:MM or ISO-8601)
+        #[arg(long)]
+        end: String,
+        /// Description of what was accomplished during this time session
+        #[arg(short, long)]
+        description: String,
+        /// Add the session even if it overlaps an existing one
+        #[arg(long)]
+        allow_overlap: bool,
+    },
+    /// Add many completed sessions from a CSV or JSON file in a single write
+    ImportEntries {
+        /// Project slug
+        project: String,
+        /// File with start, end and description per session
+        file: String,
+        /// File format (defaults to the file extension)
+        #[arg(long, value_enum)]
+        format: Option<ImportFormat>,
+    },
+    /// Import a Toggl or Clockify CSV export, creating missing projects
+    ImportToggl {
+        /// Detailed CSV export with Project, Start date, Start time, End date and End time columns
+        file: String,
+    },
+    /// Add a note to a project's session without stopping it
+    Note {
+        /// Project slug
+        project: String,
+        /// Note text
+        text: String,
+        /// Record the entry even if it lies in the future or before the project's latest entry
+        #[arg(long)]
+        force: bool,
+    },
+    /// Split a session in two at a point in time, e.g. when it really covered two tasks
+    Split {
+        /// Project slug
+        project: String,
+        /// Where to split: "HH:MM" (today), "YYYY-MM-DD HH:MM" or ISO-8601
+        #[arg(long)]
+        at: String,
+        /// Description of the part before --at (defaults to the session's description)
+        #[arg(long)]
+        description_first: Option<String>,
+        /// Description of the part after --at (defaults to the session's description)
+        #[arg(long)]
+        description_second: Option<String>,
+    },
+    /// Join two consecutive sessions by removing the stop and restart between them
+    Merge {
+        /// Project slug
+        project: String,
+        /// A time in or near the gap between the sessions: "HH:MM" (today), "YYYY-MM-DD HH:MM" or ISO-8601
+        #[arg(long)]
+        at: String,
+    },
+    /// Close sessions that were never stopped, one unmatched start at a time
+    Fix {
+        /// Project slug
+        project: String,
+    },
+    /// Edit the description or time of a time entry
     Edit {
         /// Project slug (optional - if not provided, shows selection list)
         project: Option<String>,
+        /// Edit the entry with this timestamp without prompting (use with --description or --clear)
+        #[arg(short, long, requires = "project")]
+        timestamp: Option<i64>,
+        /// New description for the entry given by --timestamp
+        #[arg(short, long, requires = "timestamp", conflicts_with = "clear")]
+        description: Option<String>,
+        /// Remove the description of the entry given by --timestamp
+        #[arg(long, requires = "timestamp")]
+        clear: bool,
     },
     /// Delete time entries for a project
     Delete {
@@ -123,11 +557,41 @@ enum TimeAction {
         /// Force delete ALL time entries (DANGEROUS! Requires confirmation)
         #[arg(long)]
         all: bool,
+        /// Delete all entries before a date (YYYY-MM-DD) or duration ago (36h, 90d, 12w)
+        #[arg(long)]
+        older_than: Option<String>,
+        /// Delete all entries from this date on (YYYY-MM-DD, inclusive)
+        #[arg(long)]
+        from: Option<String>,
+        /// Delete all entries up to and including this date (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Allow --older-than or --from/--to to delete the start of a running session
+        #[arg(long)]
+        force: bool,
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    // 0 on success, otherwise the exit code of the failure (see exit_code)
+    let code = match run().await {
+        Ok(()) => ExitCode::Success as i32,
+        Err(e) => {
+            // Commands print most failures themselves, with hints on what to do next
+            if !exit_code::is_reported(&e) {
+                eprintln!("Error: {:?}", e);
+            }
+            exit_code::classify(&e)
+        }
+    };
+    std::process::exit(code);
+}
+
+async fn run() -> Result<()> {
     let cli = Cli::parse();
     
     // Handle markdown help generation
@@ -151,97 +615,609 @@ async fn main() -> Result<()> {
         }
     };
     
-    let logger = Logger::new()?;
-    let mut api_client = ApiClient::new()?;
+    // Completion scripts only describe the CLI, no need for the API
+    if let Commands::Completions { shell } = command {
+        print_completions(shell);
+        return Ok(());
+    }
+    
+    // Load .env early so the backend and defaults can be set there as well
+    dotenv::dotenv().ok();
+    
+    config::init(cli.config.as_deref())?;
+    let workspace = cli.workspace.as_deref().map(config::workspace_project).transpose()?;
+    let command = with_default_project(command);
+    
+    tz::init(cli.tz.as_deref().or(config::get().timezone.as_deref()))?;
+    locale::init(cli.locale.as_deref());
+    table::init(cli.max_line_width);
+    output::init(cli.json);
+    let confirm = Confirm::new(cli.yes);
+    icons::init(cli.no_emoji);
+    store::init_concurrency(cli.concurrency);
+    store::init_include_archived(cli.include_archived);
+    
+    let logger = if cli.no_log {
+        Logger::disabled()
+    } else {
+        Logger::new(cli.log_file.as_deref())?
+    };
+    
+    // Ctrl-C ends the run cleanly; this runs on its own task so it also works while a prompt waits for input
+    let interrupt_logger = logger.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            handle_interrupt(&interrupt_logger).await;
+        }
+    });
+    
+    match std::env::var("API_BACKEND").unwrap_or_default().trim() {
+        // In-memory backend: starts empty and is discarded on exit, handy for trying out commands
+        "memory" => {
+            let store = MemoryStore::default();
+            return dispatch(&store, &logger, confirm, command).await;
+        }
+        // Local files, no server needed
+        "file" => {
+            let store = FileStore::from_env(workspace.as_deref())?;
+            journal::init(workspace.as_deref());
+            return dispatch(&store, &logger, confirm, command).await;
+        }
+        "" | "rest" => {}
+        other => return Err(anyhow!("Unknown API_BACKEND '{}', expected rest, file or memory", other)),
+    }
+    
+    let api_client = ApiClient::new(cli.verbose, workspace.as_deref())?;
+    journal::init(workspace.as_deref());
+    // Taken before any request so retries don't shift the time of a queued entry
+    let invoked_at = chrono::Utc::now().timestamp();
+    // Without a connection simple time entries go to the offline queue instead of getting lost
+    let mut offline = OfflineQueue::from_env().zip(offline_entry(&command, invoked_at));
 
     // Attempt to authenticate
+    let mut auth_failure = None;
     if let Err(e) = api_client.authenticate().await {
+        if api::is_unreachable(&e) {
+            if let Some((queue, (project, entry))) = offline.take() {
+                return queue_offline(&queue, &logger, &project, entry).await;
+            }
+        }
+        auth_failure = Some(if api::is_unreachable(&e) { ExitCode::Network } else { ExitCode::Auth });
         eprintln!("Warning: Authentication failed: {}. Some commands may not work.", e);
         logger.log(&format!("Authentication failed: {}", e)).await?;
     }
 
+    match dispatch(&api_client, &logger, confirm, command).await {
+        // The connection dropped after logging in, e.g. on the write itself; sync skips the entry if it got through
+        Err(e) if exit_code::code_of(&e) == Some(ExitCode::Network) => match offline {
+            Some((queue, (project, entry))) => return queue_offline(&queue, &logger, &project, entry).await,
+            None => return Err(e),
+        },
+        result => result?,
+    }
+    // A command that got by without the API still tells scripts that the login failed
+    match auth_failure {
+        Some(code) => Err(CommandError::reported(code).into()),
+        None => Ok(()),
+    }
+}
+
+/// Add an entry to the offline queue and tell the user how it gets to the API later
+async fn queue_offline(queue: &OfflineQueue, logger: &Logger, project: &str, entry: TimeEntry) -> Result<()> {
+    queue.push(project, entry.clone())?;
+    println!("{} API unreachable, queued {} for project '{}' in {}", icons::offline(), entry.entry_type, project, queue.path().display());
+    println!("   {} Run 'timetracker sync' once you're back online", icons::hint());
+    logger.log(&format!("API unreachable, queued {} entry for {} at {}", entry.entry_type, project, entry.timestamp)).await?;
+    Ok(())
+}
+
+async fn dispatch(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, command: Commands) -> Result<()> {
+    match command {
+        Commands::Run { script, keep_going } => {
+            run_script(api_client, logger, confirm, &script, keep_going).await?;
+        }
+        command => {
+            if is_undo(&command) {
+                journal::end();
+            } else {
+                journal::begin(&std::env::args().skip(1).collect::<Vec<_>>().join(" "));
+            }
+            run_command(api_client, logger, confirm, command).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_command(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, command: Commands) -> Result<()> {
     match command {
         Commands::Project { action } => {
             match action {
-                ProjectAction::Add { slug, name, description } => {
-                    commands::add_project(&api_client, &logger, &slug, name, description).await?;
+                ProjectAction::Add { slug, name, description, rate, currency } => {
+                    if let Some(slug) = slug {
+                        commands::add_project(api_client, logger, &slug, name, description, rate, currency).await?;
+                    } else {
+                        commands::add_project_interactive(api_client, logger, name, description, rate, currency).await?;
+                    }
                 }
-                ProjectAction::List => {
-                    commands::list_projects(&api_client, &logger).await?;
+                ProjectAction::List { filter, sort } => {
+                    commands::list_projects(api_client, logger, filter.as_deref(), sort).await?;
                 }
-                ProjectAction::Edit { project } => {
-                    if let Some(project_slug) = project {
-                        commands::edit_project_by_slug(&api_client, &logger, &project_slug).await?;
+                ProjectAction::Edit { project, name, description, new_slug } => {
+                    let changes = ProjectChanges { name, description, slug: new_slug };
+                    if let (Some(project_slug), false) = (&project, changes.is_empty()) {
+                        commands::apply_project_changes(api_client, logger, confirm, project_slug, &changes).await?;
+                    } else if let Some(project_slug) = project {
+                        commands::edit_project_by_slug(api_client, logger, confirm, &project_slug).await?;
                     } else {
-                        commands::edit_project_details(&api_client, &logger).await?;
+                        commands::edit_project_details(api_client, logger, confirm).await?;
                     }
                 }
-                ProjectAction::Delete { project } => {
+                ProjectAction::Delete { project, dry_run } => {
                     if let Some(project_slug) = project {
-                        commands::delete_project_with_confirmation(&api_client, &logger, &project_slug).await?;
+                        commands::delete_project_with_confirmation(api_client, logger, confirm, &project_slug, dry_run).await?;
                     } else {
-                        commands::delete_project_with_selection(&api_client, &logger).await?;
+                        commands::delete_project_with_selection(api_client, logger, confirm, dry_run).await?;
                     }
                 }
+                ProjectAction::Info { slug, raw } => {
+                    commands::project_info(api_client, logger, &slug, raw).await?;
+                }
+                ProjectAction::Touch { slug, timestamp } => {
+                    commands::touch_project(api_client, logger, &slug, timestamp).await?;
+                }
+                ProjectAction::Archive { slug } => {
+                    commands::set_archived(api_client, logger, &slug, true).await?;
+                }
+                ProjectAction::Unarchive { slug } => {
+                    commands::set_archived(api_client, logger, &slug, false).await?;
+                }
+                ProjectAction::Stats => {
+                    commands::show_project_stats(api_client, logger).await?;
+                }
             }
         }
         Commands::Time { action } => {
             match action {
-                TimeAction::Start { project, description } => {
+                TimeAction::Start { project, description, resume_if_stopped_within, ago, at, exclusive, no_exclusive, tags, auto_stop, watch, force } => {
+                    let options = StartOptions {
+                        resume_within: resume_if_stopped_within,
+                        ago,
+                        at,
+                        exclusive: !no_exclusive && (exclusive || config::exclusive_tracking()),
+                        timestamp: None,
+                        tags,
+                        auto_stop,
+                        force,
+                    };
                     if let Some(project_slug) = project {
-                        commands::start_tracking(&api_client, &logger, &project_slug, description).await?;
+                        commands::start_tracking(api_client, logger, confirm, &project_slug, description, &options).await?;
                     } else {
-                        commands::start_tracking_with_selection(&api_client, &logger, description).await?;
+                        commands::start_tracking_with_selection(api_client, logger, confirm, description, &options).await?;
+                    }
+                    if watch {
+                        if let Some(project_slug) = commands::started_this_run().last() {
+                            commands::watch_session(api_client, logger, confirm, project_slug).await?;
+                        }
                     }
                 }
-                TimeAction::Stop { project, description } => {
-                    if let Some(project_slug) = project {
-                        commands::end_tracking(&api_client, &logger, &project_slug, description).await?;
+                TimeAction::Stop { project, description, all_running, tags, force } => {
+                    if all_running {
+                        let Some(description) = description else {
+                            return Err(anyhow!("--all-running needs a --description for all sessions"));
+                        };
+                        commands::stop_all_running(api_client, logger, confirm, description, force).await?;
+                    } else if let Some(project_slug) = project {
+                        commands::end_tracking(api_client, logger, confirm, &project_slug, description, &tags, force).await?;
                     } else {
-                        commands::end_tracking_with_selection(&api_client, &logger, description).await?;
+                        commands::end_tracking_with_selection(api_client, logger, confirm, description, &tags, force).await?;
                     }
                 }
-                TimeAction::Status { project } => {
+                TimeAction::Switch { to, description, start_description, force } => {
+                    commands::switch_tracking(api_client, logger, confirm, &to, description, start_description, force).await?;
+                }
+                TimeAction::Pause { project, description, force } => {
                     if let Some(project_slug) = project {
-                        commands::show_status(&api_client, &logger, &project_slug).await?;
+                        commands::pause_tracking(api_client, logger, &project_slug, description, force).await?;
                     } else {
-                        commands::show_status_with_selection(&api_client, &logger).await?;
+                        commands::pause_tracking_with_selection(api_client, logger, description, force).await?;
                     }
                 }
-                TimeAction::List { project } => {
+                TimeAction::Resume { project, force } => {
                     if let Some(project_slug) = project {
-                        commands::list_times(&api_client, &logger, &project_slug).await?;
+                        commands::resume_tracking(api_client, logger, &project_slug, force).await?;
                     } else {
-                        commands::list_times_with_selection(&api_client, &logger).await?;
+                        commands::resume_tracking_with_selection(api_client, logger, force).await?;
                     }
                 }
-                TimeAction::Total { project } => {
+                TimeAction::ResumeLast { description, force } => {
+                    commands::resume_last(api_client, logger, confirm, description, force).await?;
+                }
+                TimeAction::Status { project, auto_stop } => {
                     if let Some(project_slug) = project {
-                        commands::show_total(&api_client, &logger, &project_slug).await?;
+                        commands::show_status(api_client, logger, confirm, &project_slug, auto_stop).await?;
                     } else {
-                        commands::show_total_with_selection(&api_client, &logger).await?;
+                        commands::show_status_with_selection(api_client, logger, confirm, auto_stop).await?;
                     }
                 }
-                TimeAction::Edit { project } => {
+                TimeAction::Current => {
+                    commands::show_current(api_client, logger).await?;
+                }
+                TimeAction::Split { project, at, description_first, description_second } => {
+                    commands::split_session(api_client, logger, confirm, &project, &at, description_first, description_second).await?;
+                }
+                TimeAction::Merge { project, at } => {
+                    commands::merge_sessions(api_client, logger, confirm, &project, &at).await?;
+                }
+                TimeAction::Today { date } => {
+                    commands::show_day(api_client, logger, date.as_deref()).await?;
+                }
+                TimeAction::Timeline { project, date } => {
+                    commands::show_timeline(api_client, logger, confirm, &project, date.as_deref()).await?;
+                }
+                TimeAction::Undo => {
+                    commands::undo_last(api_client, logger).await?;
+                }
+                TimeAction::List { project, pairs, fix_missing, from, to } => {
+                    let options = ListOptions { pairs, fix_missing, from, to };
                     if let Some(project_slug) = project {
-                        commands::edit_time_entry(&api_client, &logger, &project_slug).await?;
+                        commands::list_times(api_client, logger, &project_slug, &options).await?;
+                    } else {
+                        commands::list_times_with_selection(api_client, logger, &options).await?;
+                    }
+                }
+                TimeAction::Total {
+                    project,
+                    all,
+                    json_lines,
+                    exclude_running,
+                    include_running: _,
+                    assume_running_closed_at,
+                    working_day_hours,
+                    max_session_hours,
+                    max_unmatched,
+                    round,
+                    round_mode,
+                    rate,
+                    currency,
+                    tag,
+                    from,
+                    to,
+                    decimal,
+                } => {
+                    let options = TotalOptions {
+                        json_lines,
+                        running_policy: RunningPolicy::from_flags(exclude_running, assume_running_closed_at),
+                        working_day_hours,
+                        max_session_hours,
+                        max_unmatched,
+                        rounding: rounding(round, round_mode),
+                        rate,
+                        currency: currency.map(|currency| currency.to_uppercase()),
+                        tag,
+                        from,
+                        to,
+                        duration_style: if decimal { DurationStyle::Decimal } else { DurationStyle::Full },
+                    };
+                    if all {
+                        commands::show_total_all(api_client, logger, &options).await?;
+                    } else if let Some(project_slug) = project {
+                        commands::show_total(api_client, logger, &project_slug, &options).await?;
                     } else {
-                        commands::edit_time_entry_with_selection(&api_client, &logger).await?;
+                        commands::show_total_with_selection(api_client, logger, &options).await?;
                     }
                 }
-                TimeAction::Delete { project, timestamp, all } => {
+                TimeAction::Report { project, from, to, matrix, csv, format, group_by, round, round_mode, decimal, exclude_running, assume_running_closed_at } => {
+                    let format = if csv { ReportFormat::Csv } else { format };
+                    if matrix && format == ReportFormat::Markdown {
+                        return Err(anyhow!("--matrix has no markdown output, use --group-by instead"));
+                    }
+                    if output::json() {
+                        return Err(anyhow!("time report has no JSON output, use --format csv or 'time total --json' instead"));
+                    }
+                    let options = ReportOptions {
+                        from, to, matrix,
+                        csv: format == ReportFormat::Csv,
+                        markdown: format == ReportFormat::Markdown,
+                        group_by, rounding: rounding(round, round_mode), decimal,
+                        running_policy: RunningPolicy::from_flags(exclude_running, assume_running_closed_at),
+                    };
+                    commands::show_report(api_client, logger, project.as_deref(), &options).await?;
+                }
+                TimeAction::Add { project, start, end, description, allow_overlap } => {
+                    commands::add_manual_entry(api_client, logger, &project, &start, &end, description, allow_overlap).await?;
+                }
+                TimeAction::ImportEntries { project, file, format } => {
+                    commands::import_entries(api_client, logger, confirm, &project, &file, format).await?;
+                }
+                TimeAction::ImportToggl { file } => {
+                    commands::import_toggl(api_client, logger, &file).await?;
+                }
+                TimeAction::Note { project, text, force } => {
+                    commands::add_note(api_client, logger, confirm, &project, &text, force).await?;
+                }
+                TimeAction::Fix { project } => {
+                    commands::fix_sessions(api_client, logger, &project).await?;
+                }
+                TimeAction::Edit { project, timestamp, description, clear } => {
+                    if let (Some(project_slug), Some(timestamp)) = (&project, timestamp) {
+                        if description.is_none() && !clear {
+                            return Err(anyhow!("--timestamp needs --description or --clear"));
+                        }
+                        commands::set_time_entry_description(api_client, logger, project_slug, timestamp, description).await?;
+                    } else if let Some(project_slug) = project {
+                        commands::edit_time_entry(api_client, logger, &project_slug).await?;
+                    } else {
+                        commands::edit_time_entry_with_selection(api_client, logger).await?;
+                    }
+                }
+                TimeAction::Delete { project, timestamp, all, older_than, from, to, force, dry_run } => {
+                    let options = DeleteOptions { timestamp, all, older_than, from, to, force, dry_run };
                     if let Some(project_slug) = project {
-                        commands::delete_times(&api_client, &logger, &project_slug, timestamp, all).await?;
+                        commands::delete_times(api_client, logger, confirm, &project_slug, &options).await?;
                     } else {
-                        commands::delete_times_with_selection(&api_client, &logger, timestamp, all).await?;
+                        commands::delete_times_with_selection(api_client, logger, confirm, &options).await?;
                     }
                 }
             }
         }
-        Commands::Export { output_dir, filename_template } => {
-            commands::export_data(&api_client, &logger, &output_dir, &filename_template).await?;
+        Commands::Export { output_dir, filename_template, summary_only, since, format, exclude_running, assume_running_closed_at } => {
+            if summary_only && format == ExportFormat::Ical {
+                return Err(anyhow!("--summary-only can't be exported as a calendar"));
+            }
+            let options = ExportOptions {
+                output_dir, filename_template, summary_only, since, format,
+                running_policy: RunningPolicy::from_flags(exclude_running, assume_running_closed_at),
+            };
+            commands::export_data(api_client, logger, &options).await?;
+        }
+        Commands::Sync => {
+            commands::sync_queue(api_client, logger).await?;
+        }
+        Commands::Completions { shell } => {
+            print_completions(shell);
+        }
+        Commands::CompleteProjects => {
+            commands::complete_projects(api_client).await;
+        }
+        Commands::Run { .. } => {
+            return Err(anyhow!("'run' cannot be used inside a script"));
         }
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Log the interrupt, point out sessions this run left running and exit with the usual SIGINT status
+async fn handle_interrupt(logger: &Logger) {
+    // The stopwatch catches Ctrl-C itself to record the stop
+    if commands::watching() {
+        return;
+    }
+    eprintln!();
+    eprintln!("{} Interrupted", icons::warning());
+    let _ = logger.log("Interrupted").await;
+    for slug in commands::started_this_run() {
+        eprintln!("   {} '{}' was started in this run and keeps running. Stop it with: timetracker time stop {} -d \"...\"",
+                  icons::hint(), slug, slug);
+    }
+    std::process::exit(130);
+}
+
+fn print_completions(shell: Shell) {
+    clap_complete::generate(shell, &mut Cli::command(), "timetracker", &mut std::io::stdout());
+    // Fish can complete project slugs straight from the API
+    if shell == Shell::Fish {
+        println!("complete -c timetracker -n '__fish_seen_subcommand_from start stop switch pause resume status list total report add note fix edit delete info touch' -f -a '(timetracker __complete-projects 2>/dev/null)'");
+    }
+}
+
+/// The entry a time command would write, for commands that can be recorded without the API.
+/// Backdated starts and commands that need a project selection or a lookup are not queued.
+fn offline_entry(command: &Commands, timestamp: i64) -> Option<(String, TimeEntry)> {
+    let Commands::Time { action } = command else {
+        return None;
+    };
+    let (project, entry_type, description, tags) = match action {
+        TimeAction::Start { project: Some(project), description, ago: None, at: None, resume_if_stopped_within: None, tags, .. } => {
+            (project, "start", description.clone(), tags.clone())
+        }
+        // Without a description the notes can't be offered while offline, so those go through the API path
+        TimeAction::Stop { project: Some(project), description: Some(description), all_running: false, tags, .. } => {
+            (project, "end", Some(description.clone()), tags.clone())
+        }
+        TimeAction::Pause { project: Some(project), description, .. } => (project, "pause", description.clone(), Vec::new()),
+        TimeAction::Resume { project: Some(project), .. } => (project, "resume", None, Vec::new()),
+        TimeAction::Note { project, text, .. } => (project, "note", Some(text.clone()), Vec::new()),
+        _ => return None,
+    };
+    
+    let entry = TimeEntry {
+        timestamp,
+        entry_type: entry_type.to_string(),
+        description,
+        tags,
+    };
+    Some((project.clone(), entry))
+}
+
+/// Billing increments from --round, else `rounding_minutes` in the config; 0 means no rounding
+fn rounding(round: Option<u32>, mode: RoundMode) -> Option<Rounding> {
+    round.or(config::get().rounding_minutes)
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| Rounding { minutes, mode })
+}
+
+/// Fill in the configured default project for time commands given without a slug.
+/// A bare `time stop` is left alone, it stops whichever project is the only one running.
+fn with_default_project(mut command: Commands) -> Commands {
+    if let Commands::Time {
+        action: TimeAction::Start { project, .. }
+            | TimeAction::Pause { project, .. }
+            | TimeAction::Resume { project, .. }
+            | TimeAction::Status { project, .. }
+            | TimeAction::List { project, .. }
+            | TimeAction::Total { project, all: false, .. },
+    } = &mut command
+    {
+        if project.is_none() {
+            *project = config::default_project();
+        }
+    }
+    command
+}
+
+async fn run_script(api_client: &impl KvStore, logger: &Logger, confirm: Confirm, script: &str, keep_going: bool) -> Result<()> {
+    logger.log(&format!("Running script: {}", script)).await?;
+    
+    let content = std::fs::read_to_string(script)?;
+    // The lines share one project list; the commands drop it again whenever they change it
+    let api_client = &ProjectsCache::new(api_client);
+    let mut succeeded = 0;
+    let mut failed = 0;
+    let mut first_failure = None;
+    
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        
+        // Skip blank lines and comments
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let mut args = split_script_line(line);
+        // Allow lines copied from the shell that still start with the binary name
+        if args.first().map(|a| a.as_str()) == Some("timetracker") {
+            args.remove(0);
+        }
+        
+        println!("{}  [{}] timetracker {}", icons::start(), index + 1, line);
+        
+        let result = match Cli::try_parse_from(std::iter::once("timetracker".to_string()).chain(args)) {
+            Ok(cli) if !script_line_globals(&cli).is_empty() => {
+                Err(anyhow!("{} can't be set on a script line, pass it to 'timetracker run' instead",
+                            script_line_globals(&cli).join(", ")))
+            }
+            Ok(Cli { command: Some(command), yes, .. }) => {
+                // Undo must not record its writes into the action of the line before
+                if is_undo(&command) {
+                    journal::end();
+                } else {
+                    journal::begin(line);
+                }
+                // --yes on the line only answers that line's confirmations
+                let confirm = if yes { Confirm::new(true) } else { confirm };
+                run_command(api_client, logger, confirm, with_default_project(command)).await
+            }
+            Ok(_) => Err(anyhow!("No command provided")),
+            Err(e) => Err(anyhow!("{}", e.to_string().trim())),
+        };
+        
+        match result {
+            Ok(_) => succeeded += 1,
+            Err(e) => {
+                first_failure.get_or_insert(exit_code::code_of(&e).unwrap_or(ExitCode::Failure));
+                failed += 1;
+                eprintln!("{} Line {} failed: {}", icons::error(), index + 1, e);
+                logger.log(&format!("Script {} line {} failed: {}", script, index + 1, e)).await?;
+                
+                if !keep_going {
+                    eprintln!("   {} Use --keep-going to continue past errors", icons::hint());
+                    break;
+                }
+            }
+        }
+    }
+    
+    println!();
+    println!("{} Script finished: {} succeeded, {} failed", icons::list(), succeeded, failed);
+    logger.log(&format!("Script {} finished: {} succeeded, {} failed", script, succeeded, failed)).await?;
+    
+    if let Some(code) = first_failure {
+        // The first failed line decides the exit code, like it would on its own
+        return Err(CommandError::new(code, format!("{} command(s) in script failed", failed)).into());
+    }
+    
+    Ok(())
+}
+
+/// Global options given on a script line; they are set up once for the whole run, only --yes works per line
+fn script_line_globals(cli: &Cli) -> Vec<&'static str> {
+    let Cli {
+        markdown_help, config, locale, tz, max_line_width, json, no_emoji, concurrency, no_log, log_file,
+        include_archived, workspace, verbose, yes: _, command: _,
+    } = cli;
+    [
+        ("--markdown-help", *markdown_help),
+        ("--config", config.is_some()),
+        ("--locale", locale.is_some()),
+        ("--tz", tz.is_some()),
+        ("--max-line-width", max_line_width.is_some()),
+        ("--json", *json),
+        ("--no-emoji", *no_emoji),
+        ("--concurrency", concurrency.is_some()),
+        ("--no-log", *no_log),
+        ("--log-file", log_file.is_some()),
+        ("--include-archived", *include_archived),
+        ("--workspace", workspace.is_some()),
+        ("--verbose", *verbose > 0),
+    ]
+    .into_iter()
+    .filter(|(_, given)| *given)
+    .map(|(flag, _)| flag)
+    .collect()
+}
+
+/// `time undo` reverses the journal, so it isn't recorded in it
+fn is_undo(command: &Commands) -> bool {
+    matches!(command, Commands::Time { action: TimeAction::Undo })
+}
+
+/// Split a script line into arguments, honouring single and double quotes
+fn split_script_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some('"') if c == '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            Some(_) => current.push(c),
+            None => match c {
+                '"' | '\'' => {
+                    quote = Some(c);
+                    in_arg = true;
+                }
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                        in_arg = true;
+                    }
+                }
+                c if c.is_whitespace() => {
+                    if in_arg {
+                        args.push(std::mem::take(&mut current));
+                        in_arg = false;
+                    }
+                }
+                c => {
+                    current.push(c);
+                    in_arg = true;
+                }
+            },
+        }
+    }
+    
+    if in_arg {
+        args.push(current);
+    }
+    
+    args
+}
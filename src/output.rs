@@ -0,0 +1,41 @@
+use crate::exit_code::{self, CommandError, ExitCode};
+use crate::icons;
+use anyhow::Result;
+use std::sync::OnceLock;
+
+static JSON: OnceLock<bool> = OnceLock::new();
+
+/// Switch scriptable commands to machine-readable output for this run (`--json`)
+pub fn init(json: bool) {
+    let _ = JSON.set(json);
+}
+
+pub fn json() -> bool {
+    *JSON.get_or_init(|| false)
+}
+
+/// Print a single JSON document to stdout
+pub fn print_json(value: &serde_json::Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Report a failed command: on stderr for humans, as the error message in JSON mode; the run ends non-zero either way
+pub fn failure(message: String) -> anyhow::Error {
+    failure_with(ExitCode::Failure, message)
+}
+
+/// Like `failure`, with the exit code of the error that caused it (e.g. 4 when the API was unreachable)
+pub fn failure_from(error: &anyhow::Error, message: String) -> anyhow::Error {
+    failure_with(exit_code::code_of(error).unwrap_or(ExitCode::Failure), message)
+}
+
+/// Like `failure`, ending the run with a specific exit code
+pub fn failure_with(code: ExitCode, message: String) -> anyhow::Error {
+    if json() {
+        CommandError::new(code, message).into()
+    } else {
+        eprintln!("{} {}", icons::error(), message);
+        CommandError::reported(code).into()
+    }
+}
@@ -0,0 +1,64 @@
+use crate::api::TimeEntry;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A time entry recorded while the API was unreachable, one JSON object per line
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueuedEntry {
+    pub project: String,
+    pub entry: TimeEntry,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Local JSONL file that collects time entries until `timetracker sync` replays them
+pub struct OfflineQueue {
+    path: PathBuf,
+}
+
+impl OfflineQueue {
+    /// The queue configured with OFFLINE_QUEUE; offline mode is off without it
+    pub fn from_env() -> Option<Self> {
+        env::var("OFFLINE_QUEUE").ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(|path| Self { path: PathBuf::from(path) })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn push(&self, project: &str, entry: TimeEntry) -> Result<()> {
+        let queued = QueuedEntry { project: project.to_string(), entry, done: false };
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&queued)?)?;
+        Ok(())
+    }
+
+    /// All queued entries in the order they were recorded; a missing file is an empty queue
+    pub fn load(&self) -> Result<Vec<QueuedEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        fs::read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    pub fn save(&self, entries: &[QueuedEntry]) -> Result<()> {
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
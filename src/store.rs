@@ -0,0 +1,926 @@
+use crate::api::{KeyValueData, Project, TimeEntry};
+use crate::exit_code::{CommandError, ExitCode};
+use crate::journal;
+use crate::tz;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// How often a read-modify-write is repeated when another client keeps writing in between
+const MAX_CONFLICT_RETRIES: usize = 5;
+
+/// Requests kept in flight when fetching several keys, unless --concurrency says otherwise
+const DEFAULT_CONCURRENCY: usize = 8;
+
+static CONCURRENCY: OnceLock<usize> = OnceLock::new();
+
+/// Limit parallel requests of cross-project commands (`--concurrency`); 0 falls back to the default
+pub fn init_concurrency(concurrency: Option<usize>) {
+    let _ = CONCURRENCY.set(concurrency.filter(|n| *n > 0).unwrap_or(DEFAULT_CONCURRENCY));
+}
+
+pub fn concurrency() -> usize {
+    *CONCURRENCY.get_or_init(|| DEFAULT_CONCURRENCY)
+}
+
+static INCLUDE_ARCHIVED: OnceLock<bool> = OnceLock::new();
+
+/// Show archived projects in lists and selection menus for this run (`--include-archived`)
+pub fn init_include_archived(include: bool) {
+    let _ = INCLUDE_ARCHIVED.set(include);
+}
+
+pub fn include_archived() -> bool {
+    *INCLUDE_ARCHIVED.get_or_init(|| false)
+}
+
+/// Clock skew this small is not treated as an entry in the future
+const FUTURE_TOLERANCE_SECONDS: i64 = 5 * 60;
+
+/// Refuse an entry that would land in the future or before the latest existing one, which
+/// breaks the start/end pairing. Backfills that fill a gap on purpose use `add_time_entries`.
+fn check_entry_order(project_slug: &str, entries: &[TimeEntry], entry: &TimeEntry) -> Result<()> {
+    let time = |timestamp: i64| tz::localize(DateTime::from_timestamp(timestamp, 0).unwrap_or_default())
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    
+    if entry.timestamp > Utc::now().timestamp() + FUTURE_TOLERANCE_SECONDS {
+        return Err(anyhow!("The {} entry at {} is in the future (is the clock right?); use --force to record it anyway",
+                           entry.entry_type, time(entry.timestamp)));
+    }
+    if let Some(latest) = entries.iter().map(|e| e.timestamp).max().filter(|latest| entry.timestamp < *latest) {
+        return Err(anyhow!("The {} entry at {} is before the latest entry of '{}' at {}; use --force to record it anyway",
+                           entry.entry_type, time(entry.timestamp), project_slug, time(latest)));
+    }
+    Ok(())
+}
+
+/// The stored value was changed by someone else since it was read
+#[derive(Debug)]
+pub struct VersionConflict;
+
+impl fmt::Display for VersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the value was changed by another client")
+    }
+}
+
+impl std::error::Error for VersionConflict {}
+
+/// Values are normally stored as JSON strings; parse those and keep anything else as it is
+pub fn decode_value(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => {
+            // Try to parse the string as JSON
+            match serde_json::from_str(&s) {
+                Ok(parsed) => parsed,
+                Err(_) => serde_json::Value::String(s) // Return as-is if not valid JSON
+            }
+        }
+        value => value,
+    }
+}
+
+/// The time entries stored under a `projects/<slug>` key as a JSON array. A single entry object (e.g. after
+/// editing the value by hand) is read as a list of one and null as no entries; anything else is reported as corrupt.
+fn time_entries_value(key: &str, value: serde_json::Value) -> Result<serde_json::Value> {
+    let slug = key.strip_prefix("projects/").unwrap_or(key);
+    let kind = match &value {
+        serde_json::Value::Array(_) => return Ok(value),
+        serde_json::Value::Null => return Ok(serde_json::json!([])),
+        serde_json::Value::Object(_) => {
+            eprintln!("Warning: Project '{}' stores a single entry instead of a list, reading it as one entry", slug);
+            return Ok(serde_json::Value::Array(vec![value]));
+        }
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+    };
+    Err(corrupt_entries(slug, &format!("expected array, got {}", kind)))
+}
+
+fn corrupt_entries(slug: &str, problem: &str) -> anyhow::Error {
+    anyhow!("Project '{}' data is corrupt: {}. Back it up with 'timetracker export', repair the value of 'projects/{}' \
+             and check the result with 'timetracker time fix {}'", slug, problem, slug, slug)
+}
+
+/// The entry or project a command asked for doesn't exist; ends the run with exit code 2
+fn not_found(message: String) -> anyhow::Error {
+    CommandError::new(ExitCode::NotFound, message).into()
+}
+
+/// Key-value storage the time tracking data lives in.
+///
+/// Implementations only provide the raw key operations; projects and time entries
+/// are built on top of them so every backend behaves the same way.
+#[allow(async_fn_in_trait)] // Only used inside this binary, no Send bound needed
+pub trait KvStore {
+    /// Fetch a value exactly as stored, without parsing the inner JSON string
+    async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>>;
+
+    async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()>;
+
+    async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()>;
+
+    async fn delete_key(&self, key: &str) -> Result<()>;
+
+    async fn get_all_keys(&self) -> Result<Vec<KeyValueData>>;
+
+    /// Fetch a raw value together with its version (e.g. an ETag), if the backend has one
+    async fn get_key_versioned(&self, key: &str) -> Result<(Option<serde_json::Value>, Option<String>)> {
+        Ok((self.get_key_raw(key).await?, None))
+    }
+
+    /// Update a key only if it is still at `version`, failing with `VersionConflict` otherwise.
+    /// Without a version this is a plain update.
+    async fn update_key_if_match(&self, key: &str, value: serde_json::Value, _version: Option<&str>) -> Result<()> {
+        self.update_key(key, value).await
+    }
+
+    /// Read-modify-write of a single key that starts over when another client wrote in between.
+    /// A missing key starts out as the default value; nothing is written if `change` leaves it as it was.
+    async fn modify_key<V, T>(&self, key: &str, mut change: impl FnMut(&mut V) -> Result<T>) -> Result<T>
+    where
+        V: Serialize + DeserializeOwned + Default,
+    {
+        for _ in 0..MAX_CONFLICT_RETRIES {
+            let (raw, version) = self.get_key_versioned(key).await?;
+            let exists = raw.is_some();
+            let mut value: V = match raw {
+                Some(raw) if key.starts_with("projects/") => serde_json::from_value(time_entries_value(key, decode_value(raw))?)
+                    .map_err(|e| corrupt_entries(&key["projects/".len()..], &e.to_string()))?,
+                Some(raw) => serde_json::from_value(decode_value(raw))?,
+                None => V::default(),
+            };
+            let original = serde_json::to_value(&value)?;
+            
+            let result = change(&mut value)?;
+            let updated = serde_json::to_value(&value)?;
+            if exists && updated == original {
+                return Ok(result);
+            }
+            
+            let write = if exists {
+                self.update_key_if_match(key, updated.clone(), version.as_deref()).await
+            } else {
+                self.set_key(key, updated.clone()).await
+            };
+            match write {
+                Err(e) if e.is::<VersionConflict>() => continue,
+                Err(e) => return Err(e),
+                Ok(_) => {
+                    journal::record(key, exists.then_some(&original), Some(&updated));
+                    return Ok(result);
+                }
+            }
+        }
+        
+        Err(CommandError::new(ExitCode::Conflict, format!("Gave up updating '{}' after {} conflicting writes from other clients", key, MAX_CONFLICT_RETRIES)).into())
+    }
+
+    async fn modify_time_entries<T>(&self, project_slug: &str, change: impl FnMut(&mut Vec<TimeEntry>) -> Result<T>) -> Result<T> {
+        self.modify_key(&format!("projects/{}", project_slug), change).await
+    }
+
+    async fn get_key(&self, key: &str) -> Result<serde_json::Value> {
+        // The API returns values as JSON strings, so we need to parse them
+        match self.get_key_raw(key).await? {
+            Some(value) => Ok(decode_value(value)),
+            // Key doesn't exist, return empty array for lists
+            None => Ok(serde_json::json!([])),
+        }
+    }
+
+    async fn get_projects(&self) -> Result<Vec<Project>> {
+        let projects_value = self.get_key("projects").await?;
+        let projects: Vec<Project> = serde_json::from_value(projects_value)?;
+        Ok(projects)
+    }
+
+    async fn get_project(&self, slug: &str) -> Result<Project> {
+        let projects = self.get_projects().await?;
+        projects
+            .into_iter()
+            .find(|p| p.slug == slug)
+            .ok_or_else(|| anyhow!("Project with slug '{}' not found", slug))
+    }
+
+    async fn add_project(&self, project: Project) -> Result<()> {
+        self.modify_key("projects", |projects: &mut Vec<Project>| {
+            // Check if project already exists
+            if projects.iter().any(|p| p.slug == project.slug) {
+                return Err(anyhow!("Project with slug '{}' already exists", project.slug));
+            }
+            
+            projects.push(project.clone());
+            Ok(())
+        }).await
+    }
+
+    async fn update_project(&self, old_slug: &str, updated_project: Project) -> Result<()> {
+        let projects = self.get_projects().await?;
+        
+        // Find the project to update
+        let project_index = projects.iter().position(|p| p.slug == old_slug)
+            .ok_or_else(|| anyhow!("Project with slug '{}' not found", old_slug))?;
+        
+        // If slug is changing, check if new slug already exists (but ignore the current project)
+        if old_slug != updated_project.slug {
+            if projects.iter().enumerate().any(|(i, p)| i != project_index && p.slug == updated_project.slug) {
+                return Err(anyhow!("Project with slug '{}' already exists", updated_project.slug));
+            }
+            
+            // If slug is changing, we need to move the time entries to the new key
+            let old_time_key = format!("projects/{}", old_slug);
+            let new_time_key = format!("projects/{}", updated_project.slug);
+            
+            // Entries that can't be read would be left behind under the old slug, so nothing is renamed then
+            let time_entries = self.get_time_entries(old_slug).await
+                .map_err(|e| anyhow!("Failed to read the time entries of '{}', nothing was renamed: {}", old_slug, e))?;
+            if !time_entries.is_empty() {
+                // Save time entries under new slug
+                let value = serde_json::to_value(time_entries)?;
+                self.set_key(&new_time_key, value.clone()).await
+                    .map_err(|e| anyhow!("Failed to copy the time entries to '{}', nothing was renamed: {}", new_time_key, e))?;
+                
+                // The old entries are only deleted once the copy reads back unchanged
+                let copied = self.get_key_raw(&new_time_key).await.ok().flatten().map(decode_value);
+                if copied.as_ref() != Some(&value) {
+                    return Err(self.discard_copy(&new_time_key, &old_time_key, "the copy could not be read back").await);
+                }
+                
+                // Delete old time entries; a 404 means they are gone already
+                match self.delete_key(&old_time_key).await {
+                    Ok(_) => {}
+                    Err(e) if e.to_string().contains("404") => {}
+                    Err(e) => {
+                        return Err(self.discard_copy(&new_time_key, &old_time_key, &format!("deleting the old entries failed: {}", e)).await);
+                    }
+                }
+                journal::record(&new_time_key, None, Some(&value));
+                journal::record(&old_time_key, Some(&value), None);
+            }
+        }
+        
+        // Update the project in the projects list
+        let slug_changed = old_slug != updated_project.slug;
+        let new_slug = updated_project.slug.clone();
+        self.modify_key("projects", |projects: &mut Vec<Project>| {
+            let project = projects.iter_mut().find(|p| p.slug == old_slug)
+                .ok_or_else(|| anyhow!("Project with slug '{}' not found", old_slug))?;
+            *project = updated_project.clone();
+            Ok(())
+        }).await.map_err(|e| if slug_changed {
+            anyhow!("The time entries were moved to 'projects/{}', but the project list still has '{}': {}. \
+                     Run the same edit again to finish the rename", new_slug, old_slug, e)
+        } else {
+            e
+        })
+    }
+
+    /// Remove the copy of a half-finished slug change so only the original entries remain,
+    /// returning an error that says which keys hold what afterwards
+    async fn discard_copy(&self, copy_key: &str, original_key: &str, reason: &str) -> anyhow::Error {
+        match self.delete_key(copy_key).await {
+            Ok(_) => anyhow!("Renaming failed because {}; the entries are still under '{}' and nothing was changed", reason, original_key),
+            Err(e) => anyhow!("Renaming failed because {}, and removing the copy failed too ({}): the entries are now under both '{}' \
+                               and '{}'. Keep '{}' and delete the key '{}' once the backend works again",
+                              reason, e, original_key, copy_key, original_key, copy_key),
+        }
+    }
+
+    async fn touch_project(&self, slug: &str, timestamp: i64) -> Result<()> {
+        self.modify_key("projects", |projects: &mut Vec<Project>| {
+            // Only the metadata marker changes, time entries are left untouched
+            let project = projects.iter_mut().find(|p| p.slug == slug)
+                .ok_or_else(|| anyhow!("Project with slug '{}' not found", slug))?;
+            project.last_touched = Some(timestamp);
+            Ok(())
+        }).await
+    }
+
+    async fn get_time_entries(&self, project_slug: &str) -> Result<Vec<TimeEntry>> {
+        let key = format!("projects/{}", project_slug);
+        let value = time_entries_value(&key, self.get_key(&key).await?)?;
+        serde_json::from_value(value).map_err(|e| corrupt_entries(project_slug, &e.to_string()))
+    }
+
+    /// Time entries of several projects in the same order, with up to `concurrency()` requests in flight
+    async fn get_time_entries_of(&self, projects: &[Project]) -> Vec<Result<Vec<TimeEntry>>> {
+        stream::iter(projects)
+            .map(|project| self.get_time_entries(&project.slug))
+            .buffered(concurrency())
+            .collect()
+            .await
+    }
+
+    /// Append one entry; it has to be the project's latest and not in the future unless `force` (`--force`)
+    async fn add_time_entry(&self, project_slug: &str, entry: TimeEntry, force: bool) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            if !force {
+                check_entry_order(project_slug, entries, &entry)?;
+            }
+            entries.push(entry.clone());
+            Ok(())
+        }).await
+    }
+
+    /// Append several entries with a single write, e.g. a backfilled start/end pair; unlike
+    /// `add_time_entry` they may lie before existing entries
+    async fn add_time_entries(&self, project_slug: &str, new_entries: Vec<TimeEntry>) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            entries.extend(new_entries.iter().cloned());
+            Ok(())
+        }).await
+    }
+
+    async fn delete_project(&self, project_slug: &str) -> Result<()> {
+        let projects = self.get_projects().await?;
+        
+        if !projects.iter().any(|p| p.slug == project_slug) {
+            return Err(not_found(format!("Project with slug '{}' not found", project_slug)));
+        }
+        
+        // First, delete the time entries for this project
+        let time_key = format!("projects/{}", project_slug);
+        let time_entries = self.get_key_raw(&time_key).await?.map(decode_value);
+        match self.delete_key(&time_key).await {
+            Ok(_) => journal::record(&time_key, time_entries.as_ref(), None),
+            // Only fail if it's not a 404 (key doesn't exist)
+            Err(e) if !e.to_string().contains("404") => {
+                return Err(anyhow!("Failed to delete time entries for project '{}': {}", project_slug, e));
+            }
+            // If 404, it just means no time entries exist, which is fine
+            Err(_) => {}
+        }
+        
+        // Then update the projects list
+        self.modify_key("projects", |projects: &mut Vec<Project>| {
+            projects.retain(|p| p.slug != project_slug);
+            Ok(())
+        }).await
+    }
+
+    async fn delete_project_times(&self, project_slug: &str) -> Result<()> {
+        let key = format!("projects/{}", project_slug);
+        let time_entries = self.get_key_raw(&key).await?.map(decode_value);
+        self.delete_key(&key).await?;
+        journal::record(&key, time_entries.as_ref(), None);
+        Ok(())
+    }
+
+    async fn delete_time_entry_by_timestamp(&self, project_slug: &str, timestamp: i64) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            // Find and remove the entry with the specified timestamp
+            let original_len = entries.len();
+            entries.retain(|entry| entry.timestamp != timestamp);
+            
+            if entries.len() == original_len {
+                return Err(not_found(format!("Time entry with timestamp {} not found for project '{}'", timestamp, project_slug)));
+            }
+            Ok(())
+        }).await
+    }
+
+    async fn delete_time_entries_before(&self, project_slug: &str, cutoff: i64) -> Result<usize> {
+        // The remaining entries are written back in a single update
+        self.modify_time_entries(project_slug, |entries| {
+            // Keep everything at or after the cutoff
+            let original_len = entries.len();
+            entries.retain(|entry| entry.timestamp >= cutoff);
+            Ok(original_len - entries.len())
+        }).await
+    }
+
+    /// Delete all entries with `from <= timestamp <= to`
+    async fn delete_time_entries_in_range(&self, project_slug: &str, from: i64, to: i64) -> Result<usize> {
+        self.modify_time_entries(project_slug, |entries| {
+            let original_len = entries.len();
+            entries.retain(|entry| entry.timestamp < from || entry.timestamp > to);
+            Ok(original_len - entries.len())
+        }).await
+    }
+
+    async fn resume_session(&self, project_slug: &str, end_timestamp: i64) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            let index = entries.iter()
+                .position(|entry| entry.timestamp == end_timestamp && entry.entry_type == "end")
+                .ok_or_else(|| not_found(format!("End entry with timestamp {} not found for project '{}'", end_timestamp, project_slug)))?;
+            
+            // Keep what was written on stop as a note, otherwise drop the end entry
+            if entries[index].description.is_some() {
+                entries[index].entry_type = "note".to_string();
+            } else {
+                entries.remove(index);
+            }
+            Ok(())
+        }).await
+    }
+
+    async fn update_time_entry_descriptions(&self, project_slug: &str, descriptions: &HashMap<i64, String>) -> Result<()> {
+        // All descriptions are written back in a single update
+        self.modify_time_entries(project_slug, |entries| {
+            for entry in entries.iter_mut() {
+                if let Some(description) = descriptions.get(&entry.timestamp) {
+                    entry.description = Some(description.clone());
+                }
+            }
+            Ok(())
+        }).await
+    }
+
+    /// Move an entry to a new time. Start and end entries can't pass their neighbours,
+    /// so a start never moves past its paired end and vice versa.
+    async fn update_time_entry_timestamp(&self, project_slug: &str, timestamp: i64, new_timestamp: i64) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            let index = entries.iter().position(|entry| entry.timestamp == timestamp)
+                .ok_or_else(|| not_found(format!("Time entry with timestamp {} not found for project '{}'", timestamp, project_slug)))?;
+            
+            // Timestamps identify entries, so they have to stay unique
+            if new_timestamp != timestamp && entries.iter().any(|entry| entry.timestamp == new_timestamp) {
+                return Err(anyhow!("Another entry already exists at timestamp {}", new_timestamp));
+            }
+            
+            if matches!(entries[index].entry_type.as_str(), "start" | "end") {
+                let boundaries = entries.iter()
+                    .filter(|entry| entry.timestamp != timestamp && matches!(entry.entry_type.as_str(), "start" | "end"));
+                let previous = boundaries.clone().filter(|entry| entry.timestamp < timestamp).max_by_key(|entry| entry.timestamp);
+                let next = boundaries.filter(|entry| entry.timestamp > timestamp).min_by_key(|entry| entry.timestamp);
+                
+                if let Some(previous) = previous.filter(|previous| new_timestamp <= previous.timestamp) {
+                    return Err(anyhow!("The {} can't be moved before the preceding {} at timestamp {}", entries[index].entry_type, previous.entry_type, previous.timestamp));
+                }
+                if let Some(next) = next.filter(|next| new_timestamp >= next.timestamp) {
+                    return Err(anyhow!("The {} can't be moved past the following {} at timestamp {}", entries[index].entry_type, next.entry_type, next.timestamp));
+                }
+            }
+            
+            entries[index].timestamp = new_timestamp;
+            Ok(())
+        }).await
+    }
+
+    async fn update_time_entry_by_timestamp(&self, project_slug: &str, timestamp: i64, new_description: Option<String>) -> Result<()> {
+        self.modify_time_entries(project_slug, |entries| {
+            // Find the entry with the specified timestamp and update its description
+            let entry = entries.iter_mut().find(|entry| entry.timestamp == timestamp)
+                .ok_or_else(|| not_found(format!("Time entry with timestamp {} not found for project '{}'", timestamp, project_slug)))?;
+            entry.description = new_description.clone();
+            Ok(())
+        }).await
+    }
+}
+
+/// In-memory backend for trying out commands without a server.
+///
+/// Values are stored as JSON strings just like the REST API does.
+#[derive(Default)]
+pub struct MemoryStore {
+    data: Mutex<BTreeMap<String, serde_json::Value>>,
+}
+
+impl KvStore for MemoryStore {
+    async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>> {
+        let data = self.data.lock().map_err(|_| anyhow!("Memory store is poisoned"))?;
+        Ok(data.get(key).cloned())
+    }
+
+    async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        let value_string = serde_json::to_string(&value)?;
+        let mut data = self.data.lock().map_err(|_| anyhow!("Memory store is poisoned"))?;
+        data.insert(key.to_string(), serde_json::Value::String(value_string));
+        Ok(())
+    }
+
+    async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        let value_string = serde_json::to_string(&value)?;
+        let mut data = self.data.lock().map_err(|_| anyhow!("Memory store is poisoned"))?;
+        match data.get_mut(key) {
+            Some(stored) => {
+                *stored = serde_json::Value::String(value_string);
+                Ok(())
+            }
+            // Mirror the REST API, which can't update a key that doesn't exist
+            None => Err(anyhow!("Failed to update key: 404 Not Found")),
+        }
+    }
+
+    async fn delete_key(&self, key: &str) -> Result<()> {
+        let mut data = self.data.lock().map_err(|_| anyhow!("Memory store is poisoned"))?;
+        match data.remove(key) {
+            Some(_) => Ok(()),
+            None => Err(anyhow!("Failed to delete key: 404 Not Found")),
+        }
+    }
+
+    async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
+        let data = self.data.lock().map_err(|_| anyhow!("Memory store is poisoned"))?;
+        Ok(data
+            .iter()
+            .map(|(key, value)| KeyValueData {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect())
+    }
+}
+
+/// Backend keeping every key in its own JSON file, for using the tool without a server.
+///
+/// Files hold the parsed value so they stay readable; the key is URL-encoded into the file name.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Store under API_DATA_DIR, else `.timetracker_data`, creating the directory if needed
+    /// Keys of a workspace live in a subdirectory of the same name
+    pub fn from_env(workspace: Option<&str>) -> Result<Self> {
+        let dir = env::var("API_DATA_DIR").ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .unwrap_or_else(|| ".timetracker_data".to_string());
+        let mut dir = PathBuf::from(dir);
+        if let Some(workspace) = workspace {
+            dir.push(workspace);
+        }
+        fs::create_dir_all(&dir)
+            .map_err(|e| anyhow!("Failed to create data directory {}: {}", dir.display(), e))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", urlencoding::encode(key)))
+    }
+
+    /// Write through a temporary file so an interrupted write never leaves half a value behind
+    fn write(&self, key: &str, value: &serde_json::Value) -> Result<()> {
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&decode_value(value.clone()))?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+impl KvStore for FileStore {
+    async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let value = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(Some(value))
+    }
+
+    async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        self.write(key, &value)
+    }
+
+    async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        // Same as the REST API, which can't update a key that doesn't exist
+        if !self.path(key).exists() {
+            return Err(anyhow!("Failed to update key: 404 Not Found"));
+        }
+        self.write(key, &value)
+    }
+
+    async fn delete_key(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(anyhow!("Failed to delete key: 404 Not Found")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
+        let mut keys = Vec::new();
+        for file in fs::read_dir(&self.dir)? {
+            let file_name = file?.file_name();
+            let Some(encoded) = file_name.to_str().and_then(|name| name.strip_suffix(".json")) else {
+                continue;
+            };
+            let key = urlencoding::decode(encoded)?.into_owned();
+            if let Some(value) = self.get_key_raw(&key).await? {
+                keys.push(KeyValueData { key, value });
+            }
+        }
+        keys.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(keys)
+    }
+}
+
+/// Keeps the project list between the lines of a `run` script, so not every command fetches it again.
+///
+/// Only plain reads use the copy; changes to the list read it from the backend, and any write to it
+/// (a failed one too) drops the copy.
+pub struct ProjectsCache<'a, S> {
+    inner: &'a S,
+    projects: Mutex<Option<Option<serde_json::Value>>>,
+}
+
+impl<'a, S: KvStore> ProjectsCache<'a, S> {
+    pub fn new(inner: &'a S) -> Self {
+        Self { inner, projects: Mutex::new(None) }
+    }
+
+    fn forget(&self, key: &str) -> Result<()> {
+        if key == "projects" {
+            *self.projects.lock().map_err(|_| anyhow!("Projects cache is poisoned"))? = None;
+        }
+        Ok(())
+    }
+}
+
+impl<S: KvStore> KvStore for ProjectsCache<'_, S> {
+    async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>> {
+        if key != "projects" {
+            return self.inner.get_key_raw(key).await;
+        }
+        let cached = self.projects.lock().map_err(|_| anyhow!("Projects cache is poisoned"))?.clone();
+        match cached {
+            Some(cached) => Ok(cached),
+            None => Ok(self.get_key_versioned(key).await?.0),
+        }
+    }
+
+    /// Always read from the backend, as this is the read of a read-modify-write
+    async fn get_key_versioned(&self, key: &str) -> Result<(Option<serde_json::Value>, Option<String>)> {
+        let fetched = self.inner.get_key_versioned(key).await?;
+        if key == "projects" {
+            *self.projects.lock().map_err(|_| anyhow!("Projects cache is poisoned"))? = Some(fetched.0.clone());
+        }
+        Ok(fetched)
+    }
+
+    async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        self.forget(key)?;
+        self.inner.set_key(key, value).await
+    }
+
+    async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+        self.forget(key)?;
+        self.inner.update_key(key, value).await
+    }
+
+    async fn update_key_if_match(&self, key: &str, value: serde_json::Value, version: Option<&str>) -> Result<()> {
+        self.forget(key)?;
+        self.inner.update_key_if_match(key, value, version).await
+    }
+
+    async fn delete_key(&self, key: &str) -> Result<()> {
+        self.forget(key)?;
+        self.inner.delete_key(key).await
+    }
+
+    async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
+        self.inner.get_all_keys().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn project(slug: &str) -> Project {
+        Project {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            description: String::new(),
+            last_touched: None,
+            rate: None,
+            currency: None,
+            budget_hours: None,
+            archived: false,
+        }
+    }
+    
+    fn entry(timestamp: i64, entry_type: &str) -> TimeEntry {
+        TimeEntry { timestamp, entry_type: entry_type.to_string(), description: None, tags: Vec::new() }
+    }
+    
+    #[tokio::test]
+    async fn memory_store_keeps_projects_and_entries() {
+        let store = MemoryStore::default();
+        store.add_project(project("demo")).await.unwrap();
+        assert!(store.add_project(project("demo")).await.is_err());
+        assert_eq!(store.get_project("demo").await.unwrap().slug, "demo");
+        
+        store.add_time_entry("demo", entry(1_000, "start"), false).await.unwrap();
+        store.add_time_entry("demo", entry(1_600, "end"), false).await.unwrap();
+        // Stored as a JSON string, like the REST API does
+        assert!(store.get_key_raw("projects/demo").await.unwrap().is_some_and(|value| value.is_string()));
+        
+        let entries = store.get_time_entries("demo").await.unwrap();
+        let types: Vec<(i64, &str)> = entries.iter().map(|e| (e.timestamp, e.entry_type.as_str())).collect();
+        assert_eq!(types, vec![(1_000, "start"), (1_600, "end")]);
+        assert!(store.get_time_entries("other").await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn memory_store_edits_entries() {
+        let store = MemoryStore::default();
+        store.add_project(project("demo")).await.unwrap();
+        store.add_time_entries("demo", vec![entry(1_000, "start"), entry(1_600, "end"), entry(2_000, "start")]).await.unwrap();
+        
+        store.update_time_entry_by_timestamp("demo", 1_600, Some("Review".to_string())).await.unwrap();
+        store.update_time_entry_timestamp("demo", 1_600, 1_800).await.unwrap();
+        // An end can't pass the start that follows it
+        assert!(store.update_time_entry_timestamp("demo", 1_800, 2_100).await.is_err());
+        
+        let entries = store.get_time_entries("demo").await.unwrap();
+        let end = entries.iter().find(|e| e.entry_type == "end").unwrap();
+        assert_eq!((end.timestamp, end.description.as_deref()), (1_800, Some("Review")));
+        
+        store.delete_time_entry_by_timestamp("demo", 2_000).await.unwrap();
+        assert!(store.delete_time_entry_by_timestamp("demo", 2_000).await.is_err());
+        assert_eq!(store.delete_time_entries_before("demo", 1_500).await.unwrap(), 1);
+        assert_eq!(store.get_time_entries("demo").await.unwrap().len(), 1);
+    }
+    
+    /// Memory store where another client writes in between the first `conflicts` updates
+    /// and deleting `undeletable` fails
+    #[derive(Default)]
+    struct FlakyStore {
+        inner: MemoryStore,
+        conflicts: Mutex<usize>,
+        undeletable: Option<String>,
+    }
+    
+    impl KvStore for FlakyStore {
+        async fn get_key_raw(&self, key: &str) -> Result<Option<serde_json::Value>> {
+            self.inner.get_key_raw(key).await
+        }
+        
+        async fn set_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+            self.inner.set_key(key, value).await
+        }
+        
+        async fn update_key(&self, key: &str, value: serde_json::Value) -> Result<()> {
+            self.update_key_if_match(key, value, None).await
+        }
+        
+        async fn update_key_if_match(&self, key: &str, value: serde_json::Value, _version: Option<&str>) -> Result<()> {
+            let conflict = {
+                let mut conflicts = self.conflicts.lock().unwrap();
+                let conflict = *conflicts > 0;
+                *conflicts = conflicts.saturating_sub(1);
+                conflict
+            };
+            if conflict {
+                self.inner.add_time_entry("demo", entry(1_200, "note"), false).await?;
+                return Err(VersionConflict.into());
+            }
+            self.inner.update_key(key, value).await
+        }
+        
+        async fn delete_key(&self, key: &str) -> Result<()> {
+            if self.undeletable.as_deref() == Some(key) {
+                return Err(anyhow!("Failed to delete key: 500 Internal Server Error"));
+            }
+            self.inner.delete_key(key).await
+        }
+        
+        async fn get_all_keys(&self) -> Result<Vec<KeyValueData>> {
+            self.inner.get_all_keys().await
+        }
+    }
+    
+    #[tokio::test]
+    async fn entries_out_of_order_need_force() {
+        let store = MemoryStore::default();
+        store.add_time_entry("demo", entry(1_000, "start"), false).await.unwrap();
+        
+        let error = store.add_time_entry("demo", entry(900, "end"), false).await.unwrap_err();
+        assert!(error.to_string().contains("before the latest entry"), "{}", error);
+        let future = Utc::now().timestamp() + 3_600;
+        assert!(store.add_time_entry("demo", entry(future, "end"), false).await.is_err());
+        
+        store.add_time_entry("demo", entry(900, "note"), true).await.unwrap();
+        assert_eq!(store.get_time_entries("demo").await.unwrap().len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn projects_cache_is_dropped_on_writes() {
+        let store = MemoryStore::default();
+        store.add_project(project("demo")).await.unwrap();
+        let cache = ProjectsCache::new(&store);
+        assert_eq!(cache.get_projects().await.unwrap().len(), 1);
+        
+        // Written past the cache, e.g. by another client: the script keeps its copy
+        store.set_key("projects", serde_json::json!([])).await.unwrap();
+        assert_eq!(cache.get_projects().await.unwrap().len(), 1);
+        
+        // A change starts from the stored list, so nothing written past the cache is lost
+        cache.add_project(project("other")).await.unwrap();
+        let slugs: Vec<String> = cache.get_projects().await.unwrap().into_iter().map(|p| p.slug).collect();
+        assert_eq!(slugs, vec!["other"]);
+        assert_eq!(store.get_projects().await.unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn modify_key_starts_over_after_a_conflicting_write() {
+        let store = FlakyStore::default();
+        store.add_time_entry("demo", entry(1_000, "start"), false).await.unwrap();
+        *store.conflicts.lock().unwrap() = 1;
+        
+        let mut attempts = 0;
+        store.modify_time_entries("demo", |entries| {
+            attempts += 1;
+            entries.push(entry(1_600, "end"));
+            Ok(())
+        }).await.unwrap();
+        
+        // The change is applied again on top of the other client's note instead of overwriting it
+        assert_eq!(attempts, 2);
+        let entries = store.get_time_entries("demo").await.unwrap();
+        let types: Vec<&str> = entries.iter().map(|e| e.entry_type.as_str()).collect();
+        assert_eq!(types, vec!["start", "note", "end"]);
+    }
+    
+    #[tokio::test]
+    async fn slug_change_keeps_the_old_entries_when_deleting_them_fails() {
+        let store = FlakyStore { undeletable: Some("projects/old".to_string()), ..Default::default() };
+        store.add_project(project("old")).await.unwrap();
+        store.add_time_entries("old", vec![entry(1_000, "start"), entry(1_600, "end")]).await.unwrap();
+        
+        let error = store.update_project("old", project("new")).await.unwrap_err();
+        assert!(error.to_string().contains("nothing was changed"), "{}", error);
+        
+        // The copy is gone again, the entries and the project list are as before
+        assert_eq!(store.get_key_raw("projects/new").await.unwrap(), None);
+        assert_eq!(store.get_time_entries("old").await.unwrap().len(), 2);
+        let slugs: Vec<String> = store.get_projects().await.unwrap().into_iter().map(|p| p.slug).collect();
+        assert_eq!(slugs, vec!["old"]);
+    }
+    
+    #[test]
+    fn decode_value_keeps_arrays() {
+        let array = serde_json::json!([{"timestamp": 1000, "type": "start"}]);
+        assert_eq!(decode_value(array.clone()), array);
+        assert_eq!(decode_value(serde_json::Value::String(array.to_string())), array);
+        assert_eq!(decode_value(serde_json::json!("plain text")), serde_json::json!("plain text"));
+    }
+    
+    #[tokio::test]
+    async fn reads_a_key_stored_as_a_json_array() {
+        // Another client may have written the array itself instead of a JSON string
+        let store = MemoryStore::default();
+        let array = serde_json::json!([{"timestamp": 1000, "type": "start"}, {"timestamp": 1600, "type": "end"}]);
+        store.data.lock().unwrap().insert("projects/demo".to_string(), array.clone());
+        
+        assert_eq!(store.get_key("projects/demo").await.unwrap(), array);
+        assert_eq!(store.get_time_entries("demo").await.unwrap().len(), 2);
+        let exported = store.get_all_keys().await.unwrap().into_iter().map(|data| decode_value(data.value)).next();
+        assert_eq!(exported, Some(array));
+    }
+    
+    /// File store in a fresh temporary directory, removed again by the caller
+    fn file_store(name: &str) -> FileStore {
+        let dir = env::temp_dir().join(format!("timetracker-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        FileStore { dir }
+    }
+    
+    #[tokio::test]
+    async fn file_store_keeps_projects_and_entries_on_disk() {
+        let store = file_store("roundtrip");
+        store.add_project(project("demo")).await.unwrap();
+        store.add_time_entries("demo", vec![entry(1_000, "start"), entry(1_600, "end")]).await.unwrap();
+        
+        // Each key is one readable JSON file, the slash of the key encoded into the name
+        let on_disk: serde_json::Value = serde_json::from_str(&fs::read_to_string(store.dir.join("projects%2Fdemo.json")).unwrap()).unwrap();
+        assert_eq!(on_disk[1]["type"], "end");
+        
+        let entries = store.get_time_entries("demo").await.unwrap();
+        assert_eq!(entries.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![1_000, 1_600]);
+        let keys: Vec<String> = store.get_all_keys().await.unwrap().into_iter().map(|data| data.key).collect();
+        assert_eq!(keys, vec!["projects", "projects/demo"]);
+        
+        let _ = fs::remove_dir_all(&store.dir);
+    }
+    
+    #[tokio::test]
+    async fn file_store_reports_missing_keys_like_the_api() {
+        let store = file_store("missing");
+        assert_eq!(store.get_key_raw("projects").await.unwrap(), None);
+        assert!(store.update_key("projects", serde_json::json!([])).await.is_err());
+        assert!(store.delete_key("projects").await.is_err());
+        
+        store.set_key("projects", serde_json::json!([])).await.unwrap();
+        store.delete_key("projects").await.unwrap();
+        assert!(store.get_all_keys().await.unwrap().is_empty());
+        
+        let _ = fs::remove_dir_all(&store.dir);
+    }
+}
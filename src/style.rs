@@ -0,0 +1,44 @@
+use crate::icons;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Colors are only used when stdout is a terminal and NO_COLOR is not set
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| std::io::stdout().is_terminal() && !icons::no_color())
+}
+
+fn paint(code: &str, text: &str) -> String {
+    if enabled() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn red(text: &str) -> String {
+    paint("31", text)
+}
+
+pub fn green(text: &str) -> String {
+    paint("32", text)
+}
+
+/// Removed text in a diff, marked like `git diff --word-diff` when colors are off
+pub fn removed(text: &str) -> String {
+    if enabled() {
+        red(text)
+    } else {
+        format!("[-{}-]", text)
+    }
+}
+
+/// Added text in a diff, marked like `git diff --word-diff` when colors are off
+pub fn added(text: &str) -> String {
+    if enabled() {
+        green(text)
+    } else {
+        format!("{{+{}+}}", text)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::icons;
+use std::env;
+use std::sync::OnceLock;
+use terminal_size::{terminal_size, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+static MAX_LINE_WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Set the line width for tables: the --max-line-width flag, else the terminal's width,
+/// else $COLUMNS (for output that is piped), else unlimited
+pub fn init(max_line_width: Option<usize>) {
+    let width = max_line_width
+        .or_else(|| terminal_size().map(|(Width(columns), _)| columns as usize))
+        .or_else(|| env::var("COLUMNS").ok().and_then(|columns| columns.trim().parse().ok()));
+    let _ = MAX_LINE_WIDTH.set(width);
+}
+
+fn max_line_width() -> Option<usize> {
+    *MAX_LINE_WIDTH.get_or_init(|| None)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Applied to a whole rendered row, e.g. style::green; padding is measured without it
+type RowStyle = fn(&str) -> String;
+
+/// Fixed-column table that shrinks one column to fit the line width instead of wrapping
+pub struct Table {
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+    styles: Vec<Option<RowStyle>>,
+    truncate_column: Option<usize>,
+}
+
+impl Table {
+    pub fn new(aligns: Vec<Align>) -> Self {
+        Self {
+            aligns,
+            rows: Vec::new(),
+            styles: Vec::new(),
+            truncate_column: None,
+        }
+    }
+
+    /// Column that gets shortened with an ellipsis when the table is too wide
+    pub fn truncate(mut self, column: usize) -> Self {
+        self.truncate_column = Some(column);
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+        self.styles.push(None);
+    }
+
+    /// A row drawn with `style`, e.g. in color, keeping the columns aligned
+    pub fn styled_row(&mut self, cells: Vec<String>, style: RowStyle) {
+        self.rows.push(cells);
+        self.styles.push(Some(style));
+    }
+
+    pub fn print(&self) {
+        for line in self.render() {
+            println!("{}", line);
+        }
+    }
+
+    fn render(&self) -> Vec<String> {
+        const INDENT: &str = "  ";
+        const GAP: &str = "  ";
+
+        let mut widths = vec![0; self.aligns.len()];
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+
+        // Shrink the truncatable column so that every line fits
+        if let (Some(max_width), Some(column)) = (max_line_width(), self.truncate_column) {
+            let fixed: usize = widths.iter().enumerate()
+                .filter(|(i, _)| *i != column)
+                .map(|(_, w)| *w)
+                .sum::<usize>()
+                + INDENT.len()
+                + GAP.len() * widths.len().saturating_sub(1);
+            if let Some(width) = widths.get_mut(column) {
+                // Keep at least a few characters visible even on very narrow terminals
+                *width = (*width).min(max_width.saturating_sub(fixed).max(8));
+            }
+        }
+
+        self.rows
+            .iter()
+            .zip(&self.styles)
+            .map(|(row, style)| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .zip(&self.aligns)
+                    .map(|((cell, width), align)| {
+                        let cell = truncate(cell, *width);
+                        // Padded by display width, as wide characters like CJK or emoji take two columns
+                        let padding = " ".repeat(width.saturating_sub(cell.width()));
+                        match align {
+                            Align::Left => format!("{}{}", cell, padding),
+                            Align::Right => format!("{}{}", padding, cell),
+                        }
+                    })
+                    .collect();
+                let line = cells.join(GAP).trim_end().to_string();
+                match style {
+                    Some(style) => format!("{}{}", INDENT, style(&line)),
+                    None => format!("{}{}", INDENT, line),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Shorten text to at most `max` terminal columns, marking the cut with an ellipsis
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        text.to_string()
+    } else if max == 0 {
+        String::new()
+    } else {
+        let ellipsis = icons::ellipsis();
+        let mut remaining = max.saturating_sub(ellipsis.width());
+        let mut shortened = String::new();
+        for c in text.chars() {
+            let width = c.width().unwrap_or(0);
+            if width > remaining {
+                break;
+            }
+            remaining -= width;
+            shortened.push(c);
+        }
+        shortened.push_str(ellipsis);
+        shortened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_count_as_two_columns() {
+        let shortened = truncate("日本語のテキスト", 7);
+        assert!(shortened.starts_with("日本"), "{}", shortened);
+        assert!(shortened.width() <= 7, "{}", shortened);
+        assert_eq!(truncate("日本", 4), "日本");
+
+        let mut table = Table::new(vec![Align::Left, Align::Right]);
+        table.row(vec!["日本".to_string(), "1h".to_string()]);
+        table.row(vec!["abcd".to_string(), "2h".to_string()]);
+        let lines = table.render();
+        assert_eq!(lines[0].width(), lines[1].width());
+    }
+}
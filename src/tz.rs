@@ -0,0 +1,54 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+static ZONE: OnceLock<Option<Tz>> = OnceLock::new();
+
+/// Pin dates and times to an IANA zone like "Europe/Zurich"; None keeps the system zone
+pub fn init(name: Option<&str>) -> Result<()> {
+    let zone = name
+        .map(|name| name.parse::<Tz>().map_err(|_| anyhow!("Unknown time zone '{}'. Use an IANA name like Europe/Zurich", name)))
+        .transpose()?;
+    let _ = ZONE.set(zone);
+    Ok(())
+}
+
+fn zone() -> Option<Tz> {
+    *ZONE.get_or_init(|| None)
+}
+
+/// A point in time as seen in the configured zone
+pub fn localize(datetime: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match zone() {
+        Some(zone) => datetime.with_timezone(&zone).fixed_offset(),
+        None => datetime.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// Unix timestamp of a wall-clock time in the configured zone (the earlier one when clocks go back)
+pub fn timestamp_of(naive: &NaiveDateTime) -> Option<i64> {
+    match zone() {
+        Some(zone) => zone.from_local_datetime(naive).earliest().map(|dt| dt.timestamp()),
+        None => Local.from_local_datetime(naive).earliest().map(|dt| dt.timestamp()),
+    }
+}
+
+pub fn today() -> NaiveDate {
+    localize(Utc::now()).date_naive()
+}
+
+/// Unix timestamp of a wall-clock time in `zone`, an IANA name like "Europe/Zurich" or an offset like "+01:00"
+pub fn timestamp_in(naive: &NaiveDateTime, zone: &str) -> Result<i64> {
+    let zone = zone.trim();
+    if let Ok(named) = zone.parse::<Tz>() {
+        return named.from_local_datetime(naive).earliest()
+            .map(|dt| dt.timestamp())
+            .ok_or_else(|| anyhow!("{} does not exist in {}", naive, zone));
+    }
+    let offset = DateTime::parse_from_str(&format!("2000-01-01 00:00 {}", zone), "%Y-%m-%d %H:%M %z")
+        .map_err(|_| anyhow!("Unknown time zone '{}'. Use an IANA name like Europe/Zurich or an offset like +01:00", zone))?;
+    offset.offset().from_local_datetime(naive).single()
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| anyhow!("{} does not exist at {}", naive, zone))
+}